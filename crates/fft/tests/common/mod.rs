@@ -0,0 +1,43 @@
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use approx::AbsDiffEq;
+use fft::DFT;
+use floating_point::Complex64;
+use matrix::FullMat;
+
+// signal and spectrum columns of a data file
+fn load_samples(name: &str) -> (Vec<Complex64>, Vec<Complex64>) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "data", name]
+        .iter()
+        .collect();
+    let samples =
+        FullMat::<Complex64>::from_whitespace_reader(BufReader::new(File::open(path).unwrap()))
+            .unwrap();
+    (samples.col(0), samples.col(1))
+}
+
+// Transforms the signal of a data file, compares with its spectrum and
+// transforms back.
+pub fn check_samples<T: DFT>(name: &str) {
+    let (x, expected) = load_samples(name);
+    let y = T::fourier_transform(&x);
+    y.iter().zip(expected).for_each(|(a, b)| {
+        assert!(
+            a.abs_diff_eq(&b, (2_f64.powi(6) * f64::EPSILON).into()),
+            "a = {}, b = {}, diff = {:e}",
+            a,
+            b,
+            *a - b
+        )
+    });
+    let z = T::inverse_fourier_transform(&y);
+    x.into_iter().zip(z).for_each(|(a, b)| {
+        assert!(
+            a.abs_diff_eq(&b, (2_f64.powi(4) * f64::EPSILON).into()),
+            "a = {}, b = {}, diff = {:e}",
+            a,
+            b,
+            a - b
+        )
+    });
+}
@@ -1,5 +1,7 @@
 #![cfg_attr(feature = "nightly_bench", feature(test))]
 
+mod common;
+
 use approx::AbsDiffEq;
 use fft::{NaiveDFT, RecursiveBisectionFFT, DFT};
 use floating_point::Complex64;
@@ -11,7 +13,6 @@ fn test_recursive_bisection_fft_0() {
     let n = 2_usize.pow(16);
     println!("n = {n}");
     let x = (0..n)
-        .into_iter()
         .map(|_| -> Complex64 { Complex64::new(rng.gen(), 0.0) })
         .collect::<Vec<_>>();
     // println!("x data is {:#?}", x);
@@ -19,7 +20,7 @@ fn test_recursive_bisection_fft_0() {
     // println!("y data is {:#?}", y);
     let z = RecursiveBisectionFFT::inverse_fourier_transform(&y);
     // println!("z data is {:#?}", z);
-    x.into_iter().zip(z.into_iter()).for_each(|(a, b)| {
+    x.into_iter().zip(z).for_each(|(a, b)| {
         assert!(
             a.abs_diff_eq(&b, (2_f64.powi(4) * f64::EPSILON).into()),
             "a = {}, b = {}, diff = {}",
@@ -36,7 +37,6 @@ fn test_recursive_bisection_fft_1() {
     let n = 2_usize.pow(11);
     println!("n = {n}");
     let x = (0..n)
-        .into_iter()
         .map(|_| -> Complex64 { Complex64::new(rng.gen(), 0.0) })
         .collect::<Vec<_>>();
     // println!("x data is {:#?}", x);
@@ -44,7 +44,7 @@ fn test_recursive_bisection_fft_1() {
     // println!("y1 data is {:#?}", y1);
     let y2 = RecursiveBisectionFFT::fourier_transform(&x);
     // println!("y2 data is {:#?}", y2);
-    y1.into_iter().zip(y2.into_iter()).for_each(|(a, b)| {
+    y1.into_iter().zip(y2).for_each(|(a, b)| {
         assert!(
             a.abs_diff_eq(&b, (1e4 * f64::EPSILON).into()),
             "a = {}, b = {}, diff = {:e}",
@@ -55,6 +55,11 @@ fn test_recursive_bisection_fft_1() {
     });
 }
 
+#[test]
+fn test_recursive_bisection_fft_samples() {
+    common::check_samples::<RecursiveBisectionFFT>("square_wave_16.txt");
+}
+
 #[cfg(feature = "nightly_bench")]
 mod benchs {
     extern crate test;
@@ -66,7 +71,6 @@ mod benchs {
         let n = 2_usize.pow(10);
         println!("n = {n}");
         (0..n)
-            .into_iter()
             .map(|_| -> Complex64 { Complex64::new(rng.gen(), 0.0) })
             .collect::<Vec<_>>()
     }
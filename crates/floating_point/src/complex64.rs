@@ -39,6 +39,10 @@ impl Complex64 {
         }
     }
 
+    pub fn abs(&self) -> F64 {
+        self.real.hypot(self.imag)
+    }
+
     pub fn omega_n(n: usize) -> Self {
         let angle = core::f64::consts::TAU / n as f64;
         Self::new(angle.cos(), -angle.sin())
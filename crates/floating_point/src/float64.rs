@@ -40,6 +40,8 @@ impl F64 {
 
     pub const ONE: Self = Self(1.0);
 
+    pub const EPSILON: Self = Self(Inner::EPSILON);
+
     pub fn to_f64(&self) -> Inner {
         self.0
     }
@@ -59,6 +61,22 @@ impl F64 {
     pub fn min(&self, rhs: Self) -> Self {
         self.0.min(rhs.0).into()
     }
+
+    pub fn sqrt(&self) -> Self {
+        self.0.sqrt().into()
+    }
+
+    pub fn hypot(&self, rhs: Self) -> Self {
+        self.0.hypot(rhs.0).into()
+    }
+
+    pub fn copysign(&self, sign: Self) -> Self {
+        self.0.copysign(sign.0).into()
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.0.is_finite()
+    }
 }
 
 impl From<Inner> for F64 {
@@ -85,6 +103,16 @@ impl Neg for F64 {
     }
 }
 
+impl core::iter::Sum for F64 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        let mut sum = F64::ZERO;
+        for item in iter {
+            sum += item;
+        }
+        sum
+    }
+}
+
 impl core::fmt::Debug for F64 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         core::fmt::Debug::fmt(&self.0, f)
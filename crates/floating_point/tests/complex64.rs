@@ -94,3 +94,9 @@ fn test_omega_n() {
     assert!((-Complex64::I).abs_diff_eq(&Complex64::omega_n(4), f64::EPSILON.into()));
     assert!((-Complex64::I).abs_diff_eq(&Complex64::omega_n_power(4, 5), f64::EPSILON.into()));
 }
+
+#[test]
+fn test_abs() {
+    assert!(Complex64::new(3.0, -4.0).abs() == 5.0.into());
+    assert!(Complex64::I.abs() == 1.0.into());
+}
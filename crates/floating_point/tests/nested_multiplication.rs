@@ -44,13 +44,7 @@ fn test_round_off_error() {
     println!("nested mul result = {:?}", y_a);
     println!("geometric series result = {:?}", y_b);
     let relative_error = ((y_a - y_b) / y_a).abs();
-    println!(
-        "relative error = {}",
-        format!("{:.2e}", relative_error.to_f64())
-    );
-    println!(
-        "machine epsilon = {}",
-        format!("{:.2e}", 1.0 / 2_f64.powi(52))
-    );
+    println!("relative error = {:.2e}", relative_error.to_f64());
+    println!("machine epsilon = {:.2e}", 1.0 / 2_f64.powi(52));
     assert!(relative_error > 1e-12.into())
 }
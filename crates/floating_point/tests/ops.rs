@@ -65,7 +65,7 @@ fn test_abs() {
 
 #[test]
 fn test_map_vec() {
-    let v0 = vec![F64::from(1.0); 10];
+    let v0 = [F64::from(1.0); 10];
     let v1 = F64::map_vec(vec![1.0; 10]);
     for i in 0..10 {
         assert!(v0[i] == v1[i])
@@ -77,3 +77,16 @@ fn test_min_max() {
     assert!(F64::from(3.0).max(F64::from(4.0)) == F64::from(4.0));
     assert!(F64::from(3.0).min(F64::from(4.0)) == F64::from(3.0));
 }
+
+#[test]
+fn test_sqrt_and_hypot() {
+    assert!(F64::from(16.0).sqrt() == 4.0.into());
+    assert!(F64::from(3.0).hypot(4.0.into()) == 5.0.into());
+    assert!(F64::from(2.0).copysign((-1.0).into()) == (-2.0).into());
+}
+
+#[test]
+fn test_sum() {
+    let s: F64 = F64::map_vec(vec![1., 2., 3.]).into_iter().sum();
+    assert!(s == 6.0.into())
+}
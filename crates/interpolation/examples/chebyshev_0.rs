@@ -18,8 +18,7 @@ pub fn main() {
         .map(|x| F64::from(1.0) / (F64::from(1.0) + F64::from(12.0) * *x * *x))
         .collect::<Vec<_>>();
 
-    let coes =
-        polynomial_interpolation(x_array.clone().into_iter().zip(y_array.clone().into_iter()));
+    let coes = polynomial_interpolation(x_array.clone().into_iter().zip(y_array.clone()));
 
     let base_points = (0..inter_n)
         .map(|i| {
@@ -1,23 +1,36 @@
+use std::{fs::File, io::BufReader};
+
 use floating_point::F64;
 use interpolation::polynomial::{interpolated_polynomial, polynomial_interpolation};
+use matrix::{io::text::TextOptions, FullMat};
 use plotly::{Plot, Scatter};
 
 pub fn main() {
     let mut plot = Plot::new();
 
-    let x_array = F64::map_vec(vec![-1., 0., 2., 3.]);
-    let y_array = F64::map_vec(vec![5., -2., 3., -4.]);
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/data/polynomial_interpolation_plot_0.csv"
+    );
+    let options = TextOptions {
+        skip_rows: 1,
+        ..TextOptions::default()
+    };
+    let points =
+        FullMat::<F64>::from_csv_reader(BufReader::new(File::open(path).unwrap()), &options)
+            .unwrap();
+    let (x_array, y_array) = (points.col(0), points.col(1));
 
-    let coes =
-        polynomial_interpolation(x_array.clone().into_iter().zip(y_array.clone().into_iter()));
-    let base_points = F64::map_vec(vec![-1., 0., 2.]);
+    let coes = polynomial_interpolation(x_array.clone().into_iter().zip(y_array.clone()));
+    // Newton form: all nodes but the last
+    let base_points = &x_array[..x_array.len() - 1];
 
     let plot_x_array = (0..100)
         .map(|i| F64::from(-1.3 + 4.5 / 100.0 * i as f64))
         .collect::<Vec<_>>();
     let plot_y_array = plot_x_array
         .iter()
-        .map(|x| interpolated_polynomial(*x, &coes, &base_points))
+        .map(|x| interpolated_polynomial(*x, &coes, base_points))
         .collect::<Vec<_>>();
 
     let trace = Scatter::new(
@@ -1,20 +1,29 @@
+use std::{fs::File, io::BufReader};
+
 use floating_point::F64;
 use interpolation::polynomial::{interpolated_polynomial, polynomial_interpolation};
+use matrix::{io::text::TextOptions, FullMat};
 use plotly::{Plot, Scatter};
 
 pub fn main() {
     let mut plot = Plot::new();
 
-    let x_array = F64::map_vec(vec![
-        -3., -2.5, -2., -1.5, -1., -0.5, 0.0, 0.5, 1., 1.5, 2., 2.5, 3.,
-    ]);
-    let y_array = F64::map_vec([vec![0.0; 6], vec![1.0], vec![0.0; 6]].concat());
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/data/runge_phenomenon_0.csv"
+    );
+    let options = TextOptions {
+        skip_rows: 1,
+        ..TextOptions::default()
+    };
+    let points =
+        FullMat::<F64>::from_csv_reader(BufReader::new(File::open(path).unwrap()), &options)
+            .unwrap();
+    let (x_array, y_array) = (points.col(0), points.col(1));
 
-    let coes =
-        polynomial_interpolation(x_array.clone().into_iter().zip(y_array.clone().into_iter()));
-    let base_points = F64::map_vec(vec![
-        -3., -2.5, -2., -1.5, -1., -0.5, 0.0, 0.5, 1., 1.5, 2., 2.5,
-    ]);
+    let coes = polynomial_interpolation(x_array.clone().into_iter().zip(y_array.clone()));
+    // Newton form: all nodes but the last
+    let base_points = &x_array[..x_array.len() - 1];
 
     let n = 300;
     let plot_x_array = (0..n)
@@ -22,7 +31,7 @@ pub fn main() {
         .collect::<Vec<_>>();
     let plot_y_array = plot_x_array
         .iter()
-        .map(|x| interpolated_polynomial(*x, &coes, &base_points))
+        .map(|x| interpolated_polynomial(*x, &coes, base_points))
         .collect::<Vec<_>>();
 
     let trace = Scatter::new(
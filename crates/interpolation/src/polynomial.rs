@@ -1,4 +1,5 @@
 use floating_point::F64;
+use matrix::VandermondeMat;
 
 fn add_point(new_point: (F64, F64), points: &mut Vec<(F64, F64)>, states: &mut Vec<F64>) -> F64 {
     let (x, y) = new_point;
@@ -27,7 +28,7 @@ pub fn polynomial_interpolation<T: Iterator<Item = (F64, F64)>>(points: T) -> Ve
     coes
 }
 
-pub fn interpolated_polynomial(x: F64, coes: &Vec<F64>, base_points: &Vec<F64>) -> F64 {
+pub fn interpolated_polynomial(x: F64, coes: &[F64], base_points: &[F64]) -> F64 {
     assert!(coes.len() == base_points.len() + 1);
     let n = coes.len();
     let mut y = coes[n - 1];
@@ -38,11 +39,26 @@ pub fn interpolated_polynomial(x: F64, coes: &Vec<F64>, base_points: &Vec<F64>)
     y
 }
 
+// Coefficients a_0, ..., a_n of the interpolating polynomial in the monomial
+// basis, sum a_i x^i, from the Vandermonde system solved by the
+// Bjorck-Pereyra algorithm. Returns None if two abscissas coincide.
+pub fn monomial_interpolation<T: Iterator<Item = (F64, F64)>>(points: T) -> Option<Vec<F64>> {
+    let (x, y): (Vec<F64>, Vec<F64>) = points.unzip();
+    VandermondeMat::new(x).solve(&y)
+}
+
+pub fn monomial_polynomial(x: F64, coes: &[F64]) -> F64 {
+    coes.iter().rev().fold(F64::ZERO, |y, a| y * x + *a)
+}
+
 #[cfg(test)]
 mod tests {
     use floating_point::F64;
 
-    use super::{add_point, polynomial_interpolation};
+    use super::{
+        add_point, interpolated_polynomial, monomial_interpolation, monomial_polynomial,
+        polynomial_interpolation,
+    };
 
     #[test]
     fn test_add_point_0() {
@@ -84,7 +100,7 @@ mod tests {
     fn test_polynomial_interpolation() {
         let points = F64::map_vec(vec![-1., 0., 2., 3.])
             .into_iter()
-            .zip(F64::map_vec(vec![-5., -1., 1., 11.]).into_iter());
+            .zip(F64::map_vec(vec![-5., -1., 1., 11.]));
         let coes = polynomial_interpolation(points);
 
         assert!(coes[0] == (-5.0).into());
@@ -92,4 +108,25 @@ mod tests {
         assert!(coes[2] == (-1.0).into());
         assert!(coes[3] == 1.0.into());
     }
+
+    #[test]
+    fn test_monomial_interpolation() {
+        let x = F64::map_vec(vec![-1., 0., 2., 3.]);
+        let y = F64::map_vec(vec![-5., -1., 1., 11.]);
+        let coes = monomial_interpolation(x.clone().into_iter().zip(y.clone())).unwrap();
+        // (x + 1) x (x - 2) - (x + 1) x + 4 (x + 1) - 5 = x^3 - 2x^2 + x - 1
+        assert!(coes == F64::map_vec(vec![-1., 1., -2., 1.]));
+
+        let newton = polynomial_interpolation(x.clone().into_iter().zip(y));
+        for t in F64::map_vec(vec![-2., 0.5, 1., 4.]) {
+            let a = monomial_polynomial(t, &coes);
+            let b = interpolated_polynomial(t, &newton, &x[..3]);
+            assert!((a - b).abs() < 1e-12.into());
+        }
+
+        let repeated = F64::map_vec(vec![0., 1., 1.])
+            .into_iter()
+            .zip(vec![F64::ONE; 3]);
+        assert!(monomial_interpolation(repeated).is_none());
+    }
 }
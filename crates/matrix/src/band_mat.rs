@@ -0,0 +1,270 @@
+use core::ops::{Index, IndexMut};
+use std::fmt::Display;
+
+use floating_point::F64;
+
+use crate::{FullMat, LinearOperator, Matrix, RowAccess, TridiagonalMat};
+
+// Matrix with `kl` subdiagonals and `ku` superdiagonals. Row i stores the
+// columns i - kl ..= i + ku contiguously, the positions falling outside the
+// matrix being left unused.
+#[derive(Debug, Clone)]
+pub struct BandMat<T> {
+    row_count: usize,
+    col_count: usize,
+    kl: usize,
+    ku: usize,
+    storage: Vec<T>,
+}
+
+impl<T> BandMat<T> {
+    fn width(&self) -> usize {
+        self.kl + self.ku + 1
+    }
+
+    fn in_band(&self, index: (usize, usize)) -> bool {
+        let (i, j) = index;
+        assert!(i < self.row_count && j < self.col_count);
+        j + self.kl >= i && j <= i + self.ku
+    }
+
+    fn index_in_vec(&self, index: (usize, usize)) -> usize {
+        let (i, j) = index;
+        i * self.width() + (j + self.kl - i)
+    }
+
+    pub fn kl(&self) -> usize {
+        self.kl
+    }
+
+    pub fn ku(&self) -> usize {
+        self.ku
+    }
+
+    // first and one past the last column of the band in row i
+    fn row_range(&self, i: usize) -> std::ops::Range<usize> {
+        i.saturating_sub(self.kl)..(i + self.ku + 1).min(self.col_count)
+    }
+}
+
+impl BandMat<F64> {
+    pub fn zeros(row_count: usize, col_count: usize, kl: usize, ku: usize) -> Self {
+        assert!(row_count > 0 && col_count > 0);
+        Self {
+            row_count,
+            col_count,
+            kl,
+            ku,
+            storage: vec![F64::ZERO; row_count * (kl + ku + 1)],
+        }
+    }
+
+    // panics if A has nonzero entries outside the band
+    pub fn from_full(mat: &FullMat<F64>, kl: usize, ku: usize) -> Self {
+        let (m, n) = mat.shape();
+        let mut band = Self::zeros(m, n, kl, ku);
+        for i in 0..m {
+            for j in 0..n {
+                if band.in_band((i, j)) {
+                    band[(i, j)] = mat[(i, j)];
+                } else {
+                    assert!(mat[(i, j)] == F64::ZERO);
+                }
+            }
+        }
+        band
+    }
+
+    pub fn mul_vec(&self, rhs: &[F64]) -> Vec<F64> {
+        assert!(rhs.len() == self.col_count);
+        (0..self.row_count)
+            .map(|i| {
+                let mut sum = F64::ZERO;
+                for j in self.row_range(i) {
+                    sum += self[(i, j)] * rhs[j];
+                }
+                sum
+            })
+            .collect()
+    }
+
+    // Banded LU with partial pivoting, as in LAPACK's gbtrf. Row interchanges
+    // widen U to kl + ku superdiagonals, while L keeps kl subdiagonals.
+    // Returns None if the matrix is singular.
+    pub fn lu(&self) -> Option<BandLu> {
+        assert!(self.is_square());
+        let n = self.row_count;
+        let (kl, ku) = (self.kl, self.ku);
+
+        let mut lu = Self::zeros(n, n, kl, kl + ku);
+        for i in 0..n {
+            for j in self.row_range(i) {
+                lu[(i, j)] = self[(i, j)];
+            }
+        }
+        let mut pivots = Vec::with_capacity(n);
+
+        for k in 0..n {
+            let last_row = (k + kl).min(n - 1);
+            let last_col = (k + kl + ku).min(n - 1);
+            let mut p = k;
+            for i in (k + 1)..=last_row {
+                if lu[(i, k)].abs() > lu[(p, k)].abs() {
+                    p = i;
+                }
+            }
+            pivots.push(p);
+            if lu[(p, k)] == F64::ZERO {
+                return None;
+            }
+            if p != k {
+                for j in k..=last_col {
+                    let ia = lu.index_in_vec((k, j));
+                    let ib = lu.index_in_vec((p, j));
+                    lu.storage.swap(ia, ib);
+                }
+            }
+
+            let pivot = lu[(k, k)];
+            for i in (k + 1)..=last_row {
+                let m = lu[(i, k)] / pivot;
+                lu[(i, k)] = m;
+                for j in (k + 1)..=last_col {
+                    let ukj = lu[(k, j)];
+                    lu[(i, j)] -= m * ukj;
+                }
+            }
+        }
+
+        Some(BandLu { lu, pivots })
+    }
+
+    pub fn lu_solve(&self, b: &[F64]) -> Option<Vec<F64>> {
+        self.lu().map(|lu| lu.solve(b))
+    }
+}
+
+// Factors of a banded LU: the multipliers of L are stored below the
+// diagonal of `lu` and U on and above it. At step k, row k was interchanged
+// with row `pivots[k]` before elimination.
+#[derive(Debug, Clone)]
+pub struct BandLu {
+    lu: BandMat<F64>,
+    pivots: Vec<usize>,
+}
+
+impl BandLu {
+    pub fn pivots(&self) -> &[usize] {
+        &self.pivots
+    }
+
+    pub fn solve(&self, b: &[F64]) -> Vec<F64> {
+        let n = self.pivots.len();
+        assert!(b.len() == n);
+        let lu = &self.lu;
+
+        let mut x = b.to_vec();
+        // apply the interchanges and L in the order of the elimination
+        for k in 0..n {
+            x.swap(k, self.pivots[k]);
+            let xk = x[k];
+            for i in (k + 1)..=(k + lu.kl).min(n - 1) {
+                x[i] -= lu[(i, k)] * xk;
+            }
+        }
+        for k in (0..n).rev() {
+            let mut sum = x[k];
+            for j in (k + 1)..=(k + lu.ku).min(n - 1) {
+                sum -= lu[(k, j)] * x[j];
+            }
+            x[k] = sum / lu[(k, k)];
+        }
+        x
+    }
+
+    pub fn det(&self) -> F64 {
+        let mut det = F64::ONE;
+        for (k, p) in self.pivots.iter().enumerate() {
+            det *= self.lu[(k, k)];
+            if *p != k {
+                det = -det;
+            }
+        }
+        det
+    }
+}
+
+impl Index<(usize, usize)> for BandMat<F64> {
+    type Output = F64;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        if self.in_band(index) {
+            &self.storage[self.index_in_vec(index)]
+        } else {
+            &F64::ZERO
+        }
+    }
+}
+
+impl IndexMut<(usize, usize)> for BandMat<F64> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        assert!(self.in_band(index));
+        let i = self.index_in_vec(index);
+        &mut self.storage[i]
+    }
+}
+
+impl Matrix<F64> for BandMat<F64> {
+    fn shape(&self) -> (usize, usize) {
+        (self.row_count, self.col_count)
+    }
+}
+
+impl Display for BandMat<F64> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(self, f)
+    }
+}
+
+impl From<BandMat<F64>> for FullMat<F64> {
+    fn from(band: BandMat<F64>) -> Self {
+        let mut mat = FullMat::zeros(band.row_count, band.col_count);
+        for i in 0..band.row_count {
+            for j in band.row_range(i) {
+                mat[(i, j)] = band[(i, j)];
+            }
+        }
+        mat
+    }
+}
+
+impl From<TridiagonalMat<F64>> for BandMat<F64> {
+    fn from(tri: TridiagonalMat<F64>) -> Self {
+        let n = tri.row_count();
+        let mut band = Self::zeros(n, n, 1, 1);
+        for i in 0..n {
+            for j in band.row_range(i) {
+                band[(i, j)] = tri[(i, j)];
+            }
+        }
+        band
+    }
+}
+
+impl LinearOperator for BandMat<F64> {
+    fn dim(&self) -> usize {
+        assert!(self.is_square());
+        self.row_count
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        self.mul_vec(x)
+    }
+}
+
+impl RowAccess for BandMat<F64> {
+    fn for_each_in_row<F: FnMut(usize, F64)>(&self, i: usize, mut f: F) {
+        for j in self.row_range(i) {
+            f(j, self[(i, j)]);
+        }
+    }
+}
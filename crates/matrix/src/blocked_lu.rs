@@ -0,0 +1,190 @@
+// Blocked and recursive LU factorizations with partial pivoting. Both work in
+// place on a row-major copy of the matrix, swap whole rows as soon as a pivot
+// is chosen, and leave most of the O(n^3) work to `Gemm` updates of the
+// trailing matrix, which run on the rayon pool with the `parallel` feature.
+// The pivots follow the unblocked elimination: the entry of largest magnitude
+// in the updated column, the first one on ties.
+
+use std::ops::Range;
+
+use floating_point::F64;
+
+use crate::{
+    full_mat::LuFactors, gemm::Gemm, FullMat, Matrix, MatrixError, TriFullMat, TriangleMatType,
+};
+
+// panel width of `lu`; smaller matrices are factored in a single panel
+pub(crate) const LU_BLOCK_SIZE: usize = 64;
+
+// the recursion factors panels this narrow directly
+const RECURSION_LEAF: usize = 16;
+
+struct LuWork {
+    a: Vec<F64>,
+    n: usize,
+    // P A = L U with (P A)[i] = A[p[i]]
+    p: Vec<usize>,
+}
+
+impl LuWork {
+    fn swap_rows(&mut self, i: usize, j: usize) {
+        let n = self.n;
+        let (top, bottom) = self.a.split_at_mut(i.max(j) * n);
+        top[i.min(j) * n..][..n].swap_with_slice(&mut bottom[..n]);
+        self.p.swap(i, j);
+    }
+
+    // Elimination on columns j0..j0 + w from row j0 down. Columns right of
+    // the panel only see the row swaps.
+    fn factor_panel(&mut self, j0: usize, w: usize) -> Result<(), MatrixError> {
+        let n = self.n;
+        for j in j0..j0 + w {
+            let (mut max_abs, mut max_abs_row) = (self.a[j * n + j].abs(), j);
+            for i in (j + 1)..n {
+                let abs = self.a[i * n + j].abs();
+                if abs > max_abs {
+                    max_abs = abs;
+                    max_abs_row = i;
+                }
+            }
+            if max_abs_row != j {
+                self.swap_rows(j, max_abs_row);
+            }
+
+            let pivot = self.a[j * n + j];
+            if pivot == F64::ZERO {
+                return Err(MatrixError::Singular(j));
+            }
+            let (top, bottom) = self.a.split_at_mut((j + 1) * n);
+            let pivot_row = &top[j * n + j + 1..j * n + j0 + w];
+            for row in bottom.chunks_exact_mut(n) {
+                let m = row[j] / pivot;
+                row[j] = m;
+                for (x, u) in row[j + 1..j0 + w].iter_mut().zip(pivot_row) {
+                    *x -= m * *u;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // A[j0..j0 + w, cols] = L11^-1 A[j0..j0 + w, cols], with L11 the unit lower
+    // triangle of the factored panel
+    fn solve_block_row(&mut self, j0: usize, w: usize, cols: Range<usize>) {
+        let n = self.n;
+        for i in (j0 + 1)..(j0 + w) {
+            let (top, bottom) = self.a.split_at_mut(i * n);
+            let (l_row, row) = bottom[..n].split_at_mut(cols.start);
+            for (p, l) in l_row.iter().enumerate().take(i).skip(j0) {
+                let u_row = &top[p * n + cols.start..p * n + cols.end];
+                for (x, u) in row[..cols.len()].iter_mut().zip(u_row) {
+                    *x -= *l * *u;
+                }
+            }
+        }
+    }
+
+    // A[j0 + w.., cols] -= A[j0 + w.., j0..j0 + w] A[j0..j0 + w, cols]
+    fn update_trailing(&mut self, j0: usize, w: usize, cols: Range<usize>) {
+        let n = self.n;
+        if j0 + w == n || cols.is_empty() {
+            return;
+        }
+        // L21 shares its rows with the updated block, so it is copied out
+        let l21: Vec<F64> = self.a[(j0 + w) * n..]
+            .chunks_exact(n)
+            .flat_map(|row| row[j0..j0 + w].iter().copied())
+            .collect();
+        let (top, bottom) = self.a.split_at_mut((j0 + w) * n);
+        Gemm {
+            a: &l21,
+            a_strides: (w, 1),
+            b: &top[j0 * n + cols.start..],
+            b_strides: (n, 1),
+            m: n - j0 - w,
+            k: w,
+            n: cols.len(),
+            alpha: -F64::ONE,
+        }
+        .run(&mut bottom[cols.start..], n);
+    }
+
+    fn factor_blocked(&mut self, block_size: usize) -> Result<(), MatrixError> {
+        let n = self.n;
+        for j0 in (0..n).step_by(block_size) {
+            let w = block_size.min(n - j0);
+            self.factor_panel(j0, w)?;
+            self.solve_block_row(j0, w, (j0 + w)..n);
+            self.update_trailing(j0, w, (j0 + w)..n);
+        }
+        Ok(())
+    }
+
+    // Columns j0..j0 + w from row j0 down: the left half, then the right half
+    // after its update by the left one.
+    fn factor_recursive(&mut self, j0: usize, w: usize) -> Result<(), MatrixError> {
+        if w <= RECURSION_LEAF {
+            return self.factor_panel(j0, w);
+        }
+        let w1 = w / 2;
+        self.factor_recursive(j0, w1)?;
+        self.solve_block_row(j0, w1, (j0 + w1)..(j0 + w));
+        self.update_trailing(j0, w1, (j0 + w1)..(j0 + w));
+        self.factor_recursive(j0 + w1, w - w1)
+    }
+
+    fn into_factors(self) -> LuFactors {
+        let n = self.n;
+        let mut l = Vec::with_capacity(n * (n + 1) / 2);
+        let mut u = Vec::with_capacity(n * (n + 1) / 2);
+        for (i, row) in self.a.chunks_exact(n).enumerate() {
+            l.extend_from_slice(&row[..i]);
+            l.push(F64::ONE);
+            u.extend_from_slice(&row[i..]);
+        }
+        (
+            TriFullMat::from_vec(TriangleMatType::Lower, l),
+            TriFullMat::from_vec(TriangleMatType::Upper, u),
+            self.p,
+        )
+    }
+}
+
+impl FullMat<F64> {
+    fn lu_work(&self) -> LuWork {
+        let n = self.row_count();
+        LuWork {
+            a: self.to_row_major().into_row_major_vec(),
+            n,
+            p: (0..n).collect(),
+        }
+    }
+
+    pub(crate) fn try_lu_blocked(&self, block_size: usize) -> Result<LuFactors, MatrixError> {
+        if !self.is_square() {
+            return Err(MatrixError::NotSquare(self.shape()));
+        }
+        let mut work = self.lu_work();
+        work.factor_blocked(block_size)?;
+        Ok(work.into_factors())
+    }
+
+    // Right-looking blocked LU: eliminate in a panel of `block_size` columns,
+    // solve for the block row of U right of it and update the rest of the
+    // matrix with one product. `lu` uses this with a block size of 64.
+    pub fn lu_blocked(&self, block_size: usize) -> Option<LuFactors> {
+        assert!(self.is_square());
+        assert!(block_size > 0);
+        self.try_lu_blocked(block_size).ok()
+    }
+
+    // Recursive LU (Toledo, 1997): factor the left half of the columns, update
+    // the right half and recurse into it. The products get larger than with
+    // fixed blocks, without a block size to tune.
+    pub fn lu_recursive(&self) -> Option<LuFactors> {
+        assert!(self.is_square());
+        let mut work = self.lu_work();
+        work.factor_recursive(0, work.n).ok()?;
+        Some(work.into_factors())
+    }
+}
@@ -0,0 +1,88 @@
+use floating_point::F64;
+
+use crate::{FullMat, Matrix, SymPackedMat, TriFullMat, TriangleMatType};
+
+impl FullMat<F64> {
+    // Returns the lower triangular factor L with A = L * L^T, or None if the
+    // matrix is not symmetric positive definite.
+    pub fn cholesky(&self) -> Option<TriFullMat<F64>> {
+        assert!(self.is_square());
+
+        let n = self.col_count();
+        let packed = |i: usize, j: usize| (1 + i) * i / 2 + j;
+        let mut l: Vec<F64> = Vec::with_capacity(n * (n + 1) / 2);
+
+        for i in 0..n {
+            for j in 0..=i {
+                let mut sum = self[(i, j)];
+                for k in 0..j {
+                    sum -= l[packed(i, k)] * l[packed(j, k)];
+                }
+                if i == j {
+                    if sum <= F64::ZERO || !sum.is_finite() {
+                        return None;
+                    }
+                    l.push(sum.sqrt());
+                } else {
+                    l.push(sum / l[packed(j, j)]);
+                }
+            }
+        }
+
+        Some(TriFullMat::from_vec(TriangleMatType::Lower, l))
+    }
+
+    pub fn cholesky_solve(&self, b: &[F64]) -> Option<Vec<F64>> {
+        assert!(b.len() == self.col_count());
+
+        self.cholesky().map(|l| {
+            let y = l.solve(b.to_vec());
+            l.transpose().solve(y)
+        })
+    }
+}
+
+impl SymPackedMat<F64> {
+    // As `FullMat::cholesky`. L takes the place of the lower triangle in the
+    // storage, so a matrix holding that triangle needs no further memory.
+    pub fn into_cholesky(self) -> Option<TriFullMat<F64>> {
+        let n = self.row_count();
+        let mut l = match self.ty {
+            TriangleMatType::Lower => self.into_storage(),
+            TriangleMatType::Upper => self.to_other_triangle().into_storage(),
+        };
+        let packed = |i: usize, j: usize| (1 + i) * i / 2 + j;
+
+        for i in 0..n {
+            for j in 0..=i {
+                let mut sum = l[packed(i, j)];
+                for k in 0..j {
+                    sum -= l[packed(i, k)] * l[packed(j, k)];
+                }
+                if i == j {
+                    if sum <= F64::ZERO || !sum.is_finite() {
+                        return None;
+                    }
+                    l[packed(i, i)] = sum.sqrt();
+                } else {
+                    l[packed(i, j)] = sum / l[packed(j, j)];
+                }
+            }
+        }
+
+        Some(TriFullMat::from_vec(TriangleMatType::Lower, l))
+    }
+
+    pub fn cholesky(&self) -> Option<TriFullMat<F64>> {
+        self.clone().into_cholesky()
+    }
+
+    pub fn cholesky_solve(&self, b: &[F64]) -> Option<Vec<F64>> {
+        assert!(b.len() == self.col_count());
+
+        self.cholesky().map(|l| {
+            let y = l.solve(b.to_vec());
+            l.transpose().solve(y)
+        })
+    }
+}
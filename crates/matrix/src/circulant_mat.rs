@@ -0,0 +1,175 @@
+use core::ops::Index;
+use std::fmt::Display;
+
+use fft::{RecursiveBisectionFFT, DFT};
+use floating_point::{Complex64, F64};
+
+use crate::{FullMat, LinearOperator, Matrix};
+
+// DFT of any length. Powers of two go straight to the radix-2 FFT, other
+// lengths through Bluestein's chirp-z algorithm, which writes the DFT as a
+// convolution and evaluates it with power of two FFTs, so the cost stays
+// O(n log n).
+fn dft(data: &[Complex64]) -> Vec<Complex64> {
+    let n = data.len();
+    if n.is_power_of_two() {
+        return RecursiveBisectionFFT::fourier_transform(data);
+    }
+
+    // chirp[j] = exp(-i pi j^2 / n), with j^2 reduced modulo 2n to keep the
+    // angles accurate
+    let chirp: Vec<Complex64> = (0..n)
+        .map(|j| Complex64::omega_n_power(2 * n, (j * j) % (2 * n)))
+        .collect();
+    let m = (2 * n - 1).next_power_of_two();
+    let mut a = vec![Complex64::ZERO; m];
+    for j in 0..n {
+        a[j] = data[j] * chirp[j];
+    }
+    let mut b = vec![Complex64::ZERO; m];
+    b[0] = chirp[0].conjugate();
+    for j in 1..n {
+        b[j] = chirp[j].conjugate();
+        b[m - j] = chirp[j].conjugate();
+    }
+
+    let fa = RecursiveBisectionFFT::fourier_transform(&a);
+    let fb = RecursiveBisectionFFT::fourier_transform(&b);
+    let product: Vec<Complex64> = fa.iter().zip(fb.iter()).map(|(a, b)| *a * *b).collect();
+    let convolution = RecursiveBisectionFFT::inverse_fourier_transform(&product);
+    (0..n).map(|k| convolution[k] * chirp[k]).collect()
+}
+
+fn inverse_dft(data: &[Complex64]) -> Vec<Complex64> {
+    let scale = Complex64::new(1.0 / data.len() as f64, 0.0);
+    let conjugated: Vec<Complex64> = data.iter().map(|v| v.conjugate()).collect();
+    dft(&conjugated)
+        .into_iter()
+        .map(|v| v.conjugate() * scale)
+        .collect()
+}
+
+fn to_complex(data: &[F64]) -> Vec<Complex64> {
+    data.iter()
+        .map(|v| Complex64::from((*v, F64::ZERO)))
+        .collect()
+}
+
+// Square matrix whose rows are cyclic shifts of each other: the entry (i, j)
+// is `first_col[(i - j) mod n]`. It is diagonalised by the DFT, with the
+// eigenvalues being the DFT of the first column, so products and solves cost
+// O(n log n).
+#[derive(Debug, Clone)]
+pub struct CirculantMat {
+    first_col: Vec<F64>,
+    eigenvalues: Vec<Complex64>,
+}
+
+impl CirculantMat {
+    pub fn new(first_col: Vec<F64>) -> Self {
+        assert!(!first_col.is_empty());
+        let eigenvalues = dft(&to_complex(&first_col));
+        Self {
+            first_col,
+            eigenvalues,
+        }
+    }
+
+    pub fn first_col(&self) -> &[F64] {
+        &self.first_col
+    }
+
+    // The eigenvalue k belongs to the Fourier mode exp(2 pi i j k / n).
+    pub fn eigenvalues(&self) -> &[Complex64] {
+        &self.eigenvalues
+    }
+
+    pub fn mul_vec(&self, rhs: &[F64]) -> Vec<F64> {
+        assert!(rhs.len() == self.first_col.len());
+        let transformed: Vec<Complex64> = dft(&to_complex(rhs))
+            .into_iter()
+            .zip(self.eigenvalues.iter())
+            .map(|(x, lambda)| x * *lambda)
+            .collect();
+        inverse_dft(&transformed)
+            .into_iter()
+            .map(|v| v.real)
+            .collect()
+    }
+
+    // Returns None if the matrix is singular up to rounding, i.e. an
+    // eigenvalue is below n * epsilon times the largest one.
+    pub fn solve(&self, b: &[F64]) -> Option<Vec<F64>> {
+        let n = self.first_col.len();
+        assert!(b.len() == n);
+        let max = self
+            .eigenvalues
+            .iter()
+            .fold(F64::ZERO, |max, lambda| max.max(lambda.abs()));
+        let tolerance = F64::from(n as f64) * F64::EPSILON * max;
+        if self
+            .eigenvalues
+            .iter()
+            .any(|lambda| lambda.abs() <= tolerance)
+        {
+            return None;
+        }
+
+        let transformed: Vec<Complex64> = dft(&to_complex(b))
+            .into_iter()
+            .zip(self.eigenvalues.iter())
+            .map(|(x, lambda)| x / *lambda)
+            .collect();
+        Some(
+            inverse_dft(&transformed)
+                .into_iter()
+                .map(|v| v.real)
+                .collect(),
+        )
+    }
+}
+
+impl Index<(usize, usize)> for CirculantMat {
+    type Output = F64;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        let (i, j) = index;
+        let n = self.first_col.len();
+        assert!(i < n && j < n);
+        &self.first_col[(i + n - j) % n]
+    }
+}
+
+impl Matrix<F64> for CirculantMat {
+    fn shape(&self) -> (usize, usize) {
+        (self.first_col.len(), self.first_col.len())
+    }
+}
+
+impl Display for CirculantMat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(self, f)
+    }
+}
+
+impl From<CirculantMat> for FullMat<F64> {
+    fn from(mat: CirculantMat) -> Self {
+        let n = mat.first_col.len();
+        let mut full = FullMat::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                full[(i, j)] = mat[(i, j)];
+            }
+        }
+        full
+    }
+}
+
+impl LinearOperator for CirculantMat {
+    fn dim(&self) -> usize {
+        self.first_col.len()
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        self.mul_vec(x)
+    }
+}
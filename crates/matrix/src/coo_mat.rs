@@ -0,0 +1,147 @@
+use floating_point::F64;
+
+use crate::{CscMat, CsrMat, FullMat, Matrix};
+
+// Coordinate (triplet) storage, meant for assembly. Entries may be pushed in
+// any order and duplicates are summed when converting to CSR or CSC.
+#[derive(Debug, Clone)]
+pub struct CooMat<T> {
+    row_count: usize,
+    col_count: usize,
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Copy> CooMat<T> {
+    pub fn new(row_count: usize, col_count: usize) -> Self {
+        Self::with_capacity(row_count, col_count, 0)
+    }
+
+    pub fn with_capacity(row_count: usize, col_count: usize, capacity: usize) -> Self {
+        assert!(row_count > 0 && col_count > 0);
+        Self {
+            row_count,
+            col_count,
+            rows: Vec::with_capacity(capacity),
+            cols: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, i: usize, j: usize, value: T) {
+        assert!(i < self.row_count && j < self.col_count);
+        self.rows.push(i);
+        self.cols.push(j);
+        self.values.push(value);
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.row_count, self.col_count)
+    }
+
+    // number of stored triplets, duplicates included
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        self.rows
+            .iter()
+            .zip(self.cols.iter())
+            .zip(self.values.iter())
+            .map(|((i, j), v)| (*i, *j, *v))
+    }
+}
+
+// Sorts triplets by (major, minor) with a counting sort on the major index and
+// sums duplicates, returning compressed pointer, index and value arrays.
+fn compress(
+    major_count: usize,
+    major: &[usize],
+    minor: &[usize],
+    values: &[F64],
+) -> (Vec<usize>, Vec<usize>, Vec<F64>) {
+    let mut ptr = vec![0; major_count + 1];
+    for i in major {
+        ptr[i + 1] += 1;
+    }
+    for i in 0..major_count {
+        ptr[i + 1] += ptr[i];
+    }
+
+    let mut next = ptr.clone();
+    let mut entries = vec![(0, F64::ZERO); values.len()];
+    for ((i, j), v) in major.iter().zip(minor.iter()).zip(values.iter()) {
+        entries[next[*i]] = (*j, *v);
+        next[*i] += 1;
+    }
+
+    let mut out_ptr = Vec::with_capacity(major_count + 1);
+    let mut out_idx = Vec::with_capacity(values.len());
+    let mut out_val = Vec::with_capacity(values.len());
+    out_ptr.push(0);
+    for i in 0..major_count {
+        let segment = &mut entries[ptr[i]..ptr[i + 1]];
+        segment.sort_unstable_by_key(|e| e.0);
+        let start = out_idx.len();
+        for (j, v) in segment.iter() {
+            if out_idx.len() > start && out_idx[out_idx.len() - 1] == *j {
+                *out_val.last_mut().unwrap() += *v;
+            } else {
+                out_idx.push(*j);
+                out_val.push(*v);
+            }
+        }
+        out_ptr.push(out_idx.len());
+    }
+    (out_ptr, out_idx, out_val)
+}
+
+impl CooMat<F64> {
+    // keeps every nonzero entry of a dense matrix
+    pub fn from_dense(mat: &FullMat<F64>) -> Self {
+        let (m, n) = mat.shape();
+        let mut coo = Self::new(m, n);
+        for i in 0..m {
+            for j in 0..n {
+                if mat[(i, j)] != F64::ZERO {
+                    coo.push(i, j, mat[(i, j)]);
+                }
+            }
+        }
+        coo
+    }
+
+    // Duplicates are summed, so a sum that cancels is kept as an explicit
+    // zero in the sparsity pattern.
+    pub fn to_csr(&self) -> CsrMat<F64> {
+        let (ptr, idx, val) = compress(self.row_count, &self.rows, &self.cols, &self.values);
+        CsrMat::from_raw_parts(self.row_count, self.col_count, ptr, idx, val)
+    }
+
+    pub fn to_csc(&self) -> CscMat<F64> {
+        let (ptr, idx, val) = compress(self.col_count, &self.cols, &self.rows, &self.values);
+        CscMat::from_raw_parts(self.row_count, self.col_count, ptr, idx, val)
+    }
+
+    pub fn to_dense(&self) -> FullMat<F64> {
+        let mut mat = FullMat::zeros(self.row_count, self.col_count);
+        for (i, j, v) in self.iter() {
+            mat[(i, j)] += v;
+        }
+        mat
+    }
+}
+
+impl From<CooMat<F64>> for CsrMat<F64> {
+    fn from(coo: CooMat<F64>) -> Self {
+        coo.to_csr()
+    }
+}
+
+impl From<CooMat<F64>> for CscMat<F64> {
+    fn from(coo: CooMat<F64>) -> Self {
+        coo.to_csc()
+    }
+}
@@ -0,0 +1,196 @@
+use core::ops::Index;
+use std::fmt::Display;
+
+use floating_point::F64;
+
+use crate::csr_mat::{check_compressed, Compressed};
+use crate::{CooMat, CsrMat, FullMat, LinearOperator, Matrix};
+
+// Compressed sparse columns: the row indices and values of column j are stored
+// in `col_ptr[j]..col_ptr[j + 1]`, with strictly increasing row indices.
+#[derive(Debug, Clone)]
+pub struct CscMat<T> {
+    row_count: usize,
+    col_count: usize,
+    col_ptr: Vec<usize>,
+    row_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Copy> CscMat<T> {
+    pub fn from_raw_parts(
+        row_count: usize,
+        col_count: usize,
+        col_ptr: Vec<usize>,
+        row_indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> Self {
+        check_compressed(col_count, row_count, &col_ptr, &row_indices, values.len());
+        Self {
+            row_count,
+            col_count,
+            col_ptr,
+            row_indices,
+            values,
+        }
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn col_ptr(&self) -> &[usize] {
+        &self.col_ptr
+    }
+
+    pub fn row_indices(&self) -> &[usize] {
+        &self.row_indices
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    // the pattern is fixed, but stored values may be updated in place
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    // (row, value) pairs of the stored entries of column j
+    pub fn col(&self, j: usize) -> impl Iterator<Item = (usize, T)> + '_ {
+        let range = self.col_ptr[j]..self.col_ptr[j + 1];
+        self.row_indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter().copied())
+    }
+
+    // (row, column, value) of every stored entry in column-major order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        (0..self.col_count).flat_map(move |j| self.col(j).map(move |(i, v)| (i, j, v)))
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> Option<&T> {
+        assert!(i < self.row_count && j < self.col_count);
+        let range = self.col_ptr[j]..self.col_ptr[j + 1];
+        self.row_indices[range.clone()]
+            .binary_search(&i)
+            .ok()
+            .map(|k| &self.values[range.start + k])
+    }
+}
+
+impl CscMat<F64> {
+    // seen as compressed rows, these arrays describe A^T
+    fn as_compressed(&self) -> Compressed<'_> {
+        Compressed {
+            minor_count: self.row_count,
+            ptr: &self.col_ptr,
+            idx: &self.row_indices,
+            val: &self.values,
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        Self::from_raw_parts(n, n, (0..=n).collect(), (0..n).collect(), vec![F64::ONE; n])
+    }
+
+    pub fn from_dense(mat: &FullMat<F64>) -> Self {
+        CooMat::from_dense(mat).to_csc()
+    }
+
+    pub fn to_dense(&self) -> FullMat<F64> {
+        let mut mat = FullMat::zeros(self.row_count, self.col_count);
+        for (i, j, v) in self.iter() {
+            mat[(i, j)] = v;
+        }
+        mat
+    }
+
+    pub fn to_coo(&self) -> CooMat<F64> {
+        let mut coo = CooMat::with_capacity(self.row_count, self.col_count, self.nnz());
+        for (i, j, v) in self.iter() {
+            coo.push(i, j, v);
+        }
+        coo
+    }
+
+    pub fn to_csr(&self) -> CsrMat<F64> {
+        let (ptr, idx, val) = self.as_compressed().transpose();
+        CsrMat::from_raw_parts(self.row_count, self.col_count, ptr, idx, val)
+    }
+
+    pub fn transpose(&self) -> Self {
+        let (ptr, idx, val) = self.as_compressed().transpose();
+        Self::from_raw_parts(self.col_count, self.row_count, ptr, idx, val)
+    }
+
+    pub fn mul_vec(&self, rhs: &[F64]) -> Vec<F64> {
+        assert!(self.col_count == rhs.len());
+
+        let mut v = vec![F64::ZERO; self.row_count];
+        for (j, x) in rhs.iter().enumerate() {
+            for (i, a) in self.col(j) {
+                v[i] += a * *x;
+            }
+        }
+        v
+    }
+
+    pub fn mul_dense(&self, rhs: &FullMat<F64>) -> FullMat<F64> {
+        assert!(self.col_count == rhs.row_count());
+
+        let mut mat = FullMat::zeros(self.row_count, rhs.col_count());
+        for (i, k, v) in self.iter() {
+            for j in 0..rhs.col_count() {
+                mat[(i, j)] += v * rhs[(k, j)];
+            }
+        }
+        mat
+    }
+
+    // (AB)^T = B^T A^T, and the CSC arrays of a matrix are the CSR arrays of
+    // its transpose
+    pub fn mul_sparse(&self, rhs: &CscMat<F64>) -> CscMat<F64> {
+        assert!(self.col_count == rhs.row_count);
+
+        let (ptr, idx, val) = rhs.as_compressed().mul(&self.as_compressed());
+        Self::from_raw_parts(self.row_count, rhs.col_count, ptr, idx, val)
+    }
+}
+
+impl Index<(usize, usize)> for CscMat<F64> {
+    type Output = F64;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        self.get(index.0, index.1).unwrap_or(&F64::ZERO)
+    }
+}
+
+impl Matrix<F64> for CscMat<F64> {
+    fn shape(&self) -> (usize, usize) {
+        (self.row_count, self.col_count)
+    }
+}
+
+impl Display for CscMat<F64> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(self, f)
+    }
+}
+
+impl From<CscMat<F64>> for FullMat<F64> {
+    fn from(mat: CscMat<F64>) -> Self {
+        mat.to_dense()
+    }
+}
+
+impl LinearOperator for CscMat<F64> {
+    fn dim(&self) -> usize {
+        assert!(self.is_square());
+        self.col_count
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        self.mul_vec(x)
+    }
+}
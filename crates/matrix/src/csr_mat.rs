@@ -0,0 +1,310 @@
+use core::ops::Index;
+use std::fmt::Display;
+
+use floating_point::F64;
+
+use crate::{CooMat, CscMat, FullMat, LinearOperator, Matrix, RowAccess};
+
+// Compressed sparse rows: the column indices and values of row i are stored in
+// `row_ptr[i]..row_ptr[i + 1]`, with strictly increasing column indices.
+#[derive(Debug, Clone)]
+pub struct CsrMat<T> {
+    row_count: usize,
+    col_count: usize,
+    row_ptr: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+// Checks the invariants shared by CSR and CSC storage, seen from the major
+// (compressed) dimension.
+pub(crate) fn check_compressed(
+    major_count: usize,
+    minor_count: usize,
+    ptr: &[usize],
+    idx: &[usize],
+    value_count: usize,
+) {
+    assert!(major_count > 0 && minor_count > 0);
+    assert!(ptr.len() == major_count + 1);
+    assert!(ptr[0] == 0 && ptr[major_count] == idx.len());
+    assert!(idx.len() == value_count);
+    for k in 0..major_count {
+        assert!(ptr[k] <= ptr[k + 1]);
+        let segment = &idx[ptr[k]..ptr[k + 1]];
+        for w in segment.windows(2) {
+            assert!(w[0] < w[1]);
+        }
+        if let Some(last) = segment.last() {
+            assert!(*last < minor_count);
+        }
+    }
+}
+
+// Raw compressed arrays, so that CSR and CSC share the structural kernels:
+// the CSC arrays of A are exactly the CSR arrays of A^T.
+pub(crate) struct Compressed<'a> {
+    pub(crate) minor_count: usize,
+    pub(crate) ptr: &'a [usize],
+    pub(crate) idx: &'a [usize],
+    pub(crate) val: &'a [F64],
+}
+
+impl Compressed<'_> {
+    fn major_count(&self) -> usize {
+        self.ptr.len() - 1
+    }
+
+    // Swaps the major and minor dimensions with a counting sort, which
+    // leaves the new minor indices sorted.
+    pub(crate) fn transpose(&self) -> (Vec<usize>, Vec<usize>, Vec<F64>) {
+        let nnz = self.idx.len();
+        let mut ptr = vec![0; self.minor_count + 1];
+        for j in self.idx {
+            ptr[j + 1] += 1;
+        }
+        for j in 0..self.minor_count {
+            ptr[j + 1] += ptr[j];
+        }
+
+        let mut next = ptr.clone();
+        let mut idx = vec![0; nnz];
+        let mut val = vec![F64::ZERO; nnz];
+        for i in 0..self.major_count() {
+            for k in self.ptr[i]..self.ptr[i + 1] {
+                let j = self.idx[k];
+                idx[next[j]] = i;
+                val[next[j]] = self.val[k];
+                next[j] += 1;
+            }
+        }
+        (ptr, idx, val)
+    }
+
+    // Gustavson's row-by-row product, accumulating each row of the result in
+    // a dense work vector.
+    pub(crate) fn mul(&self, rhs: &Compressed) -> (Vec<usize>, Vec<usize>, Vec<F64>) {
+        assert!(self.minor_count == rhs.major_count());
+
+        let n = rhs.minor_count;
+        let mut marker = vec![usize::MAX; n];
+        let mut acc = vec![F64::ZERO; n];
+        let mut ptr = Vec::with_capacity(self.major_count() + 1);
+        let mut idx = vec![];
+        let mut val = vec![];
+        ptr.push(0);
+        for i in 0..self.major_count() {
+            let start = idx.len();
+            for k in self.ptr[i]..self.ptr[i + 1] {
+                let (l, a) = (self.idx[k], self.val[k]);
+                for p in rhs.ptr[l]..rhs.ptr[l + 1] {
+                    let j = rhs.idx[p];
+                    if marker[j] != i {
+                        marker[j] = i;
+                        acc[j] = a * rhs.val[p];
+                        idx.push(j);
+                    } else {
+                        acc[j] += a * rhs.val[p];
+                    }
+                }
+            }
+            idx[start..].sort_unstable();
+            val.extend(idx[start..].iter().map(|j| acc[*j]));
+            ptr.push(idx.len());
+        }
+        (ptr, idx, val)
+    }
+}
+
+impl<T: Copy> CsrMat<T> {
+    pub fn from_raw_parts(
+        row_count: usize,
+        col_count: usize,
+        row_ptr: Vec<usize>,
+        col_indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> Self {
+        check_compressed(row_count, col_count, &row_ptr, &col_indices, values.len());
+        Self {
+            row_count,
+            col_count,
+            row_ptr,
+            col_indices,
+            values,
+        }
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn row_ptr(&self) -> &[usize] {
+        &self.row_ptr
+    }
+
+    pub fn col_indices(&self) -> &[usize] {
+        &self.col_indices
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    // the pattern is fixed, but stored values may be updated in place
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    // (column, value) pairs of the stored entries of row i
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, T)> + '_ {
+        let range = self.row_ptr[i]..self.row_ptr[i + 1];
+        self.col_indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter().copied())
+    }
+
+    // (row, column, value) of every stored entry in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        (0..self.row_count).flat_map(move |i| self.row(i).map(move |(j, v)| (i, j, v)))
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> Option<&T> {
+        assert!(i < self.row_count && j < self.col_count);
+        let range = self.row_ptr[i]..self.row_ptr[i + 1];
+        self.col_indices[range.clone()]
+            .binary_search(&j)
+            .ok()
+            .map(|k| &self.values[range.start + k])
+    }
+}
+
+impl CsrMat<F64> {
+    fn as_compressed(&self) -> Compressed<'_> {
+        Compressed {
+            minor_count: self.col_count,
+            ptr: &self.row_ptr,
+            idx: &self.col_indices,
+            val: &self.values,
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        Self::from_raw_parts(n, n, (0..=n).collect(), (0..n).collect(), vec![F64::ONE; n])
+    }
+
+    pub fn from_dense(mat: &FullMat<F64>) -> Self {
+        CooMat::from_dense(mat).to_csr()
+    }
+
+    pub fn to_dense(&self) -> FullMat<F64> {
+        let mut mat = FullMat::zeros(self.row_count, self.col_count);
+        for (i, j, v) in self.iter() {
+            mat[(i, j)] = v;
+        }
+        mat
+    }
+
+    pub fn to_coo(&self) -> CooMat<F64> {
+        let mut coo = CooMat::with_capacity(self.row_count, self.col_count, self.nnz());
+        for (i, j, v) in self.iter() {
+            coo.push(i, j, v);
+        }
+        coo
+    }
+
+    pub fn to_csc(&self) -> CscMat<F64> {
+        let (ptr, idx, val) = self.as_compressed().transpose();
+        CscMat::from_raw_parts(self.row_count, self.col_count, ptr, idx, val)
+    }
+
+    pub fn transpose(&self) -> Self {
+        let (ptr, idx, val) = self.as_compressed().transpose();
+        Self::from_raw_parts(self.col_count, self.row_count, ptr, idx, val)
+    }
+
+    pub fn mul_vec(&self, rhs: &[F64]) -> Vec<F64> {
+        assert!(self.col_count == rhs.len());
+
+        self.row_ptr
+            .windows(2)
+            .map(|w| {
+                let mut sum = F64::ZERO;
+                for (j, v) in self.col_indices[w[0]..w[1]]
+                    .iter()
+                    .zip(self.values[w[0]..w[1]].iter())
+                {
+                    sum += *v * rhs[*j];
+                }
+                sum
+            })
+            .collect()
+    }
+
+    pub fn mul_dense(&self, rhs: &FullMat<F64>) -> FullMat<F64> {
+        assert!(self.col_count == rhs.row_count());
+
+        let mut mat = FullMat::zeros(self.row_count, rhs.col_count());
+        for (i, k, v) in self.iter() {
+            for j in 0..rhs.col_count() {
+                mat[(i, j)] += v * rhs[(k, j)];
+            }
+        }
+        mat
+    }
+
+    pub fn mul_sparse(&self, rhs: &CsrMat<F64>) -> CsrMat<F64> {
+        assert!(self.col_count == rhs.row_count);
+
+        let (ptr, idx, val) = self.as_compressed().mul(&rhs.as_compressed());
+        Self::from_raw_parts(self.row_count, rhs.col_count, ptr, idx, val)
+    }
+}
+
+impl Index<(usize, usize)> for CsrMat<F64> {
+    type Output = F64;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        self.get(index.0, index.1).unwrap_or(&F64::ZERO)
+    }
+}
+
+impl Matrix<F64> for CsrMat<F64> {
+    fn shape(&self) -> (usize, usize) {
+        (self.row_count, self.col_count)
+    }
+}
+
+impl Display for CsrMat<F64> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(self, f)
+    }
+}
+
+impl From<CsrMat<F64>> for FullMat<F64> {
+    fn from(mat: CsrMat<F64>) -> Self {
+        mat.to_dense()
+    }
+}
+
+impl LinearOperator for CsrMat<F64> {
+    fn dim(&self) -> usize {
+        assert!(self.is_square());
+        self.col_count
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        self.mul_vec(x)
+    }
+}
+
+impl RowAccess for CsrMat<F64> {
+    fn for_each_in_row<F: FnMut(usize, F64)>(&self, i: usize, mut f: F) {
+        for (j, v) in self.row(i) {
+            f(j, v);
+        }
+    }
+
+    fn diagonal(&self) -> Vec<F64> {
+        (0..self.dim()).map(|i| self[(i, i)]).collect()
+    }
+}
@@ -0,0 +1,224 @@
+use floating_point::F64;
+
+use crate::{vector, FullMat, LinearOperator, Matrix, TriFullMat};
+
+#[derive(Debug, Clone, Copy)]
+pub struct EigenIterationOptions {
+    // stop once ||A v - lambda v||_2 falls below this for the unit iterate v
+    pub tolerance: F64,
+    pub max_iterations: usize,
+}
+
+impl Default for EigenIterationOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: F64::from(1e-10),
+            max_iterations: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EigenIterationResult {
+    pub eigenvalue: F64,
+    // unit 2-norm
+    pub eigenvector: Vec<F64>,
+    pub iterations: usize,
+    pub converged: bool,
+    // Rayleigh quotient and residual norm after every iteration
+    pub eigenvalue_history: Vec<F64>,
+    pub residual_history: Vec<F64>,
+}
+
+impl EigenIterationResult {
+    fn new() -> Self {
+        Self {
+            eigenvalue: F64::ZERO,
+            eigenvector: vec![],
+            iterations: 0,
+            converged: false,
+            eigenvalue_history: vec![],
+            residual_history: vec![],
+        }
+    }
+
+    // Records the Rayleigh quotient of the unit vector `u` with `au = A u`
+    // and reports whether the residual is below the tolerance.
+    fn record(&mut self, u: &[F64], au: &[F64], tolerance: F64) -> bool {
+        let lambda = vector::dot(u, au);
+        let residual: Vec<F64> = au
+            .iter()
+            .zip(u.iter())
+            .map(|(x, y)| *x - lambda * *y)
+            .collect();
+        let residual_norm = vector::norm2(&residual);
+
+        self.iterations += 1;
+        self.eigenvalue = lambda;
+        self.eigenvector = u.to_vec();
+        self.eigenvalue_history.push(lambda);
+        self.residual_history.push(residual_norm);
+        self.converged = residual_norm <= tolerance;
+        self.converged
+    }
+}
+
+fn normalized(x: &[F64]) -> Vec<F64> {
+    let norm = vector::norm2(x);
+    assert!(norm != F64::ZERO);
+    x.iter().map(|y| *y / norm).collect()
+}
+
+// LU factors of A - shift * I, kept so that every iteration costs two
+// triangular solves.
+struct ShiftedLu {
+    l: TriFullMat<F64>,
+    u: TriFullMat<F64>,
+    p: Vec<usize>,
+}
+
+impl ShiftedLu {
+    fn new(mat: &FullMat<F64>, shift: F64) -> Option<Self> {
+        let mut shifted = mat.clone();
+        for i in 0..mat.col_count() {
+            shifted[(i, i)] -= shift;
+        }
+        shifted.lu().map(|(l, u, p)| Self { l, u, p })
+    }
+
+    fn solve(&self, b: &[F64]) -> Vec<F64> {
+        let pb: Vec<F64> = self.p.iter().map(|i| b[*i]).collect();
+        self.u.solve(self.l.solve(pb))
+    }
+}
+
+// Finds the eigenvalue of largest magnitude.
+pub fn power_iteration<A: LinearOperator>(
+    op: &A,
+    x0: &[F64],
+    options: EigenIterationOptions,
+) -> EigenIterationResult {
+    assert!(op.dim() == x0.len());
+
+    let mut result = EigenIterationResult::new();
+    let mut u = normalized(x0);
+    for _ in 0..options.max_iterations {
+        let au = op.apply(&u);
+        if result.record(&u, &au, options.tolerance) {
+            break;
+        }
+        u = normalized(&au);
+    }
+    result
+}
+
+// Finds the eigenvalue closest to `shift`. The LU factorization of
+// A - shift * I is computed once and reused; returns None if it is singular,
+// in which case `shift` is itself an eigenvalue.
+pub fn inverse_iteration(
+    mat: &FullMat<F64>,
+    shift: F64,
+    x0: &[F64],
+    options: EigenIterationOptions,
+) -> Option<EigenIterationResult> {
+    assert!(mat.dim() == x0.len());
+
+    let lu = ShiftedLu::new(mat, shift)?;
+    let mut result = EigenIterationResult::new();
+    let mut u = normalized(x0);
+    for _ in 0..options.max_iterations {
+        let au = mat.apply(&u);
+        if result.record(&u, &au, options.tolerance) {
+            break;
+        }
+        u = normalized(&lu.solve(&u));
+    }
+    Some(result)
+}
+
+// Inverse iteration with the Rayleigh quotient as the shift, refactoring
+// A - shift * I every step. Converges cubically for symmetric matrices.
+pub fn rayleigh_quotient_iteration(
+    mat: &FullMat<F64>,
+    x0: &[F64],
+    options: EigenIterationOptions,
+) -> EigenIterationResult {
+    assert!(mat.dim() == x0.len());
+
+    let mut result = EigenIterationResult::new();
+    let mut u = normalized(x0);
+    for _ in 0..options.max_iterations {
+        let au = mat.apply(&u);
+        if result.record(&u, &au, options.tolerance) {
+            break;
+        }
+        match ShiftedLu::new(mat, result.eigenvalue) {
+            Some(lu) => u = normalized(&lu.solve(&u)),
+            // the shift is an eigenvalue to working precision
+            None => {
+                result.converged = true;
+                break;
+            }
+        }
+    }
+    result
+}
+
+// A - sum lambda_k v_k v_k^T, removing already found eigenpairs of a
+// symmetric operator (Hotelling deflation).
+struct Deflated<'a, A: LinearOperator> {
+    op: &'a A,
+    pairs: Vec<(F64, Vec<F64>)>,
+}
+
+impl<A: LinearOperator> LinearOperator for Deflated<'_, A> {
+    fn dim(&self) -> usize {
+        self.op.dim()
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        let mut y = self.op.apply(x);
+        for (lambda, v) in &self.pairs {
+            let c = *lambda * vector::dot(v, x);
+            y.iter_mut().zip(v.iter()).for_each(|(y, v)| *y -= c * *v);
+        }
+        y
+    }
+}
+
+// The `count` eigenpairs of largest magnitude of a symmetric operator, found
+// by power iteration with Hotelling deflation. Stops early if an iteration
+// fails to converge.
+pub fn dominant_eigenpairs<A: LinearOperator>(
+    op: &A,
+    count: usize,
+    x0: &[F64],
+    options: EigenIterationOptions,
+) -> Vec<EigenIterationResult> {
+    assert!(count <= op.dim());
+
+    let mut deflated = Deflated { op, pairs: vec![] };
+    let mut results = vec![];
+    for _ in 0..count {
+        // start orthogonal to the known eigenvectors
+        let mut x = x0.to_vec();
+        for (_, v) in &deflated.pairs {
+            let c = vector::dot(v, &x);
+            x.iter_mut().zip(v.iter()).for_each(|(x, v)| *x -= c * *v);
+        }
+        if vector::norm2(&x) == F64::ZERO {
+            break;
+        }
+
+        let result = power_iteration(&deflated, &x, options);
+        let converged = result.converged;
+        deflated
+            .pairs
+            .push((result.eigenvalue, result.eigenvector.clone()));
+        results.push(result);
+        if !converged {
+            break;
+        }
+    }
+    results
+}
@@ -3,17 +3,137 @@ use std::fmt::{Debug, Display};
 
 use floating_point::F64;
 
-use crate::{Matrix, TriFullMat, TriangleMatType};
+use crate::{blocked_lu::LU_BLOCK_SIZE, Matrix, MatrixError, TriFullMat, TriangleMatType};
+
+// L, U and the row permutation p of P A = L U
+pub(crate) type LuFactors = (TriFullMat<F64>, TriFullMat<F64>, Vec<usize>);
+
+// How the entries of a `FullMat` are laid out in its storage. Row-major is
+// what the constructors build; column-major is the order of Fortran, LAPACK
+// and NumPy arrays with fortran_order. Any other strides, e.g. a leading
+// dimension larger than the matrix, are `Strided`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    RowMajor,
+    ColMajor,
+    Strided,
+}
 
+// Entry (i, j) is at i * row_stride + j * col_stride in `storage`.
 #[derive(Clone, Debug)]
 pub struct FullMat<T> {
     storage: Vec<T>,
+    row_count: usize,
     col_count: usize,
+    row_stride: usize,
+    col_stride: usize,
 }
 
+// side of the tiles copied at once when changing the layout, so that both the
+// reads and the writes stay within a few cache lines per row
+const RELAYOUT_TILE: usize = 32;
+
 impl<T> FullMat<T> {
     fn index_in_vec(&self, index: (usize, usize)) -> usize {
-        index.0 * self.col_count + index.1
+        debug_assert!(index.0 < self.row_count && index.1 < self.col_count);
+        index.0 * self.row_stride + index.1 * self.col_stride
+    }
+
+    // the storage, with the entries at `strides`
+    pub(crate) fn as_slice(&self) -> &[T] {
+        &self.storage
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.storage
+    }
+
+    // Row- and column-major mean the storage holds exactly the entries, so
+    // a leading dimension larger than the matrix makes it strided.
+    pub fn layout(&self) -> Layout {
+        if self.storage.len() != self.row_count * self.col_count {
+            Layout::Strided
+        } else if self.row_stride == self.col_count && self.col_stride == 1 {
+            Layout::RowMajor
+        } else if self.row_stride == 1 && self.col_stride == self.row_count {
+            Layout::ColMajor
+        } else {
+            Layout::Strided
+        }
+    }
+
+    // (row_stride, col_stride): the distances in the storage between
+    // neighbours in a column and in a row
+    pub fn strides(&self) -> (usize, usize) {
+        (self.row_stride, self.col_stride)
+    }
+
+    // The transpose without moving any entry, only the strides are swapped,
+    // so a row-major matrix becomes a column-major one and vice versa.
+    pub fn into_transpose(self) -> Self {
+        Self {
+            row_count: self.col_count,
+            col_count: self.row_count,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+            storage: self.storage,
+        }
+    }
+
+    pub fn row_iter(&self, i: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(i < self.row_count);
+        self.storage[i * self.row_stride..]
+            .iter()
+            .step_by(self.col_stride)
+            .take(self.col_count)
+    }
+
+    pub fn col_iter(&self, j: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(j < self.col_count);
+        self.storage[j * self.col_stride..]
+            .iter()
+            .step_by(self.row_stride)
+            .take(self.row_count)
+    }
+
+    // all entries, row after row, whatever the layout
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.row_count).flat_map(move |i| self.row_iter(i))
+    }
+
+    // Swaps `count` entries `stride` apart starting at `a` with those starting
+    // at `b`, which cover disjoint rows or columns.
+    fn swap_strided(&mut self, a: usize, b: usize, stride: usize, count: usize) {
+        if a == b {
+            return;
+        }
+        if stride == 1 {
+            let (low, high) = (a.min(b), a.max(b));
+            let (left, right) = self.storage.split_at_mut(high);
+            left[low..low + count].swap_with_slice(&mut right[..count]);
+        } else {
+            for k in 0..count {
+                self.storage.swap(a + k * stride, b + k * stride);
+            }
+        }
+    }
+
+    pub fn swap(&mut self, index_a: (usize, usize), index_b: (usize, usize)) {
+        let ia = self.index_in_vec(index_a);
+        let ib = self.index_in_vec(index_b);
+        self.storage.swap(ia, ib)
+    }
+
+    pub fn swap_row(&mut self, row_a: usize, row_b: usize) {
+        assert!(row_a < self.row_count && row_b < self.row_count);
+        let (rs, cs) = self.strides();
+        self.swap_strided(row_a * rs, row_b * rs, cs, self.col_count);
+    }
+
+    pub fn swap_col(&mut self, col_a: usize, col_b: usize) {
+        assert!(col_a < self.col_count && col_b < self.col_count);
+        let (rs, cs) = self.strides();
+        self.swap_strided(col_a * cs, col_b * cs, rs, self.row_count);
     }
 }
 
@@ -27,10 +147,12 @@ impl<T: Clone> FullMat<T> {
             assert!(row.len() == col_count);
         }
 
-        Self {
-            col_count,
-            storage: rows.concat(),
-        }
+        Self::from_vec(col_count, rows.concat())
+    }
+
+    // the column-major counterpart of `from_rows`, without a transpose
+    pub fn from_cols(cols: Vec<Vec<T>>) -> Self {
+        Self::from_rows(cols).into_transpose()
     }
 
     pub fn from_vec(col_count: usize, elements: Vec<T>) -> Self {
@@ -38,37 +160,206 @@ impl<T: Clone> FullMat<T> {
         assert!(!elements.is_empty());
         assert!(elements.len() % col_count == 0);
         Self {
+            row_count: elements.len() / col_count,
             col_count,
+            row_stride: col_count,
+            col_stride: 1,
+            storage: elements,
+        }
+    }
+
+    // elements column after column, as Fortran stores them
+    pub fn from_vec_col_major(row_count: usize, elements: Vec<T>) -> Self {
+        Self::from_vec(row_count, elements).into_transpose()
+    }
+
+    // Entry (i, j) of the m x n matrix is elements[i * strides.0 + j *
+    // strides.1]. One stride has to step over a whole row or column of the
+    // other, e.g. (1, lda) for a Fortran array with leading dimension lda >= m,
+    // so no two entries share an element.
+    pub fn from_vec_strided(
+        (m, n): (usize, usize),
+        (row_stride, col_stride): (usize, usize),
+        elements: Vec<T>,
+    ) -> Self {
+        assert!(m > 0 && n > 0);
+        assert!(row_stride > 0 && col_stride > 0);
+        assert!(row_stride >= n * col_stride || col_stride >= m * row_stride);
+        assert!((m - 1) * row_stride + (n - 1) * col_stride < elements.len());
+        Self {
             storage: elements,
+            row_count: m,
+            col_count: n,
+            row_stride,
+            col_stride,
         }
     }
+
+    // Fails on an empty input or a ragged row, reported as the shape of the
+    // rows accepted so far against the shape of the offending row.
+    pub fn try_from_rows(rows: Vec<Vec<T>>) -> Result<Self, MatrixError> {
+        if rows.is_empty() || rows[0].is_empty() {
+            return Err(MatrixError::EmptyInput);
+        }
+        let col_count = rows[0].len();
+        for (i, row) in rows.iter().enumerate().skip(1) {
+            if row.len() != col_count {
+                return Err(MatrixError::ShapeMismatch {
+                    left: (i, col_count),
+                    right: (1, row.len()),
+                });
+            }
+        }
+        Ok(Self::from_rows(rows))
+    }
+
+    // Fails on an empty input, or if the elements leave a partial last row,
+    // reported as the shape of a full row against that of the partial one.
+    pub fn try_from_vec(col_count: usize, elements: Vec<T>) -> Result<Self, MatrixError> {
+        if col_count == 0 || elements.is_empty() {
+            return Err(MatrixError::EmptyInput);
+        }
+        let rest = elements.len() % col_count;
+        if rest != 0 {
+            return Err(MatrixError::ShapeMismatch {
+                left: (1, col_count),
+                right: (1, rest),
+            });
+        }
+        Ok(Self::from_vec(col_count, elements))
+    }
 }
 
 impl<T: Copy> FullMat<T> {
-    pub fn swap(&mut self, index_a: (usize, usize), index_b: (usize, usize)) {
-        let ia = self.index_in_vec(index_a);
-        let ib = self.index_in_vec(index_b);
-        self.storage.swap(ia, ib)
+    // the entries in row-major order if `col_major` is false, else in
+    // column-major order, copied tile by tile
+    fn relayout(&self, col_major: bool) -> Vec<T> {
+        let (m, n) = (self.row_count, self.col_count);
+        let (outer, inner) = if col_major { (n, m) } else { (m, n) };
+        let mut v = vec![self[(0, 0)]; m * n];
+        for outer0 in (0..outer).step_by(RELAYOUT_TILE) {
+            let outer1 = (outer0 + RELAYOUT_TILE).min(outer);
+            for inner0 in (0..inner).step_by(RELAYOUT_TILE) {
+                let inner1 = (inner0 + RELAYOUT_TILE).min(inner);
+                for o in outer0..outer1 {
+                    for i in inner0..inner1 {
+                        let (row, col) = if col_major { (i, o) } else { (o, i) };
+                        v[o * inner + i] = self[(row, col)];
+                    }
+                }
+            }
+        }
+        v
     }
 
-    pub fn swap_row(&mut self, row_a: usize, row_b: usize) {
-        if row_a != row_b {
-            let col_count = self.col_count;
-            let min_row = row_a.min(row_b);
-            let max_row = row_a.max(row_b);
-            let left_count = (min_row + 1) * col_count;
-            let (left, right) = self.storage.split_at_mut(left_count);
-            let left_start = min_row * col_count;
-            let right_start = (max_row - min_row - 1) * col_count;
-            left[left_start..].swap_with_slice(&mut right[right_start..(right_start + col_count)])
+    pub fn to_row_major(&self) -> Self {
+        match self.layout() {
+            Layout::RowMajor => self.clone(),
+            _ => Self::from_vec(self.col_count, self.relayout(false)),
+        }
+    }
+
+    pub fn to_col_major(&self) -> Self {
+        match self.layout() {
+            Layout::ColMajor => self.clone(),
+            _ => Self::from_vec_col_major(self.row_count, self.relayout(true)),
+        }
+    }
+
+    // no copy if the matrix is row-major already
+    pub fn into_row_major(self) -> Self {
+        match self.layout() {
+            Layout::RowMajor => self,
+            _ => self.to_row_major(),
+        }
+    }
+
+    pub fn into_col_major(self) -> Self {
+        match self.layout() {
+            Layout::ColMajor => self,
+            _ => self.to_col_major(),
+        }
+    }
+
+    // a copy in the layout of `self`, row-major for strided matrices
+    pub fn transpose(&self) -> Self {
+        let transpose = self.clone().into_transpose();
+        match self.layout() {
+            Layout::ColMajor => transpose.into_col_major(),
+            _ => transpose.into_row_major(),
+        }
+    }
+
+    pub(crate) fn into_row_major_vec(self) -> Vec<T> {
+        self.into_row_major().storage
+    }
+
+    pub fn col(&self, j: usize) -> Vec<T> {
+        self.col_iter(j).copied().collect()
+    }
+
+    // f applied to the entries of `self` and `rhs` at the same position, laid
+    // out like `self`, or row-major if that is strided
+    fn zip_map<F: Fn(T, T) -> T>(&self, rhs: &Self, f: F) -> Self {
+        let (m, n) = (self.row_count, self.col_count);
+        assert!((rhs.row_count, rhs.col_count) == (m, n));
+        let layout = self.layout();
+        if layout != Layout::Strided && self.strides() == rhs.strides() {
+            let storage = self
+                .storage
+                .iter()
+                .zip(&rhs.storage)
+                .map(|(x, y)| f(*x, *y))
+                .collect();
+            return Self { storage, ..*self };
+        }
+        let entry = |i, j| f(self[(i, j)], rhs[(i, j)]);
+        if layout == Layout::ColMajor {
+            let v = (0..n).flat_map(|j| (0..m).map(move |i| entry(i, j)));
+            Self::from_vec_col_major(m, v.collect())
+        } else {
+            let v = (0..m).flat_map(|i| (0..n).map(move |j| entry(i, j)));
+            Self::from_vec(n, v.collect())
         }
     }
 }
 
 impl FullMat<F64> {
+    pub fn zeros(row_count: usize, col_count: usize) -> Self {
+        Self::from_vec(col_count, vec![F64::ZERO; row_count * col_count])
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut mat = Self::zeros(n, n);
+        for i in 0..n {
+            mat[(i, i)] = F64::ONE;
+        }
+        mat
+    }
+
+    pub fn try_mul_mat(&self, rhs: &FullMat<F64>) -> Result<FullMat<F64>, MatrixError> {
+        if rhs.row_count() != self.col_count() {
+            return Err(MatrixError::ShapeMismatch {
+                left: self.shape(),
+                right: rhs.shape(),
+            });
+        }
+        Ok(self.mul_mat(rhs))
+    }
+
+    // blocked and packed, see `gemm`
     pub fn mul_mat(&self, rhs: &FullMat<F64>) -> FullMat<F64> {
         assert!(rhs.row_count() == self.col_count());
 
+        let mut product = FullMat::zeros(self.row_count(), rhs.col_count());
+        product.gemm(F64::ONE, self, rhs, F64::ZERO);
+        product
+    }
+
+    // the textbook triple loop, kept as a reference for tests and benchmarks
+    pub fn mul_mat_naive(&self, rhs: &FullMat<F64>) -> FullMat<F64> {
+        assert!(rhs.row_count() == self.col_count());
+
         let mut v = vec![];
         for i in 0..self.row_count() {
             for j in 0..rhs.col_count() {
@@ -83,7 +374,7 @@ impl FullMat<F64> {
         FullMat::from_vec(rhs.col_count(), v)
     }
 
-    pub fn mul_vec(&self, rhs: &Vec<F64>) -> Vec<F64> {
+    pub fn mul_vec(&self, rhs: &[F64]) -> Vec<F64> {
         assert!(self.col_count() == rhs.len());
 
         let mut v = vec![];
@@ -101,35 +392,33 @@ impl FullMat<F64> {
     pub fn add(&self, rhs: &FullMat<F64>) -> Self {
         assert!(self.shape() == rhs.shape());
 
-        let v = self
-            .storage
-            .iter()
-            .zip(rhs.storage.iter())
-            .map(|(x, y)| x + y)
-            .collect();
-
-        Self::from_vec(self.col_count(), v)
+        self.zip_map(rhs, |x, y| x + y)
     }
 
     pub fn sub(&self, rhs: &FullMat<F64>) -> Self {
         assert!(self.shape() == rhs.shape());
 
-        let v = self
-            .storage
-            .iter()
-            .zip(rhs.storage.iter())
-            .map(|(x, y)| x - y)
-            .collect();
-
-        Self::from_vec(self.col_count(), v)
+        self.zip_map(rhs, |x, y| x - y)
     }
 
     pub fn element_max_abs(&self) -> F64 {
-        self.storage.iter().fold(0.0.into(), |max, x| max.max(*x))
+        self.iter().fold(0.0.into(), |max, x| max.max(x.abs()))
     }
 
     pub fn lu(&self) -> Option<(TriFullMat<F64>, TriFullMat<F64>, Vec<usize>)> {
         assert!(self.is_square());
+        self.try_lu().ok()
+    }
+
+    // blocked, see `lu_blocked`
+    pub fn try_lu(&self) -> Result<LuFactors, MatrixError> {
+        self.try_lu_blocked(LU_BLOCK_SIZE)
+    }
+
+    // element by element elimination, kept as a reference for tests and
+    // benchmarks
+    pub fn lu_unblocked(&self) -> Option<LuFactors> {
+        assert!(self.is_square());
 
         let mut mat = self.clone();
         let n = mat.col_count();
@@ -184,61 +473,55 @@ impl FullMat<F64> {
         ))
     }
 
-    pub fn lu_solve(&self, b: &Vec<F64>) -> Option<Vec<F64>> {
+    pub fn lu_solve(&self, b: &[F64]) -> Option<Vec<F64>> {
         assert!(self.is_square());
         assert!(b.len() == self.col_count());
+        self.try_lu_solve(b).ok()
+    }
 
-        if let Some((l, u, p)) = self.lu() {
-            let pb: Vec<F64> = p.into_iter().map(|i| b[i]).collect();
-            let y = l.solve(pb);
-            let y = u.solve(y);
-            Some(y)
-        } else {
-            None
+    pub fn try_lu_solve(&self, b: &[F64]) -> Result<Vec<F64>, MatrixError> {
+        if b.len() != self.row_count() {
+            return Err(MatrixError::ShapeMismatch {
+                left: self.shape(),
+                right: (b.len(), 1),
+            });
         }
+        let (l, u, p) = self.try_lu()?;
+        let pb: Vec<F64> = p.into_iter().map(|i| b[i]).collect();
+        Ok(u.solve(l.solve(pb)))
     }
 
     pub fn inv(&self) -> Option<Self> {
         assert!(self.is_square());
+        self.try_inv().ok()
+    }
 
-        if let Some((l, u, p)) = self.lu() {
-            let mut cols = vec![];
+    pub fn try_inv(&self) -> Result<Self, MatrixError> {
+        let (l, u, p) = self.try_lu()?;
+        let mut cols = vec![];
 
-            for i in 0..self.col_count() {
-                let mut b = vec![F64::from(0.0); self.col_count()];
-                b[i] = 1.0.into();
-                let pb: Vec<F64> = p.iter().map(|i| b[*i]).collect();
-                let y = l.solve(pb);
-                let y = u.solve(y);
-                cols.push(y);
-            }
+        for i in 0..self.col_count() {
+            let mut b = vec![F64::from(0.0); self.col_count()];
+            b[i] = 1.0.into();
+            let pb: Vec<F64> = p.iter().map(|i| b[*i]).collect();
+            let y = l.solve(pb);
+            let y = u.solve(y);
+            cols.push(y);
+        }
 
-            let mut v = vec![];
-            for i in 0..self.col_count() {
-                for col in &cols {
-                    v.push(col[i]);
-                }
+        let mut v = vec![];
+        for i in 0..self.col_count() {
+            for col in &cols {
+                v.push(col[i]);
             }
-
-            Some(Self::from_vec(self.col_count(), v))
-        } else {
-            None
         }
+
+        Ok(Self::from_vec(self.col_count(), v))
     }
 
+    // the 1-norm, see `norm1`
     pub fn norm(&self) -> F64 {
-        assert!(self.is_square());
-
-        let mut col_sums = vec![];
-        for j in 0..self.col_count() {
-            let mut sum = 0.0.into();
-            for i in 0..self.col_count() {
-                sum += self[(i, j)].abs();
-            }
-            col_sums.push(sum);
-        }
-
-        col_sums.into_iter().fold(0.0.into(), |max, x| max.max(x))
+        self.norm1()
     }
 }
 
@@ -258,16 +541,7 @@ impl<T> IndexMut<(usize, usize)> for FullMat<T> {
 
 impl<T: Display> Matrix<T> for FullMat<T> {
     fn shape(&self) -> (usize, usize) {
-        debug_assert!(self.storage.len() % self.col_count == 0);
-        (self.storage.len() / self.col_count, self.col_count)
-    }
-
-    fn col_count(&self) -> usize {
-        self.col_count
-    }
-
-    fn row_count(&self) -> usize {
-        self.storage.len() / self.col_count()
+        (self.row_count, self.col_count)
     }
 }
 
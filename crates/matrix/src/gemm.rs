@@ -0,0 +1,247 @@
+// General matrix multiplication C = alpha A B + beta C in the style of
+// GotoBLAS and BLIS. B is packed into panels NR columns wide and A into panels
+// MR rows tall, laid out in the order the micro-kernel reads them, so the
+// innermost loop streams through contiguous memory. The blocks are sized for
+// the caches: a KC x NR sliver of B stays in L1 while the micro-kernel sweeps
+// over an MC x KC block of A in L2, and a KC x NC panel of B is reused from L3
+// for all row blocks. The micro-kernel keeps an MR x NR block of C in
+// registers.
+//
+// With the `parallel` feature the MC row blocks of C are computed on the rayon
+// thread pool. Each task packs its own block of A and reads the shared packed
+// panel of B.
+
+use floating_point::F64;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{FullMat, Layout, Matrix};
+
+const MR: usize = 4;
+const NR: usize = 8;
+const KC: usize = 256;
+const MC: usize = 128;
+const NC: usize = 4096;
+
+// below this many multiply-adds the packing does not pay off
+const SMALL: usize = 32 * 32 * 32;
+
+// position of a KC x NC panel of B
+#[derive(Clone, Copy)]
+struct Panel {
+    pc: usize,
+    kc: usize,
+    jc: usize,
+    nc: usize,
+}
+
+// C += alpha A B for A (m x k) and B (k x n) with entry (i, j) at
+// i * strides.0 + j * strides.1, so blocks of larger matrices and any layout
+// work too. C is row-major with rows `ldc` apart.
+pub(crate) struct Gemm<'a> {
+    pub(crate) a: &'a [F64],
+    pub(crate) a_strides: (usize, usize),
+    pub(crate) b: &'a [F64],
+    pub(crate) b_strides: (usize, usize),
+    pub(crate) m: usize,
+    pub(crate) k: usize,
+    pub(crate) n: usize,
+    pub(crate) alpha: F64,
+}
+
+// B[pc..pc + kc, jc..jc + nc] as panels of kc rows of NR entries, the last
+// one padded with zeros
+fn pack_b(b: &[F64], (rs, cs): (usize, usize), panel: Panel, packed: &mut Vec<f64>) {
+    packed.clear();
+    for jr in (0..panel.nc).step_by(NR) {
+        let nr = NR.min(panel.nc - jr);
+        for p in panel.pc..panel.pc + panel.kc {
+            let start = p * rs + (panel.jc + jr) * cs;
+            if cs == 1 {
+                packed.extend(b[start..][..nr].iter().map(F64::to_f64));
+            } else {
+                packed.extend((0..nr).map(|j| b[start + j * cs].to_f64()));
+            }
+            packed.extend(std::iter::repeat(0.0).take(NR - nr));
+        }
+    }
+}
+
+// A[ic..ic + mc, pc..pc + kc] as panels of kc columns of MR entries, the
+// last one padded with zeros
+fn pack_a(
+    a: &[F64],
+    (rs, cs): (usize, usize),
+    ic: usize,
+    mc: usize,
+    panel: Panel,
+    packed: &mut Vec<f64>,
+) {
+    packed.clear();
+    for ir in (ic..ic + mc).step_by(MR) {
+        let mr = MR.min(ic + mc - ir);
+        for p in panel.pc..panel.pc + panel.kc {
+            packed.extend((ir..ir + mr).map(|i| a[i * rs + p * cs].to_f64()));
+            packed.extend(std::iter::repeat(0.0).take(MR - mr));
+        }
+    }
+}
+
+// The product of an MR-row panel of A and an NR-column panel of B. The fixed
+// sizes let the compiler unroll both inner loops and keep `acc` in vector
+// registers.
+#[inline(always)]
+fn kernel(a: &[f64], b: &[f64]) -> [[f64; NR]; MR] {
+    let mut acc = [[0.0; NR]; MR];
+    for (a, b) in a.chunks_exact(MR).zip(b.chunks_exact(NR)) {
+        for (acc_row, a_i) in acc.iter_mut().zip(a) {
+            for (acc_ij, b_j) in acc_row.iter_mut().zip(b) {
+                *acc_ij += a_i * b_j;
+            }
+        }
+    }
+    acc
+}
+
+impl Gemm<'_> {
+    // `c` holds C from row ic on, rows `ldc` apart.
+    fn block(
+        &self,
+        panel: Panel,
+        packed_b: &[f64],
+        ic: usize,
+        packed_a: &mut Vec<f64>,
+        c: &mut [F64],
+        ldc: usize,
+    ) {
+        let mc = MC.min(self.m - ic);
+        pack_a(self.a, self.a_strides, ic, mc, panel, packed_a);
+        for (jr, b_panel) in packed_b.chunks_exact(panel.kc * NR).enumerate() {
+            let (jr, nr) = (jr * NR, NR.min(panel.nc - jr * NR));
+            for (ir, a_panel) in packed_a.chunks_exact(panel.kc * MR).enumerate() {
+                let (ir, mr) = (ir * MR, MR.min(mc - ir * MR));
+                let acc = kernel(a_panel, b_panel);
+                for (i, acc_row) in acc.iter().enumerate().take(mr) {
+                    let row = &mut c[(ir + i) * ldc + panel.jc + jr..][..nr];
+                    for (c_ij, acc_ij) in row.iter_mut().zip(acc_row) {
+                        *c_ij += self.alpha * F64::from(*acc_ij);
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn run(&self, c: &mut [F64], ldc: usize) {
+        if self.m * self.n * self.k <= SMALL {
+            return self.run_small(c, ldc);
+        }
+        let mut packed_b = Vec::with_capacity(KC * NC);
+        #[cfg(not(feature = "parallel"))]
+        let mut packed_a = Vec::with_capacity(MC * KC);
+        for jc in (0..self.n).step_by(NC) {
+            for pc in (0..self.k).step_by(KC) {
+                let panel = Panel {
+                    pc,
+                    kc: KC.min(self.k - pc),
+                    jc,
+                    nc: NC.min(self.n - jc),
+                };
+                pack_b(self.b, self.b_strides, panel, &mut packed_b);
+
+                #[cfg(feature = "parallel")]
+                c.par_chunks_mut(MC * ldc).enumerate().for_each_init(
+                    Vec::new,
+                    |packed_a, (block, c)| {
+                        self.block(panel, &packed_b, block * MC, packed_a, c, ldc)
+                    },
+                );
+                #[cfg(not(feature = "parallel"))]
+                for (block, c) in c.chunks_mut(MC * ldc).enumerate() {
+                    self.block(panel, &packed_b, block * MC, &mut packed_a, c, ldc);
+                }
+            }
+        }
+    }
+
+    // i-k-j loops without packing for small products
+    fn run_small(&self, c: &mut [F64], ldc: usize) {
+        let ((rsa, csa), (rsb, csb)) = (self.a_strides, self.b_strides);
+        for i in 0..self.m {
+            let c_row = &mut c[i * ldc..][..self.n];
+            for p in 0..self.k {
+                let a_ip = self.alpha * self.a[i * rsa + p * csa];
+                let b_row = self.b[p * rsb..].iter().step_by(csb);
+                for (c_ij, b_pj) in c_row.iter_mut().zip(b_row) {
+                    *c_ij += a_ip * *b_pj;
+                }
+            }
+        }
+    }
+}
+
+impl FullMat<F64> {
+    // C = alpha A B + beta C, with C = self, for any layouts. As in BLAS, C is
+    // not read when beta is zero, so NaNs in it do not propagate.
+    pub fn gemm(&mut self, alpha: F64, a: &FullMat<F64>, b: &FullMat<F64>, beta: F64) {
+        let ((m, k), n) = (a.shape(), b.col_count());
+        assert!(b.row_count() == k);
+        assert!(self.shape() == (m, n));
+
+        let scale = |c_ij: &mut F64| {
+            *c_ij = if beta == F64::ZERO {
+                F64::ZERO
+            } else {
+                beta * *c_ij
+            }
+        };
+        if self.layout() == Layout::Strided {
+            for i in 0..m {
+                for j in 0..n {
+                    scale(&mut self[(i, j)]);
+                }
+            }
+        } else {
+            self.as_mut_slice().iter_mut().for_each(scale);
+        }
+        let (rs, cs) = self.strides();
+        let (a_strides, b_strides) = (a.strides(), b.strides());
+        // the storage past the last row or column, if the leading dimension
+        // is larger than the matrix, is not handed to the kernel
+        let len = self.as_slice().len();
+        if cs == 1 {
+            Gemm {
+                a: a.as_slice(),
+                a_strides,
+                b: b.as_slice(),
+                b_strides,
+                m,
+                k,
+                n,
+                alpha,
+            }
+            .run(&mut self.as_mut_slice()[..(m * rs).min(len)], rs);
+        } else if rs == 1 {
+            // C^T = B^T A^T, with C^T row-major
+            let swap = |(r, c): (usize, usize)| (c, r);
+            Gemm {
+                a: b.as_slice(),
+                a_strides: swap(b_strides),
+                b: a.as_slice(),
+                b_strides: swap(a_strides),
+                m: n,
+                k,
+                n: m,
+                alpha,
+            }
+            .run(&mut self.as_mut_slice()[..(n * cs).min(len)], cs);
+        } else {
+            let mut c = self.to_row_major();
+            c.gemm(alpha, a, b, F64::ONE);
+            for i in 0..m {
+                for j in 0..n {
+                    self[(i, j)] = c[(i, j)];
+                }
+            }
+        }
+    }
+}
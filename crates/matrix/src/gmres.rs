@@ -0,0 +1,264 @@
+use floating_point::{Complex64, F64};
+
+use crate::{
+    vector, ConvergenceReason, FullMat, KrylovResult, LinearOperator, Matrix, Preconditioner,
+    StoppingCriterion,
+};
+
+// Arnoldi factorization A V_k = V_{k+1} H_k, where the columns of V_{k+1}
+// are orthonormal and H_k is (k + 1) x k upper Hessenberg.
+#[derive(Debug, Clone)]
+pub struct Arnoldi {
+    pub basis: Vec<Vec<F64>>,
+    pub hessenberg: FullMat<F64>,
+}
+
+impl Arnoldi {
+    pub fn steps(&self) -> usize {
+        self.hessenberg.col_count()
+    }
+
+    // Eigenvalues of the leading square k x k part of H_k, which approximate
+    // the extremal eigenvalues of A.
+    pub fn ritz_values(&self) -> Option<Vec<Complex64>> {
+        let k = self.steps();
+        let mut h = FullMat::zeros(k, k);
+        for i in 0..k {
+            for j in 0..k {
+                h[(i, j)] = self.hessenberg[(i, j)];
+            }
+        }
+        h.eigenvalues()
+    }
+}
+
+// Modified Gram-Schmidt step with one reorthogonalization pass when there is
+// heavy cancellation. Orthogonalizes `w` against the basis and returns the new
+// Hessenberg column, whose last entry is the norm of what is left of `w`, and
+// whether the Krylov space has become numerically invariant.
+fn orthogonalize(basis: &[Vec<F64>], w: &mut [F64]) -> (Vec<F64>, bool) {
+    let w_norm = vector::norm2(w);
+    let mut h = vec![F64::ZERO; basis.len() + 1];
+    for pass in 0..2 {
+        for (hi, v) in h.iter_mut().zip(basis.iter()) {
+            let c = vector::dot(w, v);
+            vector::axpy(-c, v, w);
+            *hi += c;
+        }
+        h[basis.len()] = vector::norm2(w);
+        if pass == 0 && h[basis.len()] > F64::from(0.5f64.sqrt()) * w_norm {
+            break;
+        }
+    }
+    let breakdown = h[basis.len()] <= F64::EPSILON * w_norm;
+    if breakdown {
+        h[basis.len()] = F64::ZERO;
+    }
+    (h, breakdown)
+}
+
+// Runs `steps` steps of the Arnoldi process from `v0`, stopping early if the
+// Krylov space becomes invariant.
+pub fn arnoldi<A: LinearOperator>(a: &A, v0: &[F64], steps: usize) -> Arnoldi {
+    assert!(a.dim() == v0.len());
+    assert!(steps > 0);
+
+    let norm = vector::norm2(v0);
+    assert!(norm != F64::ZERO);
+    let mut basis = vec![v0.iter().map(|x| *x / norm).collect::<Vec<_>>()];
+    let mut cols: Vec<Vec<F64>> = vec![];
+
+    for _ in 0..steps {
+        let mut w = a.apply(&basis[basis.len() - 1]);
+        let (h, breakdown) = orthogonalize(&basis, &mut w);
+        let h_next = h[h.len() - 1];
+        cols.push(h);
+        if breakdown {
+            break;
+        }
+        basis.push(w.into_iter().map(|x| x / h_next).collect());
+    }
+
+    let k = cols.len();
+    let mut hessenberg = FullMat::zeros(k + 1, k);
+    for (j, col) in cols.iter().enumerate() {
+        for (i, x) in col.iter().enumerate() {
+            hessenberg[(i, j)] = *x;
+        }
+    }
+    if basis.len() == k {
+        // invariant subspace: the last row of H is zero and there is no new
+        // basis vector
+        basis.push(vec![F64::ZERO; v0.len()]);
+    }
+
+    Arnoldi { basis, hessenberg }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreconditionSide {
+    // solves M^-1 A x = M^-1 b, the residual norms are those of M^-1 r
+    Left,
+    // solves A M^-1 y = b with x = M^-1 y, the residual norms are the true ones
+    Right,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GmresOptions {
+    // Krylov dimension before a restart
+    pub restart: usize,
+    pub side: PreconditionSide,
+}
+
+impl Default for GmresOptions {
+    fn default() -> Self {
+        Self {
+            restart: 30,
+            side: PreconditionSide::Right,
+        }
+    }
+}
+
+fn givens(a: F64, b: F64) -> (F64, F64) {
+    if b == F64::ZERO {
+        (F64::ONE, F64::ZERO)
+    } else {
+        let r = a.hypot(b);
+        (a / r, b / r)
+    }
+}
+
+// Restarted GMRES(m) with modified Gram-Schmidt Arnoldi and Givens rotations
+// for the small least squares problems. The iteration count in the result
+// counts inner steps over all restarts.
+pub fn gmres<A: LinearOperator, M: Preconditioner>(
+    a: &A,
+    b: &[F64],
+    x0: &[F64],
+    preconditioner: &M,
+    options: &GmresOptions,
+    criterion: &StoppingCriterion,
+) -> KrylovResult {
+    let n = a.dim();
+    assert!(b.len() == n && x0.len() == n);
+    assert!(options.restart > 0);
+
+    let left = options.side == PreconditionSide::Left;
+    let op = |v: &[F64]| {
+        if left {
+            preconditioner.solve(&a.apply(v))
+        } else {
+            a.apply(&preconditioner.solve(v))
+        }
+    };
+    let b_norm = if left {
+        vector::norm2(&preconditioner.solve(b))
+    } else {
+        vector::norm2(b)
+    };
+
+    let residual_of = |x: &[F64]| {
+        let r = vector::sub(b, &a.apply(x));
+        if left {
+            preconditioner.solve(&r)
+        } else {
+            r
+        }
+    };
+
+    let mut result = KrylovResult::new(x0.to_vec());
+    loop {
+        let r = residual_of(&result.x);
+        let beta = vector::norm2(&r);
+        if criterion.is_satisfied(beta, b_norm) {
+            result.reason = ConvergenceReason::Converged;
+            break;
+        }
+        if result.iterations >= criterion.max_iterations {
+            break;
+        }
+
+        let mut basis = vec![r.into_iter().map(|x| x / beta).collect::<Vec<_>>()];
+        let mut cols: Vec<Vec<F64>> = vec![];
+        let mut rotations: Vec<(F64, F64)> = vec![];
+        let mut g = vec![beta];
+        let mut done = false;
+        let mut broke_down = false;
+
+        while cols.len() < options.restart && result.iterations < criterion.max_iterations {
+            let j = cols.len();
+            let mut w = op(&basis[j]);
+            let (mut h, breakdown) = orthogonalize(&basis, &mut w);
+            let h_next = h[j + 1];
+
+            for (i, (c, s)) in rotations.iter().enumerate() {
+                let (x, y) = (h[i], h[i + 1]);
+                h[i] = *c * x + *s * y;
+                h[i + 1] = *c * y - *s * x;
+            }
+            if breakdown && h[j] == F64::ZERO {
+                // A is singular on the invariant Krylov space: the new
+                // direction does not reduce the residual and would put a
+                // zero on the diagonal of R, so it is dropped
+                result.record(g[j].abs());
+                broke_down = true;
+                break;
+            }
+            let (c, s) = givens(h[j], h[j + 1]);
+            h[j] = c * h[j] + s * h[j + 1];
+            h[j + 1] = F64::ZERO;
+            rotations.push((c, s));
+            g.push(-s * g[j]);
+            g[j] *= c;
+            cols.push(h);
+
+            let residual = g[j + 1].abs();
+            result.record(residual);
+            if criterion.is_satisfied(residual, b_norm) {
+                done = true;
+                break;
+            }
+            if breakdown {
+                broke_down = true;
+                break;
+            }
+            basis.push(w.into_iter().map(|x| x / h_next).collect());
+        }
+
+        // back substitution for the upper triangular least squares problem
+        let k = cols.len();
+        let mut y = vec![F64::ZERO; k];
+        for i in (0..k).rev() {
+            let mut sum = g[i];
+            for l in (i + 1)..k {
+                sum -= cols[l][i] * y[l];
+            }
+            y[i] = sum / cols[i][i];
+        }
+        let mut update = vec![F64::ZERO; n];
+        for (v, yi) in basis.iter().zip(y.iter()) {
+            vector::axpy(*yi, v, &mut update);
+        }
+        if !left {
+            update = preconditioner.solve(&update);
+        }
+        vector::axpy(F64::ONE, &update, &mut result.x);
+
+        if done {
+            result.reason = ConvergenceReason::Converged;
+            break;
+        }
+        if broke_down {
+            // restarting would only break down again, so the true residual
+            // decides
+            let r = residual_of(&result.x);
+            result.reason = if criterion.is_satisfied(vector::norm2(&r), b_norm) {
+                ConvergenceReason::Converged
+            } else {
+                ConvergenceReason::Breakdown
+            };
+            break;
+        }
+    }
+    result
+}
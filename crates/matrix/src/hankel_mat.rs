@@ -0,0 +1,105 @@
+use core::ops::Index;
+use std::fmt::Display;
+
+use floating_point::F64;
+
+use crate::{FullMat, LinearOperator, Matrix, ToeplitzMat};
+
+// Square matrix that is constant along its antidiagonals: the entry (i, j)
+// is `values[i + j]`, so `values` is the first row followed by the rest of
+// the last column.
+#[derive(Debug, Clone)]
+pub struct HankelMat<T> {
+    values: Vec<T>,
+}
+
+impl<T: Clone> HankelMat<T> {
+    pub fn new(values: Vec<T>) -> Self {
+        assert!(values.len() % 2 == 1);
+        Self { values }
+    }
+
+    pub fn from_first_row_and_last_col(first_row: Vec<T>, last_col: Vec<T>) -> Self {
+        assert!(!first_row.is_empty() && first_row.len() == last_col.len());
+        let mut values = first_row;
+        values.extend(last_col.into_iter().skip(1));
+        Self { values }
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+}
+
+impl HankelMat<F64> {
+    pub fn mul_vec(&self, rhs: &[F64]) -> Vec<F64> {
+        let n = self.row_count();
+        assert!(rhs.len() == n);
+        (0..n)
+            .map(|i| {
+                let mut sum = F64::ZERO;
+                for (j, x) in rhs.iter().enumerate() {
+                    sum += self.values[i + j] * *x;
+                }
+                sum
+            })
+            .collect()
+    }
+
+    // Reversing the columns gives the Toeplitz matrix H J, so H x = b is
+    // solved as (H J) (J x) = b by the Levinson recursion.
+    pub fn solve(&self, b: &[F64]) -> Option<Vec<F64>> {
+        let n = self.row_count();
+        let first_col = self.values[(n - 1)..].to_vec();
+        let first_row = self.values[..n].iter().rev().cloned().collect();
+        let mut x = ToeplitzMat::new(first_col, first_row).solve(b)?;
+        x.reverse();
+        Some(x)
+    }
+}
+
+impl Index<(usize, usize)> for HankelMat<F64> {
+    type Output = F64;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        let (i, j) = index;
+        let n = self.row_count();
+        assert!(i < n && j < n);
+        &self.values[i + j]
+    }
+}
+
+impl Matrix<F64> for HankelMat<F64> {
+    fn shape(&self) -> (usize, usize) {
+        let n = self.values.len() / 2 + 1;
+        (n, n)
+    }
+}
+
+impl Display for HankelMat<F64> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(self, f)
+    }
+}
+
+impl From<HankelMat<F64>> for FullMat<F64> {
+    fn from(mat: HankelMat<F64>) -> Self {
+        let n = mat.row_count();
+        let mut full = FullMat::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                full[(i, j)] = mat[(i, j)];
+            }
+        }
+        full
+    }
+}
+
+impl LinearOperator for HankelMat<F64> {
+    fn dim(&self) -> usize {
+        self.row_count()
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        self.mul_vec(x)
+    }
+}
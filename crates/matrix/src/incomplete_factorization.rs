@@ -0,0 +1,283 @@
+use std::collections::BTreeSet;
+
+use floating_point::F64;
+
+use crate::preconditioner::stored_rows;
+use crate::{
+    CooMat, Matrix, Preconditioner, PreconditionerError, RowAccess, SparseTriMat, TriangleMatType,
+};
+
+// Builds the unit lower and the upper triangular factors from rows of
+// (column, value) pairs, the strictly lower part going to L.
+fn split_factors(rows: &[Vec<(usize, F64)>]) -> (SparseTriMat, SparseTriMat) {
+    let n = rows.len();
+    let mut l = CooMat::new(n, n);
+    let mut u = CooMat::new(n, n);
+    for (i, row) in rows.iter().enumerate() {
+        l.push(i, i, F64::ONE);
+        for (j, v) in row.iter() {
+            if *j < i {
+                l.push(i, *j, *v);
+            } else {
+                u.push(i, *j, *v);
+            }
+        }
+    }
+    (
+        SparseTriMat::from_csc(TriangleMatType::Lower, l.to_csc()),
+        SparseTriMat::from_csc(TriangleMatType::Upper, u.to_csc()),
+    )
+}
+
+// Incomplete LU without fill: L U agrees with A on the nonzero pattern of A.
+#[derive(Debug, Clone)]
+pub struct Ilu0Preconditioner {
+    l: SparseTriMat,
+    u: SparseTriMat,
+}
+
+impl Ilu0Preconditioner {
+    // IKJ variant of Gaussian elimination restricted to the pattern of A.
+    pub fn new<A: RowAccess>(a: &A) -> Result<Self, PreconditionerError> {
+        let a = stored_rows(a);
+        let n = a.row_count();
+        let mut rows: Vec<Vec<(usize, F64)>> = (0..n).map(|i| a.row(i).collect()).collect();
+        // position of column j in the current row
+        let mut position = vec![usize::MAX; n];
+
+        for i in 0..n {
+            for (p, (j, _)) in rows[i].iter().enumerate() {
+                position[*j] = p;
+            }
+            let (done, rest) = rows.split_at_mut(i);
+            let row = &mut rest[0];
+            let mut p = 0;
+            while p < row.len() && row[p].0 < i {
+                let k = row[p].0;
+                // row k is finished, so its pivot is known to be nonzero
+                let pivot_pos = done[k].iter().position(|e| e.0 == k).unwrap();
+                let lik = row[p].1 / done[k][pivot_pos].1;
+                row[p].1 = lik;
+                for (j, ukj) in done[k][(pivot_pos + 1)..].iter() {
+                    if position[*j] != usize::MAX {
+                        row[position[*j]].1 -= lik * *ukj;
+                    }
+                }
+                p += 1;
+            }
+            if p == row.len() || row[p].0 != i || row[p].1 == F64::ZERO || !row[p].1.is_finite() {
+                return Err(PreconditionerError::ZeroPivot(i));
+            }
+            for (j, _) in row.iter() {
+                position[*j] = usize::MAX;
+            }
+        }
+
+        let (l, u) = split_factors(&rows);
+        Ok(Self { l, u })
+    }
+
+    pub fn l(&self) -> &SparseTriMat {
+        &self.l
+    }
+
+    pub fn u(&self) -> &SparseTriMat {
+        &self.u
+    }
+}
+
+impl Preconditioner for Ilu0Preconditioner {
+    fn solve(&self, r: &[F64]) -> Vec<F64> {
+        self.u.solve(self.l.solve(r.to_vec()))
+    }
+}
+
+// Keeps the `fill` entries of largest magnitude; entries in `keep` always stay.
+fn keep_largest(entries: &mut Vec<(usize, F64)>, fill: usize, keep: Option<usize>) {
+    let (kept, mut others): (Vec<_>, Vec<_>) = entries.drain(..).partition(|e| Some(e.0) == keep);
+    others.sort_by(|a, b| b.1.abs().to_f64().total_cmp(&a.1.abs().to_f64()));
+    others.truncate(fill);
+    entries.extend(kept);
+    entries.extend(others);
+    entries.sort_unstable_by_key(|e| e.0);
+}
+
+// Saad's dual threshold incomplete LU, ILUT(tau, p). While row i is
+// eliminated, entries below tau times the 2-norm of row i of A are dropped,
+// and at most p entries are kept in each of the L and U parts of the row
+// besides the diagonal.
+#[derive(Debug, Clone)]
+pub struct IlutPreconditioner {
+    l: SparseTriMat,
+    u: SparseTriMat,
+}
+
+impl IlutPreconditioner {
+    pub fn new<A: RowAccess>(
+        a: &A,
+        drop_tolerance: F64,
+        fill: usize,
+    ) -> Result<Self, PreconditionerError> {
+        assert!(drop_tolerance >= F64::ZERO);
+        let a = stored_rows(a);
+        let n = a.row_count();
+        let mut rows: Vec<Vec<(usize, F64)>> = Vec::with_capacity(n);
+        // U part of each finished row, diagonal first
+        let mut upper: Vec<Vec<(usize, F64)>> = Vec::with_capacity(n);
+        let mut w = vec![F64::ZERO; n];
+        let mut touched = vec![false; n];
+
+        for i in 0..n {
+            let mut lower: BTreeSet<usize> = BTreeSet::new();
+            let mut nonzeros = vec![];
+            let mut norm = F64::ZERO;
+            for (j, v) in a.row(i) {
+                w[j] = v;
+                touched[j] = true;
+                nonzeros.push(j);
+                norm += v * v;
+                if j < i {
+                    lower.insert(j);
+                }
+            }
+            let tau = drop_tolerance * norm.sqrt();
+
+            let mut l_row = vec![];
+            while let Some(k) = lower.pop_first() {
+                let wk = w[k] / upper[k][0].1;
+                if wk.abs() < tau {
+                    continue;
+                }
+                l_row.push((k, wk));
+                for (j, ukj) in upper[k][1..].iter() {
+                    if !touched[*j] {
+                        touched[*j] = true;
+                        nonzeros.push(*j);
+                        if *j < i {
+                            lower.insert(*j);
+                        }
+                    }
+                    w[*j] -= wk * *ukj;
+                }
+            }
+
+            let mut u_row: Vec<(usize, F64)> = nonzeros
+                .iter()
+                .filter(|j| **j >= i)
+                .map(|j| (*j, w[*j]))
+                .filter(|(j, v)| *j == i || v.abs() >= tau)
+                .collect();
+            for j in nonzeros {
+                w[j] = F64::ZERO;
+                touched[j] = false;
+            }
+            keep_largest(&mut l_row, fill, None);
+            keep_largest(&mut u_row, fill, Some(i));
+            match u_row.first() {
+                Some((j, v)) if *j == i && *v != F64::ZERO && v.is_finite() => {}
+                _ => return Err(PreconditionerError::ZeroPivot(i)),
+            }
+
+            upper.push(u_row.clone());
+            l_row.extend(u_row);
+            rows.push(l_row);
+        }
+
+        let (l, u) = split_factors(&rows);
+        Ok(Self { l, u })
+    }
+
+    pub fn l(&self) -> &SparseTriMat {
+        &self.l
+    }
+
+    pub fn u(&self) -> &SparseTriMat {
+        &self.u
+    }
+}
+
+impl Preconditioner for IlutPreconditioner {
+    fn solve(&self, r: &[F64]) -> Vec<F64> {
+        self.u.solve(self.l.solve(r.to_vec()))
+    }
+}
+
+// Incomplete Cholesky without fill for symmetric positive definite A: L L^T
+// agrees with A on the pattern of the lower triangle of A, which is the only
+// part read.
+#[derive(Debug, Clone)]
+pub struct Ic0Preconditioner {
+    l: SparseTriMat,
+}
+
+// sum of x_j y_j over the columns j < end stored in both sorted rows
+fn sparse_dot(x: &[(usize, F64)], y: &[(usize, F64)], end: usize) -> F64 {
+    let (mut p, mut q) = (0, 0);
+    let mut sum = F64::ZERO;
+    while p < x.len() && q < y.len() && x[p].0 < end && y[q].0 < end {
+        if x[p].0 == y[q].0 {
+            sum += x[p].1 * y[q].1;
+            p += 1;
+            q += 1;
+        } else if x[p].0 < y[q].0 {
+            p += 1;
+        } else {
+            q += 1;
+        }
+    }
+    sum
+}
+
+impl Ic0Preconditioner {
+    pub fn new<A: RowAccess>(a: &A) -> Result<Self, PreconditionerError> {
+        let a = stored_rows(a);
+        let n = a.row_count();
+        let mut rows: Vec<Vec<(usize, F64)>> = Vec::with_capacity(n);
+
+        for i in 0..n {
+            let mut row: Vec<(usize, F64)> = a.row(i).take_while(|(j, _)| *j <= i).collect();
+            for p in 0..row.len() {
+                let (k, aik) = row[p];
+                let dot = if k < i {
+                    sparse_dot(&row, &rows[k], k)
+                } else {
+                    sparse_dot(&row, &row, k)
+                };
+                if k < i {
+                    let lkk = rows[k][rows[k].len() - 1].1;
+                    row[p].1 = (aik - dot) / lkk;
+                } else {
+                    let d = aik - dot;
+                    if d <= F64::ZERO || !d.is_finite() {
+                        return Err(PreconditionerError::NotPositiveDefinite(i));
+                    }
+                    row[p].1 = d.sqrt();
+                }
+            }
+            if row.last().map(|e| e.0) != Some(i) {
+                return Err(PreconditionerError::ZeroPivot(i));
+            }
+            rows.push(row);
+        }
+
+        let mut l = CooMat::new(n, n);
+        for (i, row) in rows.iter().enumerate() {
+            for (j, v) in row.iter() {
+                l.push(i, *j, *v);
+            }
+        }
+        Ok(Self {
+            l: SparseTriMat::from_csc(TriangleMatType::Lower, l.to_csc()),
+        })
+    }
+
+    pub fn l(&self) -> &SparseTriMat {
+        &self.l
+    }
+}
+
+impl Preconditioner for Ic0Preconditioner {
+    fn solve(&self, r: &[F64]) -> Vec<F64> {
+        self.l.solve_transpose(self.l.solve(r.to_vec()))
+    }
+}
@@ -0,0 +1,5 @@
+// Readers and writers for exchanging matrices with other tools.
+
+pub mod mtx;
+pub mod npy;
+pub mod text;
@@ -0,0 +1,562 @@
+// Matrix Market exchange format (https://math.nist.gov/MatrixMarket). A file
+// starts with the banner
+//     %%MatrixMarket matrix <format> <field> <symmetry>
+// followed by `%` comment lines, a size line and the entries. Coordinate
+// files list "i j value" triplets with 1-based indices, array files list
+// every value in column-major order. For the symmetric kinds only the lower
+// triangle is stored, without the diagonal for skew-symmetric matrices.
+
+use std::io::{BufRead, Write};
+
+use floating_point::{Complex64, F64};
+
+use crate::{CooMat, FullMat, Matrix};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MtxFormat {
+    Coordinate,
+    Array,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MtxField {
+    Real,
+    Complex,
+    Integer,
+    // coordinate files only: the positions of the nonzeros without values
+    Pattern,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MtxSymmetry {
+    General,
+    Symmetric,
+    SkewSymmetric,
+    // complex data only: A = A^H
+    Hermitian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MtxHeader {
+    pub format: MtxFormat,
+    pub field: MtxField,
+    pub symmetry: MtxSymmetry,
+}
+
+#[derive(Debug)]
+pub enum MtxError {
+    Io(std::io::Error),
+    // line number, counting from 1, and what is wrong with it
+    Parse(usize, String),
+}
+
+impl std::fmt::Display for MtxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "I/O error: {}", error),
+            Self::Parse(line, message) => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for MtxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Parse(..) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for MtxError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+fn parse_error<T>(line: usize, message: impl Into<String>) -> Result<T, MtxError> {
+    Err(MtxError::Parse(line, message.into()))
+}
+
+fn parse_header(line: &str) -> Result<MtxHeader, MtxError> {
+    let tokens: Vec<String> = line
+        .split_whitespace()
+        .map(|t| t.to_ascii_lowercase())
+        .collect();
+    if tokens.first().map(String::as_str) != Some("%%matrixmarket") {
+        return parse_error(1, "missing %%MatrixMarket banner");
+    }
+    if tokens.len() != 5 {
+        return parse_error(1, "the banner needs object, format, field and symmetry");
+    }
+    if tokens[1] != "matrix" {
+        return parse_error(1, format!("unsupported object '{}'", tokens[1]));
+    }
+    let format = match tokens[2].as_str() {
+        "coordinate" => MtxFormat::Coordinate,
+        "array" => MtxFormat::Array,
+        other => return parse_error(1, format!("unknown format '{}'", other)),
+    };
+    let field = match tokens[3].as_str() {
+        "real" | "double" => MtxField::Real,
+        "complex" => MtxField::Complex,
+        "integer" => MtxField::Integer,
+        "pattern" => MtxField::Pattern,
+        other => return parse_error(1, format!("unknown field '{}'", other)),
+    };
+    let symmetry = match tokens[4].as_str() {
+        "general" => MtxSymmetry::General,
+        "symmetric" => MtxSymmetry::Symmetric,
+        "skew-symmetric" => MtxSymmetry::SkewSymmetric,
+        "hermitian" => MtxSymmetry::Hermitian,
+        other => return parse_error(1, format!("unknown symmetry '{}'", other)),
+    };
+
+    if format == MtxFormat::Array && field == MtxField::Pattern {
+        return parse_error(1, "pattern data needs the coordinate format");
+    }
+    if symmetry == MtxSymmetry::Hermitian && field != MtxField::Complex {
+        return parse_error(1, "hermitian symmetry needs complex data");
+    }
+    if symmetry == MtxSymmetry::SkewSymmetric && field == MtxField::Pattern {
+        return parse_error(1, "pattern data cannot be skew-symmetric");
+    }
+    Ok(MtxHeader {
+        format,
+        field,
+        symmetry,
+    })
+}
+
+fn parse_usize(token: &str, line: usize) -> Result<usize, MtxError> {
+    token
+        .parse()
+        .or_else(|_| parse_error(line, format!("expected an integer, found '{}'", token)))
+}
+
+fn parse_value(tokens: &[&str], field: MtxField, line: usize) -> Result<Complex64, MtxError> {
+    let expected = match field {
+        MtxField::Pattern => 0,
+        MtxField::Complex => 2,
+        MtxField::Real | MtxField::Integer => 1,
+    };
+    if tokens.len() != expected {
+        return parse_error(
+            line,
+            format!("expected {} value(s), found {}", expected, tokens.len()),
+        );
+    }
+    let real = |token: &str| -> Result<F64, MtxError> {
+        if field == MtxField::Integer {
+            token
+                .parse::<i64>()
+                .map(|v| F64::from(v as f64))
+                .or_else(|_| parse_error(line, format!("expected an integer, found '{}'", token)))
+        } else {
+            token
+                .parse::<f64>()
+                .map(F64::from)
+                .or_else(|_| parse_error(line, format!("expected a number, found '{}'", token)))
+        }
+    };
+    Ok(match field {
+        MtxField::Pattern => Complex64::ONE,
+        MtxField::Complex => Complex64::from((real(tokens[0])?, real(tokens[1])?)),
+        MtxField::Real | MtxField::Integer => Complex64::from((real(tokens[0])?, F64::ZERO)),
+    })
+}
+
+// The mirrored entry (j, i) implied by the symmetry of a stored (i, j).
+fn mirror(symmetry: MtxSymmetry, value: Complex64) -> Complex64 {
+    match symmetry {
+        MtxSymmetry::General | MtxSymmetry::Symmetric => value,
+        MtxSymmetry::SkewSymmetric => -value,
+        MtxSymmetry::Hermitian => value.conjugate(),
+    }
+}
+
+// The lines after the banner, skipping comments and blank lines.
+struct DataLines<I> {
+    lines: I,
+    // the number of the last line read, for errors at the end of the file
+    last_line: usize,
+}
+
+impl<I: Iterator<Item = (usize, std::io::Result<String>)>> DataLines<I> {
+    fn next(&mut self) -> Result<Option<(usize, String)>, MtxError> {
+        for (number, line) in self.lines.by_ref() {
+            let line = line?;
+            self.last_line = number;
+            let trimmed = line.trim();
+            if !trimmed.is_empty() && !trimmed.starts_with('%') {
+                return Ok(Some((number, trimmed.to_string())));
+            }
+        }
+        Ok(None)
+    }
+}
+
+// Everything in a file: the header, the shape and every entry with the
+// symmetric half filled in, as 0-based triplets.
+struct MtxContents {
+    header: MtxHeader,
+    shape: (usize, usize),
+    entries: Vec<(usize, usize, Complex64)>,
+}
+
+fn read_contents<R: BufRead>(reader: R) -> Result<MtxContents, MtxError> {
+    let mut lines = reader.lines().enumerate().map(|(i, line)| (i + 1, line));
+    let header = match lines.next() {
+        Some((_, line)) => parse_header(&line?)?,
+        None => return parse_error(1, "empty file"),
+    };
+    let mut data_lines = DataLines {
+        lines,
+        last_line: 1,
+    };
+
+    let (size_line, size) = match data_lines.next()? {
+        Some(line) => line,
+        None => return parse_error(data_lines.last_line, "missing size line"),
+    };
+    let size: Vec<&str> = size.split_whitespace().collect();
+    let size_count = match header.format {
+        MtxFormat::Coordinate => 3,
+        MtxFormat::Array => 2,
+    };
+    if size.len() != size_count {
+        return parse_error(
+            size_line,
+            format!("expected {} sizes, found {}", size_count, size.len()),
+        );
+    }
+    let (m, n) = (
+        parse_usize(size[0], size_line)?,
+        parse_usize(size[1], size_line)?,
+    );
+    if m == 0 || n == 0 {
+        return parse_error(size_line, "the matrix is empty");
+    }
+    if header.symmetry != MtxSymmetry::General && m != n {
+        return parse_error(size_line, "a symmetric matrix must be square");
+    }
+
+    let symmetric = header.symmetry != MtxSymmetry::General;
+    let skew = header.symmetry == MtxSymmetry::SkewSymmetric;
+    let mut entries = vec![];
+    let mut push = |i: usize, j: usize, value: Complex64| {
+        entries.push((i, j, value));
+        if symmetric && i != j {
+            entries.push((j, i, mirror(header.symmetry, value)));
+        }
+    };
+
+    match header.format {
+        MtxFormat::Coordinate => {
+            let nnz = parse_usize(size[2], size_line)?;
+            for k in 0..nnz {
+                let (number, line) = match data_lines.next()? {
+                    Some(line) => line,
+                    None => {
+                        return parse_error(
+                            data_lines.last_line,
+                            format!("expected {} entries, found {}", nnz, k),
+                        )
+                    }
+                };
+                let tokens: Vec<&str> = line.split_whitespace().collect();
+                if tokens.len() < 2 {
+                    return parse_error(number, "expected a row and a column index");
+                }
+                let (i, j) = (
+                    parse_usize(tokens[0], number)?,
+                    parse_usize(tokens[1], number)?,
+                );
+                if i == 0 || i > m || j == 0 || j > n {
+                    return parse_error(
+                        number,
+                        format!("index ({}, {}) outside a {}x{} matrix", i, j, m, n),
+                    );
+                }
+                if symmetric && (j > i || (skew && i == j)) {
+                    return parse_error(
+                        number,
+                        format!("entry ({}, {}) is not in the stored lower triangle", i, j),
+                    );
+                }
+                push(
+                    i - 1,
+                    j - 1,
+                    parse_value(&tokens[2..], header.field, number)?,
+                );
+            }
+        }
+        MtxFormat::Array => {
+            for j in 0..n {
+                let first_row = match header.symmetry {
+                    MtxSymmetry::General => 0,
+                    MtxSymmetry::SkewSymmetric => j + 1,
+                    MtxSymmetry::Symmetric | MtxSymmetry::Hermitian => j,
+                };
+                for i in first_row..m {
+                    let (number, line) = match data_lines.next()? {
+                        Some(line) => line,
+                        None => {
+                            return parse_error(
+                                data_lines.last_line,
+                                format!("missing the value of entry ({}, {})", i + 1, j + 1),
+                            )
+                        }
+                    };
+                    let tokens: Vec<&str> = line.split_whitespace().collect();
+                    push(i, j, parse_value(&tokens, header.field, number)?);
+                }
+            }
+        }
+    }
+
+    if let Some((number, _)) = data_lines.next()? {
+        return parse_error(number, "more entries than the size line announces");
+    }
+    Ok(MtxContents {
+        header,
+        shape: (m, n),
+        entries,
+    })
+}
+
+// Only the banner of a file, to decide how to load the rest.
+pub fn read_header<R: BufRead>(mut reader: R) -> Result<MtxHeader, MtxError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    parse_header(&line)
+}
+
+fn reject_complex(contents: &MtxContents) -> Result<(), MtxError> {
+    if contents.header.field == MtxField::Complex {
+        return parse_error(1, "complex data cannot be loaded into a real matrix");
+    }
+    Ok(())
+}
+
+// Any real, integer or pattern file as a dense matrix, summing duplicate
+// coordinate entries.
+pub fn read_full<R: BufRead>(reader: R) -> Result<FullMat<F64>, MtxError> {
+    let contents = read_contents(reader)?;
+    reject_complex(&contents)?;
+    let (m, n) = contents.shape;
+    let mut mat = FullMat::zeros(m, n);
+    for (i, j, v) in contents.entries {
+        mat[(i, j)] += v.real;
+    }
+    Ok(mat)
+}
+
+pub fn read_full_complex<R: BufRead>(reader: R) -> Result<FullMat<Complex64>, MtxError> {
+    let contents = read_contents(reader)?;
+    let (m, n) = contents.shape;
+    let mut mat = FullMat::from_vec(n, vec![Complex64::ZERO; m * n]);
+    for (i, j, v) in contents.entries {
+        mat[(i, j)] += v;
+    }
+    Ok(mat)
+}
+
+// Any real, integer or pattern file as triplets, ready for `to_csr` or
+// `to_csc`. Array files keep their explicit zeros.
+pub fn read_coo<R: BufRead>(reader: R) -> Result<CooMat<F64>, MtxError> {
+    let contents = read_contents(reader)?;
+    reject_complex(&contents)?;
+    let (m, n) = contents.shape;
+    let mut coo = CooMat::with_capacity(m, n, contents.entries.len());
+    for (i, j, v) in contents.entries {
+        coo.push(i, j, v.real);
+    }
+    Ok(coo)
+}
+
+// Any file as complex triplets, with the real kinds promoted.
+pub fn read_coo_complex<R: BufRead>(reader: R) -> Result<CooMat<Complex64>, MtxError> {
+    let contents = read_contents(reader)?;
+    let (m, n) = contents.shape;
+    let mut coo = CooMat::with_capacity(m, n, contents.entries.len());
+    for (i, j, v) in contents.entries {
+        coo.push(i, j, v);
+    }
+    Ok(coo)
+}
+
+fn write_banner<W: Write>(writer: &mut W, header: MtxHeader) -> Result<(), MtxError> {
+    let format = match header.format {
+        MtxFormat::Coordinate => "coordinate",
+        MtxFormat::Array => "array",
+    };
+    let field = match header.field {
+        MtxField::Real => "real",
+        MtxField::Complex => "complex",
+        MtxField::Integer => "integer",
+        MtxField::Pattern => "pattern",
+    };
+    let symmetry = match header.symmetry {
+        MtxSymmetry::General => "general",
+        MtxSymmetry::Symmetric => "symmetric",
+        MtxSymmetry::SkewSymmetric => "skew-symmetric",
+        MtxSymmetry::Hermitian => "hermitian",
+    };
+    writeln!(
+        writer,
+        "%%MatrixMarket matrix {} {} {}",
+        format, field, symmetry
+    )?;
+    Ok(())
+}
+
+// Whether (i, j) belongs to the stored part of a matrix with this symmetry.
+fn is_stored(symmetry: MtxSymmetry, i: usize, j: usize) -> bool {
+    match symmetry {
+        MtxSymmetry::General => true,
+        MtxSymmetry::Symmetric | MtxSymmetry::Hermitian => i >= j,
+        MtxSymmetry::SkewSymmetric => i > j,
+    }
+}
+
+fn format_real(value: F64, field: MtxField) -> String {
+    if field == MtxField::Integer {
+        assert!(value.to_f64().fract() == 0.0);
+        format!("{}", value.to_f64() as i64)
+    } else {
+        // shortest representation that reads back to the same number
+        format!("{:e}", value.to_f64())
+    }
+}
+
+// Writes a dense matrix in array format with a real or integer field. For
+// the symmetric kinds only the lower triangle is written, so the caller must
+// make sure the upper one mirrors it.
+pub fn write_full<W: Write>(
+    mut writer: W,
+    mat: &FullMat<F64>,
+    field: MtxField,
+    symmetry: MtxSymmetry,
+) -> Result<(), MtxError> {
+    assert!(field == MtxField::Real || field == MtxField::Integer);
+    assert!(symmetry != MtxSymmetry::Hermitian);
+    let (m, n) = mat.shape();
+    assert!(symmetry == MtxSymmetry::General || m == n);
+
+    let format = MtxFormat::Array;
+    write_banner(
+        &mut writer,
+        MtxHeader {
+            format,
+            field,
+            symmetry,
+        },
+    )?;
+    writeln!(writer, "{} {}", m, n)?;
+    for j in 0..n {
+        for i in (0..m).filter(|i| is_stored(symmetry, *i, j)) {
+            writeln!(writer, "{}", format_real(mat[(i, j)], field))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn write_full_complex<W: Write>(
+    mut writer: W,
+    mat: &FullMat<Complex64>,
+    symmetry: MtxSymmetry,
+) -> Result<(), MtxError> {
+    let (m, n) = mat.shape();
+    assert!(symmetry == MtxSymmetry::General || m == n);
+
+    let (format, field) = (MtxFormat::Array, MtxField::Complex);
+    write_banner(
+        &mut writer,
+        MtxHeader {
+            format,
+            field,
+            symmetry,
+        },
+    )?;
+    writeln!(writer, "{} {}", m, n)?;
+    for j in 0..n {
+        for i in (0..m).filter(|i| is_stored(symmetry, *i, j)) {
+            writeln!(writer, "{}", format_complex(mat[(i, j)]))?;
+        }
+    }
+    Ok(())
+}
+
+fn format_complex(value: Complex64) -> String {
+    format!("{:e} {:e}", value.real.to_f64(), value.imag.to_f64())
+}
+
+// Writes the triplets in coordinate format with a real, integer or pattern
+// field. For the symmetric kinds the triplets outside the stored lower
+// triangle are skipped.
+pub fn write_coo<W: Write>(
+    mut writer: W,
+    mat: &CooMat<F64>,
+    field: MtxField,
+    symmetry: MtxSymmetry,
+) -> Result<(), MtxError> {
+    assert!(field != MtxField::Complex);
+    assert!(symmetry != MtxSymmetry::Hermitian);
+    let (m, n) = mat.shape();
+    assert!(symmetry == MtxSymmetry::General || m == n);
+    assert!(field != MtxField::Pattern || symmetry != MtxSymmetry::SkewSymmetric);
+
+    let format = MtxFormat::Coordinate;
+    write_banner(
+        &mut writer,
+        MtxHeader {
+            format,
+            field,
+            symmetry,
+        },
+    )?;
+    let nnz = mat
+        .iter()
+        .filter(|(i, j, _)| is_stored(symmetry, *i, *j))
+        .count();
+    writeln!(writer, "{} {} {}", m, n, nnz)?;
+    for (i, j, v) in mat.iter().filter(|(i, j, _)| is_stored(symmetry, *i, *j)) {
+        if field == MtxField::Pattern {
+            writeln!(writer, "{} {}", i + 1, j + 1)?;
+        } else {
+            writeln!(writer, "{} {} {}", i + 1, j + 1, format_real(v, field))?;
+        }
+    }
+    Ok(())
+}
+
+// Writes complex triplets in coordinate format. For the symmetric kinds the
+// triplets outside the stored lower triangle are skipped.
+pub fn write_coo_complex<W: Write>(
+    mut writer: W,
+    mat: &CooMat<Complex64>,
+    symmetry: MtxSymmetry,
+) -> Result<(), MtxError> {
+    let (m, n) = mat.shape();
+    assert!(symmetry == MtxSymmetry::General || m == n);
+
+    let (format, field) = (MtxFormat::Coordinate, MtxField::Complex);
+    write_banner(
+        &mut writer,
+        MtxHeader {
+            format,
+            field,
+            symmetry,
+        },
+    )?;
+    let nnz = mat
+        .iter()
+        .filter(|(i, j, _)| is_stored(symmetry, *i, *j))
+        .count();
+    writeln!(writer, "{} {} {}", m, n, nnz)?;
+    for (i, j, v) in mat.iter().filter(|(i, j, _)| is_stored(symmetry, *i, *j)) {
+        writeln!(writer, "{} {} {}", i + 1, j + 1, format_complex(v))?;
+    }
+    Ok(())
+}
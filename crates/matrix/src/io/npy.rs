@@ -0,0 +1,617 @@
+// NumPy binary files
+// (https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html).
+// An .npy file starts with the magic string "\x93NUMPY", a major and minor
+// version byte and the length of a header that is a Python dict literal like
+//     {'descr': '<f8', 'fortran_order': False, 'shape': (3, 4), }
+// followed by the raw values. Version 1 stores the header length in 2 bytes,
+// versions 2 and 3 in 4. An .npz file is a zip archive of .npy files, one per
+// array, as written by `numpy.savez` and `numpy.savez_compressed`.
+//
+// Only float64 ('f8') and complex128 ('c16') data is supported, in either
+// byte order and either memory order. Real data can be loaded into complex
+// matrices and vectors, never the other way round.
+
+use std::io::{Read, Seek, Write};
+
+use floating_point::{Complex64, F64};
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{FullMat, Layout, Matrix};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NpyDtype {
+    Float64,
+    Complex128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpyHeader {
+    pub dtype: NpyDtype,
+    pub big_endian: bool,
+    // column-major data if set
+    pub fortran_order: bool,
+    pub shape: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub enum NpyError {
+    Io(std::io::Error),
+    Zip(zip::result::ZipError),
+    // a malformed file or an array of the wrong shape
+    Format(String),
+    // the 'descr' of the header, e.g. '<i8' or '<f4'
+    UnsupportedDtype(String),
+}
+
+impl std::fmt::Display for NpyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "I/O error: {}", error),
+            Self::Zip(error) => write!(f, "zip error: {}", error),
+            Self::Format(message) => write!(f, "invalid npy data: {}", message),
+            Self::UnsupportedDtype(descr) => write!(
+                f,
+                "unsupported dtype {}, only float64 ('f8') and complex128 ('c16') can be loaded",
+                descr
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NpyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Zip(error) => Some(error),
+            Self::Format(_) | Self::UnsupportedDtype(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for NpyError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<zip::result::ZipError> for NpyError {
+    fn from(error: zip::result::ZipError) -> Self {
+        Self::Zip(error)
+    }
+}
+
+fn format_error<T>(message: impl Into<String>) -> Result<T, NpyError> {
+    Err(NpyError::Format(message.into()))
+}
+
+// The subset of Python literals appearing in headers.
+#[derive(Debug)]
+enum Literal {
+    Str(String),
+    Bool(bool),
+    Tuple(Vec<usize>),
+    // anything else, e.g. the list describing a structured dtype
+    Other(String),
+}
+
+struct HeaderParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> HeaderParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    // Consumes `token` after optional whitespace if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), NpyError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            format_error(format!("expected '{}' in header {}", token, self.text))
+        }
+    }
+
+    fn string(&mut self) -> Result<String, NpyError> {
+        self.skip_whitespace();
+        let quote = match self.rest().chars().next() {
+            Some(c @ ('\'' | '"')) => c,
+            _ => return format_error(format!("expected a string in header {}", self.text)),
+        };
+        let rest = &self.rest()[1..];
+        match rest.find(quote) {
+            Some(end) => {
+                self.pos += end + 2;
+                Ok(rest[..end].to_string())
+            }
+            None => format_error(format!("unterminated string in header {}", self.text)),
+        }
+    }
+
+    fn integer(&mut self) -> Result<usize, NpyError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        match rest[..digits].parse() {
+            Ok(value) => {
+                self.pos += digits;
+                Ok(value)
+            }
+            Err(_) => format_error(format!("expected a dimension in header {}", self.text)),
+        }
+    }
+
+    // Skips a nested list or tuple, keeping its text.
+    fn bracketed(&mut self) -> Result<String, NpyError> {
+        let start = self.pos;
+        let mut depth = 0;
+        for (offset, c) in self.rest().char_indices() {
+            match c {
+                '[' | '(' => depth += 1,
+                ']' | ')' => depth -= 1,
+                _ => (),
+            }
+            if depth == 0 {
+                self.pos += offset + 1;
+                return Ok(self.text[start..self.pos].to_string());
+            }
+        }
+        format_error(format!("unbalanced brackets in header {}", self.text))
+    }
+
+    fn literal(&mut self) -> Result<Literal, NpyError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        if rest.starts_with(['\'', '"']) {
+            Ok(Literal::Str(self.string()?))
+        } else if self.eat("True") {
+            Ok(Literal::Bool(true))
+        } else if self.eat("False") {
+            Ok(Literal::Bool(false))
+        } else if self.eat("(") {
+            // (), (n,) or (m, n, ...) with an optional trailing comma
+            let mut dims = vec![];
+            while !self.eat(")") {
+                dims.push(self.integer()?);
+                if !self.eat(",") {
+                    self.expect(")")?;
+                    break;
+                }
+            }
+            Ok(Literal::Tuple(dims))
+        } else if rest.starts_with('[') {
+            Ok(Literal::Other(self.bracketed()?))
+        } else {
+            format_error(format!("unexpected value in header {}", self.text))
+        }
+    }
+
+    fn dict(&mut self) -> Result<Vec<(String, Literal)>, NpyError> {
+        self.expect("{")?;
+        let mut entries = vec![];
+        while !self.eat("}") {
+            let key = self.string()?;
+            self.expect(":")?;
+            entries.push((key, self.literal()?));
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok(entries)
+    }
+}
+
+fn parse_descr(descr: &str) -> Result<(NpyDtype, bool), NpyError> {
+    let unsupported = || Err(NpyError::UnsupportedDtype(format!("'{}'", descr)));
+    let (big_endian, kind) = match descr.as_bytes().first() {
+        Some(b'<') => (false, &descr[1..]),
+        Some(b'>') => (true, &descr[1..]),
+        Some(b'=') => (cfg!(target_endian = "big"), &descr[1..]),
+        Some(b'|') => return unsupported(),
+        _ => (cfg!(target_endian = "big"), descr),
+    };
+    match kind {
+        "f8" => Ok((NpyDtype::Float64, big_endian)),
+        "c16" => Ok((NpyDtype::Complex128, big_endian)),
+        _ => unsupported(),
+    }
+}
+
+fn parse_header(text: &str) -> Result<NpyHeader, NpyError> {
+    let mut parser = HeaderParser { text, pos: 0 };
+    let entries = parser.dict()?;
+    let (mut descr, mut fortran_order, mut shape) = (None, None, None);
+    for (key, value) in entries {
+        match (key.as_str(), value) {
+            ("descr", Literal::Str(value)) => descr = Some(parse_descr(&value)?),
+            ("descr", Literal::Other(value)) => return Err(NpyError::UnsupportedDtype(value)),
+            ("fortran_order", Literal::Bool(value)) => fortran_order = Some(value),
+            ("shape", Literal::Tuple(value)) => shape = Some(value),
+            (key, value) => {
+                return format_error(format!("unexpected header entry '{}': {:?}", key, value))
+            }
+        }
+    }
+    match (descr, fortran_order, shape) {
+        (Some((dtype, big_endian)), Some(fortran_order), Some(shape)) => Ok(NpyHeader {
+            dtype,
+            big_endian,
+            fortran_order,
+            shape,
+        }),
+        _ => format_error(format!(
+            "the header needs 'descr', 'fortran_order' and 'shape', found {}",
+            text
+        )),
+    }
+}
+
+pub fn read_header<R: Read>(mut reader: R) -> Result<NpyHeader, NpyError> {
+    let mut preamble = [0u8; 8];
+    reader.read_exact(&mut preamble)?;
+    if &preamble[..6] != MAGIC {
+        return format_error("missing \\x93NUMPY magic string");
+    }
+    let header_len = match preamble[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        major => return format_error(format!("unknown format version {}.{}", major, preamble[7])),
+    };
+    // the length is untrusted, so the buffer only grows with the bytes read
+    let mut header = Vec::new();
+    (&mut reader)
+        .take(header_len as u64)
+        .read_to_end(&mut header)?;
+    if header.len() < header_len {
+        return format_error("the file ends inside the header");
+    }
+    // versions 1 and 2 are latin-1, but a valid header is plain ASCII anyway
+    match String::from_utf8(header) {
+        Ok(text) => parse_header(&text),
+        Err(_) => format_error("the header is not valid text"),
+    }
+}
+
+fn shape_to_string(shape: &[usize]) -> String {
+    match shape {
+        [n] => format!("({},)", n),
+        _ => format!(
+            "({})",
+            shape
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+// Reads the header and all values, as complex numbers with zero imaginary
+// parts for real data.
+fn read_array<R: Read>(mut reader: R) -> Result<(NpyHeader, Vec<Complex64>), NpyError> {
+    let header = read_header(&mut reader)?;
+    let width = match header.dtype {
+        NpyDtype::Float64 => 8,
+        NpyDtype::Complex128 => 16,
+    };
+    let size = header
+        .shape
+        .iter()
+        .try_fold(1usize, |count, n| count.checked_mul(*n))
+        .and_then(|count| count.checked_mul(width).map(|size| (count, size)));
+    let (count, size) = match size {
+        Some(size) => size,
+        None => {
+            return format_error(format!(
+                "shape {} is too large",
+                shape_to_string(&header.shape)
+            ))
+        }
+    };
+    // as for the header, a bogus shape must not allocate more than the data
+    let mut bytes = Vec::new();
+    (&mut reader).take(size as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < size {
+        return format_error(format!(
+            "the data ends before the {} values of shape {}",
+            count,
+            shape_to_string(&header.shape)
+        ));
+    }
+    let numbers: Vec<f64> = bytes
+        .chunks_exact(8)
+        .map(|chunk| {
+            let chunk = chunk.try_into().unwrap();
+            if header.big_endian {
+                f64::from_be_bytes(chunk)
+            } else {
+                f64::from_le_bytes(chunk)
+            }
+        })
+        .collect();
+    let values = match header.dtype {
+        NpyDtype::Float64 => numbers
+            .into_iter()
+            .map(|x| Complex64::new(x, 0.0))
+            .collect(),
+        NpyDtype::Complex128 => numbers
+            .chunks_exact(2)
+            .map(|pair| Complex64::new(pair[0], pair[1]))
+            .collect(),
+    };
+    Ok((header, values))
+}
+
+fn reject_complex(header: &NpyHeader) -> Result<(), NpyError> {
+    if header.dtype == NpyDtype::Complex128 {
+        return format_error("complex128 data cannot be loaded into a real matrix");
+    }
+    Ok(())
+}
+
+// A 2-D array as a matrix in its memory order, without copying Fortran-ordered
+// data into rows.
+fn matrix_values<T: Copy>(header: &NpyHeader, values: Vec<T>) -> Result<FullMat<T>, NpyError> {
+    let (m, n) = match header.shape[..] {
+        [m, n] if m > 0 && n > 0 => (m, n),
+        _ => {
+            return format_error(format!(
+                "expected a nonempty 2-D array, found shape {}",
+                shape_to_string(&header.shape)
+            ))
+        }
+    };
+    if header.fortran_order {
+        Ok(FullMat::from_vec_col_major(m, values))
+    } else {
+        Ok(FullMat::from_vec(n, values))
+    }
+}
+
+pub fn read_full<R: Read>(reader: R) -> Result<FullMat<F64>, NpyError> {
+    let (header, values) = read_array(reader)?;
+    reject_complex(&header)?;
+    let values = values.iter().map(|v| v.real).collect();
+    matrix_values(&header, values)
+}
+
+pub fn read_full_complex<R: Read>(reader: R) -> Result<FullMat<Complex64>, NpyError> {
+    let (header, values) = read_array(reader)?;
+    matrix_values(&header, values)
+}
+
+// A 1-D array, e.g. an FFT buffer saved from NumPy.
+pub fn read_complex_vec<R: Read>(reader: R) -> Result<Vec<Complex64>, NpyError> {
+    let (header, values) = read_array(reader)?;
+    if header.shape.len() != 1 {
+        return format_error(format!(
+            "expected a 1-D array, found shape {}",
+            shape_to_string(&header.shape)
+        ));
+    }
+    Ok(values)
+}
+
+// Little-endian data with a version 1 header, padded with spaces so the data
+// starts at a multiple of 64 bytes as NumPy does. Version 2 is only used for
+// headers too long for 2 length bytes.
+fn write_array<W: Write>(
+    mut writer: W,
+    dtype: NpyDtype,
+    fortran_order: bool,
+    shape: &[usize],
+    numbers: impl Iterator<Item = f64>,
+) -> Result<(), NpyError> {
+    let descr = match dtype {
+        NpyDtype::Float64 => "<f8",
+        NpyDtype::Complex128 => "<c16",
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': {}, 'shape': {}, }}",
+        descr,
+        if fortran_order { "True" } else { "False" },
+        shape_to_string(shape)
+    );
+    let version: u8 = if header.len() + 11 > u16::MAX as usize {
+        2
+    } else {
+        1
+    };
+    let preamble_len = if version == 1 { 10 } else { 12 };
+    let total = (preamble_len + header.len() + 1).div_ceil(64) * 64;
+    header.push_str(&" ".repeat(total - preamble_len - header.len() - 1));
+    header.push('\n');
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[version, 0])?;
+    if version == 1 {
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    } else {
+        writer.write_all(&(header.len() as u32).to_le_bytes())?;
+    }
+    writer.write_all(header.as_bytes())?;
+    let mut bytes = vec![];
+    for x in numbers {
+        bytes.extend_from_slice(&x.to_le_bytes());
+    }
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+// Column-major matrices are written in Fortran order, all others in C order.
+fn matrix_entries<T: Copy + std::fmt::Display>(mat: &FullMat<T>) -> (bool, Vec<T>) {
+    if mat.layout() == Layout::ColMajor {
+        let cols = (0..mat.col_count()).flat_map(|j| mat.col_iter(j));
+        (true, cols.copied().collect())
+    } else {
+        (false, mat.iter().copied().collect())
+    }
+}
+
+pub fn write_full<W: Write>(writer: W, mat: &FullMat<F64>) -> Result<(), NpyError> {
+    let (fortran_order, entries) = matrix_entries(mat);
+    let numbers = entries.into_iter().map(|x| x.to_f64());
+    write_array(
+        writer,
+        NpyDtype::Float64,
+        fortran_order,
+        &[mat.row_count(), mat.col_count()],
+        numbers,
+    )
+}
+
+pub fn write_full_complex<W: Write>(writer: W, mat: &FullMat<Complex64>) -> Result<(), NpyError> {
+    let (fortran_order, entries) = matrix_entries(mat);
+    let numbers = entries
+        .into_iter()
+        .flat_map(|v| [v.real.to_f64(), v.imag.to_f64()]);
+    write_array(
+        writer,
+        NpyDtype::Complex128,
+        fortran_order,
+        &[mat.row_count(), mat.col_count()],
+        numbers,
+    )
+}
+
+pub fn write_complex_vec<W: Write>(writer: W, values: &[Complex64]) -> Result<(), NpyError> {
+    let numbers = values
+        .iter()
+        .flat_map(|v| [v.real.to_f64(), v.imag.to_f64()]);
+    write_array(
+        writer,
+        NpyDtype::Complex128,
+        false,
+        &[values.len()],
+        numbers,
+    )
+}
+
+// Arrays in an .npz archive are looked up by the names given to
+// `numpy.savez`, with or without the ".npy" suffix of the member files.
+pub struct NpzReader<R: Read + Seek> {
+    archive: ZipArchive<R>,
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+    pub fn new(reader: R) -> Result<Self, NpyError> {
+        Ok(Self {
+            archive: ZipArchive::new(reader)?,
+        })
+    }
+
+    // sorted by name
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .archive
+            .file_names()
+            .map(|name| name.strip_suffix(".npy").unwrap_or(name).to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn member(&mut self, name: &str) -> Result<zip::read::ZipFile<'_>, NpyError> {
+        let file_name = if name.ends_with(".npy") {
+            name.to_string()
+        } else {
+            format!("{}.npy", name)
+        };
+        match self.archive.by_name(&file_name) {
+            Err(zip::result::ZipError::FileNotFound) => {
+                format_error(format!("no array named '{}' in the archive", name))
+            }
+            member => Ok(member?),
+        }
+    }
+
+    pub fn read_full(&mut self, name: &str) -> Result<FullMat<F64>, NpyError> {
+        read_full(self.member(name)?)
+    }
+
+    pub fn read_full_complex(&mut self, name: &str) -> Result<FullMat<Complex64>, NpyError> {
+        read_full_complex(self.member(name)?)
+    }
+
+    pub fn read_complex_vec(&mut self, name: &str) -> Result<Vec<Complex64>, NpyError> {
+        read_complex_vec(self.member(name)?)
+    }
+}
+
+pub struct NpzWriter<W: Write + Seek> {
+    archive: ZipWriter<W>,
+    options: FileOptions,
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+    // stored members, like `numpy.savez`
+    pub fn new(writer: W) -> Self {
+        Self::with_compression(writer, CompressionMethod::Stored)
+    }
+
+    // deflated members, like `numpy.savez_compressed`
+    pub fn compressed(writer: W) -> Self {
+        Self::with_compression(writer, CompressionMethod::Deflated)
+    }
+
+    fn with_compression(writer: W, method: CompressionMethod) -> Self {
+        Self {
+            archive: ZipWriter::new(writer),
+            options: FileOptions::default().compression_method(method),
+        }
+    }
+
+    fn start(&mut self, name: &str) -> Result<&mut ZipWriter<W>, NpyError> {
+        self.archive
+            .start_file(format!("{}.npy", name), self.options)?;
+        Ok(&mut self.archive)
+    }
+
+    pub fn write_full(&mut self, name: &str, mat: &FullMat<F64>) -> Result<(), NpyError> {
+        write_full(self.start(name)?, mat)
+    }
+
+    pub fn write_full_complex(
+        &mut self,
+        name: &str,
+        mat: &FullMat<Complex64>,
+    ) -> Result<(), NpyError> {
+        write_full_complex(self.start(name)?, mat)
+    }
+
+    pub fn write_complex_vec(&mut self, name: &str, values: &[Complex64]) -> Result<(), NpyError> {
+        write_complex_vec(self.start(name)?, values)
+    }
+
+    // Writes the central directory and hands back the underlying writer.
+    pub fn finish(mut self) -> Result<W, NpyError> {
+        Ok(self.archive.finish()?)
+    }
+}
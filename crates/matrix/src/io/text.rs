@@ -0,0 +1,270 @@
+// Delimited text files with one matrix row per line, as written by
+// spreadsheets, `numpy.savetxt` or `pandas.DataFrame.to_csv`. Cells are
+// numbers, or complex numbers in the `Complex64` syntax "1.5 - 2 I"; the
+// spaces are optional, so "1.5-2I" and "-2I" work in whitespace-delimited
+// files too. Quoted cells are not supported.
+
+use std::io::{BufRead, Write};
+
+use floating_point::{Complex64, F64};
+
+use crate::{FullMat, Matrix};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextOptions {
+    // None splits on runs of whitespace
+    pub delimiter: Option<char>,
+    // lines dropped at the top of the file before anything else, e.g. a row
+    // of column names
+    pub skip_rows: usize,
+    // lines starting with this character are ignored, blank lines always are
+    pub comment: Option<char>,
+    // Read in any case besides Rust's own "NaN", "inf" and "infinity", and
+    // used when writing. An empty NaN token reads empty cells as NaN, which is
+    // how pandas writes missing values.
+    pub nan_token: String,
+    pub inf_token: String,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: Some(','),
+            skip_rows: 0,
+            comment: Some('#'),
+            nan_token: "nan".to_string(),
+            inf_token: "inf".to_string(),
+        }
+    }
+}
+
+impl TextOptions {
+    pub fn whitespace() -> Self {
+        Self {
+            delimiter: None,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TextError {
+    Io(std::io::Error),
+    // line number, counting from 1, and what is wrong with it
+    Parse(usize, String),
+}
+
+impl std::fmt::Display for TextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "I/O error: {}", error),
+            Self::Parse(line, message) => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for TextError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Parse(..) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for TextError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+fn parse_real(token: &str, options: &TextOptions) -> Option<F64> {
+    let token = token.trim();
+    if token.eq_ignore_ascii_case(&options.nan_token) {
+        return Some(F64::from(f64::NAN));
+    }
+    let (negative, magnitude) = match token.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, token.strip_prefix('+').unwrap_or(token)),
+    };
+    if !magnitude.is_empty() && magnitude.eq_ignore_ascii_case(&options.inf_token) {
+        let inf = if negative {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+        return Some(F64::from(inf));
+    }
+    token.parse::<f64>().ok().map(F64::from)
+}
+
+// "a + b I", "a - b I", "b I" or just "a", with or without the spaces
+fn parse_complex(token: &str, options: &TextOptions) -> Option<Complex64> {
+    let token = token.trim();
+    let body = match token.strip_suffix(['I', 'i']) {
+        Some(body) => body.trim_end(),
+        None => return parse_real(token, options).map(|re| Complex64::from((re, F64::ZERO))),
+    };
+    // the sign between the parts is the last one that neither starts the
+    // cell nor belongs to an exponent
+    let bytes = body.as_bytes();
+    let split = (1..bytes.len())
+        .rev()
+        .find(|k| matches!(bytes[*k], b'+' | b'-') && !matches!(bytes[k - 1], b'e' | b'E'));
+    let (re, im) = match split {
+        Some(k) => {
+            let im = format!("{}{}", &body[k..k + 1], body[k + 1..].trim_start());
+            (parse_real(&body[..k], options)?, parse_real(&im, options)?)
+        }
+        None => (F64::ZERO, parse_real(body, options)?),
+    };
+    Some(Complex64::from((re, im)))
+}
+
+fn format_real(value: F64, options: &TextOptions) -> String {
+    let value = value.to_f64();
+    if value.is_nan() {
+        options.nan_token.clone()
+    } else if value.is_infinite() {
+        let sign = if value < 0.0 { "-" } else { "" };
+        format!("{}{}", sign, options.inf_token)
+    } else {
+        // shortest representation that reads back to the same number
+        format!("{:e}", value)
+    }
+}
+
+// Spaces inside a cell would split it in a whitespace-delimited file.
+fn format_complex(value: Complex64, options: &TextOptions) -> String {
+    let re = format_real(value.real, options);
+    let sign = if value.imag.to_f64().is_sign_negative() {
+        '-'
+    } else {
+        '+'
+    };
+    let im = format_real(value.imag.abs(), options);
+    match options.delimiter {
+        Some(_) => format!("{} {} {} I", re, sign, im),
+        None => format!("{}{}{}I", re, sign, im),
+    }
+}
+
+fn read_text<R: BufRead, T: Copy>(
+    reader: R,
+    options: &TextOptions,
+    parse: impl Fn(&str, &TextOptions) -> Option<T>,
+    expected: &str,
+) -> Result<FullMat<T>, TextError> {
+    let mut values = vec![];
+    let mut col_count = None;
+    let mut last_line = options.skip_rows;
+    for (i, line) in reader.lines().enumerate().skip(options.skip_rows) {
+        let (number, line) = (i + 1, line?);
+        last_line = number;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || options.comment.is_some_and(|c| trimmed.starts_with(c)) {
+            continue;
+        }
+        let cells: Vec<&str> = match options.delimiter {
+            Some(delimiter) => trimmed.split(delimiter).collect(),
+            None => trimmed.split_whitespace().collect(),
+        };
+        match col_count {
+            None => col_count = Some(cells.len()),
+            Some(n) if n != cells.len() => {
+                return Err(TextError::Parse(
+                    number,
+                    format!("expected {} columns, found {}", n, cells.len()),
+                ))
+            }
+            Some(_) => (),
+        }
+        for cell in cells {
+            match parse(cell, options) {
+                Some(value) => values.push(value),
+                None => {
+                    return Err(TextError::Parse(
+                        number,
+                        format!("expected {}, found '{}'", expected, cell.trim()),
+                    ))
+                }
+            }
+        }
+    }
+    match col_count {
+        Some(n) => Ok(FullMat::from_vec(n, values)),
+        None => Err(TextError::Parse(last_line.max(1), "no data".to_string())),
+    }
+}
+
+fn write_text<W: Write, T: Copy + std::fmt::Display>(
+    mut writer: W,
+    mat: &FullMat<T>,
+    options: &TextOptions,
+    format: impl Fn(T, &TextOptions) -> String,
+) -> Result<(), TextError> {
+    let separator = match options.delimiter {
+        Some(delimiter) => delimiter.to_string(),
+        None => " ".to_string(),
+    };
+    let (m, n) = mat.shape();
+    for i in 0..m {
+        let cells: Vec<String> = (0..n).map(|j| format(mat[(i, j)], options)).collect();
+        writeln!(writer, "{}", cells.join(&separator))?;
+    }
+    Ok(())
+}
+
+// Any delimiter works, `TextOptions::whitespace()` reads the output of
+// `numpy.savetxt` with its default settings. The writers put out the rows
+// only, so a header line has to be written to `writer` beforehand.
+impl FullMat<F64> {
+    pub fn from_csv_reader<R: BufRead>(
+        reader: R,
+        options: &TextOptions,
+    ) -> Result<Self, TextError> {
+        read_text(reader, options, parse_real, "a number")
+    }
+
+    pub fn to_csv_writer<W: Write>(
+        &self,
+        writer: W,
+        options: &TextOptions,
+    ) -> Result<(), TextError> {
+        write_text(writer, self, options, format_real)
+    }
+
+    pub fn from_whitespace_reader<R: BufRead>(reader: R) -> Result<Self, TextError> {
+        Self::from_csv_reader(reader, &TextOptions::whitespace())
+    }
+
+    pub fn to_whitespace_writer<W: Write>(&self, writer: W) -> Result<(), TextError> {
+        self.to_csv_writer(writer, &TextOptions::whitespace())
+    }
+}
+
+// Real cells load with zero imaginary parts.
+impl FullMat<Complex64> {
+    pub fn from_csv_reader<R: BufRead>(
+        reader: R,
+        options: &TextOptions,
+    ) -> Result<Self, TextError> {
+        read_text(reader, options, parse_complex, "a complex number")
+    }
+
+    pub fn to_csv_writer<W: Write>(
+        &self,
+        writer: W,
+        options: &TextOptions,
+    ) -> Result<(), TextError> {
+        write_text(writer, self, options, format_complex)
+    }
+
+    pub fn from_whitespace_reader<R: BufRead>(reader: R) -> Result<Self, TextError> {
+        Self::from_csv_reader(reader, &TextOptions::whitespace())
+    }
+
+    pub fn to_whitespace_writer<W: Write>(&self, writer: W) -> Result<(), TextError> {
+        self.to_csv_writer(writer, &TextOptions::whitespace())
+    }
+}
@@ -0,0 +1,306 @@
+use floating_point::F64;
+
+use crate::{vector, LinearOperator, Preconditioner};
+
+#[derive(Debug, Clone, Copy)]
+pub struct StoppingCriterion {
+    pub relative_tolerance: F64,
+    pub absolute_tolerance: F64,
+    pub max_iterations: usize,
+}
+
+impl Default for StoppingCriterion {
+    fn default() -> Self {
+        Self {
+            relative_tolerance: F64::from(1e-10),
+            absolute_tolerance: F64::ZERO,
+            max_iterations: 1000,
+        }
+    }
+}
+
+impl StoppingCriterion {
+    // Satisfied once the residual norm is below the relative tolerance times
+    // the norm of the right-hand side, or below the absolute tolerance.
+    pub fn is_satisfied(&self, residual_norm: F64, rhs_norm: F64) -> bool {
+        residual_norm
+            <= self
+                .absolute_tolerance
+                .max(self.relative_tolerance * rhs_norm)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvergenceReason {
+    Converged,
+    MaxIterationsReached,
+    // p^T A p <= 0 in conjugate gradients
+    NotPositiveDefinite,
+    // a division by zero in the recurrences, or an indefinite preconditioner
+    Breakdown,
+}
+
+#[derive(Debug, Clone)]
+pub struct KrylovResult {
+    pub x: Vec<F64>,
+    pub iterations: usize,
+    pub reason: ConvergenceReason,
+    // residual norm after every iteration
+    pub residual_history: Vec<F64>,
+}
+
+impl KrylovResult {
+    pub(crate) fn new(x: Vec<F64>) -> Self {
+        Self {
+            x,
+            iterations: 0,
+            reason: ConvergenceReason::MaxIterationsReached,
+            residual_history: vec![],
+        }
+    }
+
+    pub(crate) fn record(&mut self, residual_norm: F64) {
+        self.iterations += 1;
+        self.residual_history.push(residual_norm);
+    }
+
+    pub fn converged(&self) -> bool {
+        self.reason == ConvergenceReason::Converged
+    }
+}
+
+fn initial_residual<A: LinearOperator>(a: &A, b: &[F64], x0: &[F64]) -> Vec<F64> {
+    assert!(a.dim() == b.len() && b.len() == x0.len());
+    vector::sub(b, &a.apply(x0))
+}
+
+// r^T M^-1 r must stay positive for a positive definite preconditioner, and
+// is zero or NaN if the preconditioner returned nothing usable.
+fn is_breakdown(rz: F64) -> bool {
+    rz <= F64::ZERO || !rz.is_finite()
+}
+
+// Preconditioned conjugate gradients for symmetric positive definite A and M.
+pub fn conjugate_gradient<A: LinearOperator, M: Preconditioner>(
+    a: &A,
+    b: &[F64],
+    x0: &[F64],
+    preconditioner: &M,
+    criterion: &StoppingCriterion,
+) -> KrylovResult {
+    let b_norm = vector::norm2(b);
+    let mut r = initial_residual(a, b, x0);
+    let mut result = KrylovResult::new(x0.to_vec());
+    if criterion.is_satisfied(vector::norm2(&r), b_norm) {
+        result.reason = ConvergenceReason::Converged;
+        return result;
+    }
+
+    let mut z = preconditioner.solve(&r);
+    let mut p = z.clone();
+    let mut rz = vector::dot(&r, &z);
+    if is_breakdown(rz) {
+        result.reason = ConvergenceReason::Breakdown;
+        return result;
+    }
+    for _ in 0..criterion.max_iterations {
+        let ap = a.apply(&p);
+        let pap = vector::dot(&p, &ap);
+        if pap <= F64::ZERO {
+            result.reason = ConvergenceReason::NotPositiveDefinite;
+            break;
+        }
+
+        let alpha = rz / pap;
+        vector::axpy(alpha, &p, &mut result.x);
+        vector::axpy(-alpha, &ap, &mut r);
+        let r_norm = vector::norm2(&r);
+        result.record(r_norm);
+        if criterion.is_satisfied(r_norm, b_norm) {
+            result.reason = ConvergenceReason::Converged;
+            break;
+        }
+
+        z = preconditioner.solve(&r);
+        let rz_next = vector::dot(&r, &z);
+        if is_breakdown(rz_next) {
+            result.reason = ConvergenceReason::Breakdown;
+            break;
+        }
+        let beta = rz_next / rz;
+        rz = rz_next;
+        p.iter_mut()
+            .zip(z.iter())
+            .for_each(|(p, z)| *p = *z + beta * *p);
+    }
+    result
+}
+
+// Preconditioned MINRES (Paige and Saunders) for symmetric, possibly
+// indefinite A and a symmetric positive definite preconditioner M. The
+// residual history holds ||r||_{M^-1}, the norm MINRES minimises, which is
+// the 2-norm when M = I; the relative tolerance is applied to ||b||_{M^-1}.
+pub fn minres<A: LinearOperator, M: Preconditioner>(
+    a: &A,
+    b: &[F64],
+    x0: &[F64],
+    preconditioner: &M,
+    criterion: &StoppingCriterion,
+) -> KrylovResult {
+    let n = b.len();
+    let mut result = KrylovResult::new(x0.to_vec());
+    let b_norm = vector::dot(b, &preconditioner.solve(b))
+        .max(F64::ZERO)
+        .sqrt();
+
+    let mut r1 = initial_residual(a, b, x0);
+    let mut y = preconditioner.solve(&r1);
+    let beta1 = vector::dot(&r1, &y);
+    if beta1 < F64::ZERO {
+        result.reason = ConvergenceReason::Breakdown;
+        return result;
+    }
+    let beta1 = beta1.sqrt();
+    if criterion.is_satisfied(beta1, b_norm) {
+        result.reason = ConvergenceReason::Converged;
+        return result;
+    }
+
+    let (mut oldb, mut beta, mut dbar, mut epsln) = (F64::ZERO, beta1, F64::ZERO, F64::ZERO);
+    let mut phibar = beta1;
+    let (mut cs, mut sn) = (-F64::ONE, F64::ZERO);
+    let mut w = vec![F64::ZERO; n];
+    let mut w2 = vec![F64::ZERO; n];
+    let mut r2 = r1.clone();
+
+    for k in 0..criterion.max_iterations {
+        // Lanczos step
+        let v: Vec<F64> = y.iter().map(|y| *y / beta).collect();
+        y = a.apply(&v);
+        if k > 0 {
+            vector::axpy(-beta / oldb, &r1, &mut y);
+        }
+        let alpha = vector::dot(&v, &y);
+        vector::axpy(-alpha / beta, &r2, &mut y);
+        r1 = r2;
+        r2 = y;
+        y = preconditioner.solve(&r2);
+        oldb = beta;
+        beta = vector::dot(&r2, &y);
+        if beta < F64::ZERO {
+            result.reason = ConvergenceReason::Breakdown;
+            break;
+        }
+        beta = beta.sqrt();
+
+        // apply the previous rotation and compute the next one
+        let oldeps = epsln;
+        let delta = cs * dbar + sn * alpha;
+        let gbar = sn * dbar - cs * alpha;
+        epsln = sn * beta;
+        dbar = -cs * beta;
+        let gamma = gbar.hypot(beta).max(F64::EPSILON);
+        cs = gbar / gamma;
+        sn = beta / gamma;
+        let phi = cs * phibar;
+        phibar *= sn;
+
+        // update the solution along the new search direction
+        let w1 = std::mem::replace(&mut w2, w);
+        w = (0..n)
+            .map(|i| (v[i] - oldeps * w1[i] - delta * w2[i]) / gamma)
+            .collect();
+        vector::axpy(phi, &w, &mut result.x);
+
+        result.record(phibar.abs());
+        if criterion.is_satisfied(phibar.abs(), b_norm) {
+            result.reason = ConvergenceReason::Converged;
+            break;
+        }
+        if beta == F64::ZERO {
+            // the Krylov space is invariant, so the last solution is exact
+            result.reason = ConvergenceReason::Converged;
+            break;
+        }
+    }
+    result
+}
+
+// Right-preconditioned BiCGSTAB (van der Vorst) for general nonsymmetric A.
+pub fn bicgstab<A: LinearOperator, M: Preconditioner>(
+    a: &A,
+    b: &[F64],
+    x0: &[F64],
+    preconditioner: &M,
+    criterion: &StoppingCriterion,
+) -> KrylovResult {
+    let n = b.len();
+    let b_norm = vector::norm2(b);
+    let mut r = initial_residual(a, b, x0);
+    let mut result = KrylovResult::new(x0.to_vec());
+    if criterion.is_satisfied(vector::norm2(&r), b_norm) {
+        result.reason = ConvergenceReason::Converged;
+        return result;
+    }
+
+    let r_hat = r.clone();
+    let (mut rho, mut alpha, mut omega) = (F64::ONE, F64::ONE, F64::ONE);
+    let mut v = vec![F64::ZERO; n];
+    let mut p = vec![F64::ZERO; n];
+
+    for _ in 0..criterion.max_iterations {
+        let rho_next = vector::dot(&r_hat, &r);
+        if rho_next == F64::ZERO {
+            result.reason = ConvergenceReason::Breakdown;
+            break;
+        }
+        let beta = (rho_next / rho) * (alpha / omega);
+        rho = rho_next;
+        for i in 0..n {
+            p[i] = r[i] + beta * (p[i] - omega * v[i]);
+        }
+
+        let p_hat = preconditioner.solve(&p);
+        v = a.apply(&p_hat);
+        let r_hat_v = vector::dot(&r_hat, &v);
+        if r_hat_v == F64::ZERO {
+            result.reason = ConvergenceReason::Breakdown;
+            break;
+        }
+        alpha = rho / r_hat_v;
+        let mut s = r.clone();
+        vector::axpy(-alpha, &v, &mut s);
+        let s_norm = vector::norm2(&s);
+        if criterion.is_satisfied(s_norm, b_norm) {
+            vector::axpy(alpha, &p_hat, &mut result.x);
+            result.record(s_norm);
+            result.reason = ConvergenceReason::Converged;
+            break;
+        }
+
+        let s_hat = preconditioner.solve(&s);
+        let t = a.apply(&s_hat);
+        let tt = vector::dot(&t, &t);
+        if tt == F64::ZERO {
+            result.reason = ConvergenceReason::Breakdown;
+            break;
+        }
+        omega = vector::dot(&t, &s) / tt;
+        vector::axpy(alpha, &p_hat, &mut result.x);
+        vector::axpy(omega, &s_hat, &mut result.x);
+        r = s;
+        vector::axpy(-omega, &t, &mut r);
+
+        let r_norm = vector::norm2(&r);
+        result.record(r_norm);
+        if criterion.is_satisfied(r_norm, b_norm) {
+            result.reason = ConvergenceReason::Converged;
+            break;
+        }
+        if omega == F64::ZERO {
+            result.reason = ConvergenceReason::Breakdown;
+            break;
+        }
+    }
+    result
+}
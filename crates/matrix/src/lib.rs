@@ -1,8 +1,127 @@
 mod matrix;
 pub use self::matrix::Matrix;
 
+mod matrix_error;
+pub use matrix_error::MatrixError;
+
 mod full_mat;
-pub use full_mat::FullMat;
+pub use full_mat::{FullMat, Layout};
 
 mod triangle_mat;
 pub use triangle_mat::{TriFullMat, TriangleMatType};
+
+mod coo_mat;
+pub use coo_mat::CooMat;
+
+mod csr_mat;
+pub use csr_mat::CsrMat;
+
+mod csc_mat;
+pub use csc_mat::CscMat;
+
+mod tridiagonal_mat;
+pub use tridiagonal_mat::TridiagonalMat;
+
+mod band_mat;
+pub use band_mat::{BandLu, BandMat};
+
+mod toeplitz_mat;
+pub use toeplitz_mat::{durbin, ToeplitzMat};
+
+mod hankel_mat;
+pub use hankel_mat::HankelMat;
+
+mod circulant_mat;
+pub use circulant_mat::CirculantMat;
+
+mod vandermonde_mat;
+pub use vandermonde_mat::VandermondeMat;
+
+mod static_mat;
+pub use static_mat::{SLu, SMat, SVec};
+
+mod sym_packed_mat;
+pub use sym_packed_mat::{HermPackedMat, SymPackedMat};
+
+mod special_mat;
+
+mod gemm;
+
+mod blocked_lu;
+
+pub mod io;
+
+mod vector;
+
+mod norm;
+pub use norm::{vector_norm1, vector_norm2, vector_norm_inf, vector_norm_p};
+
+mod cholesky;
+
+mod qr;
+
+mod svd;
+pub use svd::{Svd, SvdMode};
+
+mod lstsq;
+pub use lstsq::{lstsq, lstsq_tikhonov, lstsq_weighted, LstsqMethod, LstsqSolution};
+
+mod symmetric_eigen;
+pub use symmetric_eigen::{EigenSubset, SymmetricEigen, SymmetricEigenMethod};
+
+mod nonsymmetric_eigen;
+pub use nonsymmetric_eigen::{Eigen, Schur};
+
+mod matrix_function;
+
+mod matrix_equation;
+pub use matrix_equation::{
+    care, lyapunov, stein, sylvester, MatrixEquationSolution, SylvesterMethod,
+};
+
+mod linear_operator;
+pub use linear_operator::{LinearOperator, RowAccess};
+
+mod eigen_iteration;
+pub use eigen_iteration::{
+    dominant_eigenpairs, inverse_iteration, power_iteration, rayleigh_quotient_iteration,
+    EigenIterationOptions, EigenIterationResult,
+};
+
+mod stationary;
+pub use stationary::{
+    check_diagonal_dominance, optimal_sor_omega, stationary_solve, StationaryError,
+    StationaryMethod, StationaryOptions, StationaryResult,
+};
+
+mod preconditioner;
+pub use preconditioner::{
+    BlockJacobiPreconditioner, IdentityPreconditioner, JacobiPreconditioner, Preconditioner,
+    PreconditionerError, SsorPreconditioner,
+};
+
+mod krylov;
+pub use krylov::{
+    bicgstab, conjugate_gradient, minres, ConvergenceReason, KrylovResult, StoppingCriterion,
+};
+
+mod gmres;
+pub use gmres::{arnoldi, gmres, Arnoldi, GmresOptions, PreconditionSide};
+
+mod sparse_tri_mat;
+pub use sparse_tri_mat::SparseTriMat;
+
+mod sparse_ordering;
+pub use sparse_ordering::{
+    approximate_minimum_degree, bandwidth_and_profile, minimum_degree, reverse_cuthill_mckee,
+    SparseOrdering,
+};
+
+mod sparse_cholesky;
+pub use sparse_cholesky::{SparseCholesky, SymbolicCholesky};
+
+mod sparse_lu;
+pub use sparse_lu::{SparseLu, SymbolicLu};
+
+mod incomplete_factorization;
+pub use incomplete_factorization::{Ic0Preconditioner, Ilu0Preconditioner, IlutPreconditioner};
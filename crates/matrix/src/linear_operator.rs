@@ -0,0 +1,54 @@
+use floating_point::F64;
+
+use crate::{FullMat, Matrix};
+
+// A square linear map R^n -> R^n that is only accessed through products with
+// vectors, so iterative methods can run without forming the matrix.
+pub trait LinearOperator {
+    fn dim(&self) -> usize;
+
+    fn apply(&self, x: &[F64]) -> Vec<F64>;
+}
+
+impl LinearOperator for FullMat<F64> {
+    fn dim(&self) -> usize {
+        assert!(self.is_square());
+        self.col_count()
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        self.mul_vec(x)
+    }
+}
+
+// Row-wise access to the stored entries, needed by splitting methods such as
+// Gauss-Seidel that update one unknown at a time.
+pub trait RowAccess: LinearOperator {
+    fn for_each_in_row<F: FnMut(usize, F64)>(&self, i: usize, f: F);
+
+    fn diagonal(&self) -> Vec<F64> {
+        (0..self.dim())
+            .map(|i| {
+                let mut d = F64::ZERO;
+                self.for_each_in_row(i, |j, x| {
+                    if i == j {
+                        d += x;
+                    }
+                });
+                d
+            })
+            .collect()
+    }
+}
+
+impl RowAccess for FullMat<F64> {
+    fn for_each_in_row<F: FnMut(usize, F64)>(&self, i: usize, mut f: F) {
+        for j in 0..self.col_count() {
+            f(j, self[(i, j)]);
+        }
+    }
+
+    fn diagonal(&self) -> Vec<F64> {
+        (0..self.dim()).map(|i| self[(i, i)]).collect()
+    }
+}
@@ -0,0 +1,118 @@
+use floating_point::F64;
+
+use crate::{qr::HouseholderQr, vector, FullMat, Matrix};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LstsqMethod {
+    // A^T A x = A^T b solved by Cholesky, fast but squares the condition number
+    NormalEquations,
+    QR,
+    // minimum norm solution, also valid for rank-deficient problems
+    SVD,
+}
+
+#[derive(Debug, Clone)]
+pub struct LstsqSolution {
+    pub x: Vec<F64>,
+    pub residual_norm: F64,
+    pub rank: usize,
+    // only available when solved with `LstsqMethod::SVD`
+    pub singular_values: Option<Vec<F64>>,
+}
+
+// Solves min ||A x - b||_2. Returns None if the chosen method breaks down,
+// i.e. A^T A is not positive definite for `NormalEquations`, A is rank
+// deficient for `QR`, or the SVD fails to converge.
+pub fn lstsq(a: &FullMat<F64>, b: &[F64], method: LstsqMethod) -> Option<LstsqSolution> {
+    assert!(a.row_count() == b.len());
+
+    let n = a.col_count();
+    let (x, rank, singular_values) = match method {
+        LstsqMethod::NormalEquations => {
+            let at = a.transpose();
+            let x = at.mul_mat(a).cholesky_solve(&at.mul_vec(b))?;
+            (x, n, None)
+        }
+        LstsqMethod::QR => {
+            if a.row_count() < n {
+                return None;
+            }
+            (HouseholderQr::new(a).solve(b)?, n, None)
+        }
+        LstsqMethod::SVD => {
+            let svd = a.svd()?;
+            let s = &svd.singular_values;
+            let rank = svd.rank();
+
+            let mut x = vec![F64::ZERO; n];
+            for (k, sk) in s.iter().enumerate().take(rank) {
+                let coe = vector::dot(&svd.u.col(k), b) / *sk;
+                for (i, xi) in x.iter_mut().enumerate() {
+                    *xi += coe * svd.v[(i, k)];
+                }
+            }
+            (x, rank, Some(svd.singular_values))
+        }
+    };
+
+    let residual_norm = vector::norm2(&vector::sub(&a.mul_vec(&x), b));
+    Some(LstsqSolution {
+        x,
+        residual_norm,
+        rank,
+        singular_values,
+    })
+}
+
+// Solves min sum w_i (A x - b)_i^2 with positive weights. The reported
+// residual norm is the weighted one.
+pub fn lstsq_weighted(
+    a: &FullMat<F64>,
+    b: &[F64],
+    weights: &[F64],
+    method: LstsqMethod,
+) -> Option<LstsqSolution> {
+    assert!(a.row_count() == weights.len());
+    assert!(weights.iter().all(|w| *w > F64::ZERO));
+
+    let sqrt_w: Vec<F64> = weights.iter().map(F64::sqrt).collect();
+    let mut wa = a.clone();
+    for (i, w) in sqrt_w.iter().enumerate() {
+        for j in 0..a.col_count() {
+            wa[(i, j)] *= *w;
+        }
+    }
+    let wb: Vec<F64> = b.iter().zip(sqrt_w.iter()).map(|(x, w)| x * w).collect();
+
+    lstsq(&wa, &wb, method)
+}
+
+// Solves min ||A x - b||^2 + lambda * ||x||^2 through the augmented system
+// [A; sqrt(lambda) I] x = [b; 0]. The reported residual norm is ||A x - b||.
+pub fn lstsq_tikhonov(
+    a: &FullMat<F64>,
+    b: &[F64],
+    lambda: F64,
+    method: LstsqMethod,
+) -> Option<LstsqSolution> {
+    assert!(a.row_count() == b.len());
+    assert!(lambda >= F64::ZERO);
+
+    let (m, n) = a.shape();
+    let mut aug = FullMat::zeros(m + n, n);
+    for i in 0..m {
+        for j in 0..n {
+            aug[(i, j)] = a[(i, j)];
+        }
+    }
+    let mu = lambda.sqrt();
+    for i in 0..n {
+        aug[(m + i, i)] = mu;
+    }
+    let mut aug_b = b.to_vec();
+    aug_b.resize(m + n, F64::ZERO);
+
+    let mut solution = lstsq(&aug, &aug_b, method)?;
+    solution.residual_norm = vector::norm2(&vector::sub(&a.mul_vec(&solution.x), b));
+    Some(solution)
+}
@@ -0,0 +1,324 @@
+use floating_point::{Complex64, F64};
+
+use crate::{
+    matrix_function::{
+        complex_mul, complex_schur, conjugate_transpose, real_similarity, solve_mat, to_complex,
+        CMat,
+    },
+    FullMat, Matrix,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SylvesterMethod {
+    // both coefficients reduced to Schur form
+    BartelsStewart,
+    // A only to Hessenberg form, which is cheaper when A is the larger one
+    HessenbergSchur,
+}
+
+// `residual` is the 1-norm of the residual of the equation divided by the
+// sum of the 1-norms of its terms, so it is of the order of the unit
+// roundoff for a backward stable solution.
+#[derive(Debug, Clone)]
+pub struct MatrixEquationSolution {
+    pub x: FullMat<F64>,
+    pub residual: F64,
+}
+
+fn relative_residual(residual: &FullMat<F64>, terms: F64) -> F64 {
+    let norm = residual.norm1();
+    if norm == F64::ZERO {
+        F64::ZERO
+    } else {
+        norm / terms
+    }
+}
+
+// y with (alpha M + shift I) y = r for an upper Hessenberg M, by elimination
+// with partial pivoting between neighbouring rows; nothing is eliminated when
+// M is triangular. Entries of M below the subdiagonal are not read. None when
+// the shifted matrix is singular.
+fn solve_shifted_hessenberg(
+    m: &CMat,
+    alpha: Complex64,
+    shift: Complex64,
+    mut r: Vec<Complex64>,
+) -> Option<Vec<Complex64>> {
+    let n = m.col_count();
+    let mut h: Vec<Vec<Complex64>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    if j + 1 < i {
+                        Complex64::ZERO
+                    } else if j == i {
+                        alpha * m[(i, j)] + shift
+                    } else {
+                        alpha * m[(i, j)]
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    for k in 0..n.saturating_sub(1) {
+        if h[k + 1][k] == Complex64::ZERO {
+            continue;
+        }
+        if h[k + 1][k].abs() > h[k][k].abs() {
+            h.swap(k, k + 1);
+            r.swap(k, k + 1);
+        }
+        let l = h[k + 1][k] / h[k][k];
+        let (upper, lower) = h.split_at_mut(k + 1);
+        for (below, above) in lower[0][k..].iter_mut().zip(&upper[k][k..]) {
+            *below -= l * *above;
+        }
+        let above = r[k];
+        r[k + 1] -= l * above;
+    }
+
+    let mut y = vec![Complex64::ZERO; n];
+    for i in (0..n).rev() {
+        if h[i][i] == Complex64::ZERO {
+            return None;
+        }
+        let s: Complex64 = ((i + 1)..n).map(|j| h[i][j] * y[j]).sum();
+        y[i] = (r[i] - s) / h[i][i];
+    }
+    Some(y)
+}
+
+// Y with A Y = B by Gaussian elimination with partial pivoting
+fn complex_solve(a: &CMat, b: &CMat) -> Option<CMat> {
+    let (n, k) = b.shape();
+    let mut a: Vec<Vec<Complex64>> = (0..n).map(|i| a.row_iter(i).copied().collect()).collect();
+    let mut b: Vec<Vec<Complex64>> = (0..n).map(|i| b.row_iter(i).copied().collect()).collect();
+    for j in 0..n {
+        let p =
+            (j..n).max_by(|x, y| a[*x][j].abs().to_f64().total_cmp(&a[*y][j].abs().to_f64()))?;
+        if a[p][j] == Complex64::ZERO || !a[p][j].abs().is_finite() {
+            return None;
+        }
+        a.swap(j, p);
+        b.swap(j, p);
+        let (a_pivot, a_below) = a.split_at_mut(j + 1);
+        let (b_pivot, b_below) = b.split_at_mut(j + 1);
+        for (a_i, b_i) in a_below.iter_mut().zip(b_below) {
+            let l = a_i[j] / a_pivot[j][j];
+            for (x, pivot) in a_i[j..].iter_mut().zip(&a_pivot[j][j..]) {
+                *x -= l * *pivot;
+            }
+            for (x, pivot) in b_i.iter_mut().zip(&b_pivot[j]) {
+                *x -= l * *pivot;
+            }
+        }
+    }
+    let mut y = vec![vec![Complex64::ZERO; k]; n];
+    for i in (0..n).rev() {
+        for c in 0..k {
+            let s: Complex64 = ((i + 1)..n).map(|j| a[i][j] * y[j][c]).sum();
+            y[i][c] = (b[i][c] - s) / a[i][i];
+        }
+    }
+    Some(FullMat::from_rows(y))
+}
+
+// Moves the eigenvalues with negative real parts to the top of the complex
+// Schur form U T U^H by swapping neighbouring diagonal entries, each with a
+// rotation whose first column is the eigenvector of the lower one. Returns
+// how many there are.
+fn reorder_stable_first(u: &mut CMat, t: &mut CMat) -> usize {
+    let n = t.col_count();
+    let mut stable = 0;
+    for i in 0..n {
+        if t[(i, i)].real >= F64::ZERO {
+            continue;
+        }
+        for k in (stable..i).rev() {
+            let (t11, t22) = (t[(k, k)], t[(k + 1, k + 1)]);
+            let (x, y) = (t[(k, k + 1)], t22 - t11);
+            let r = Complex64::from((x.abs().hypot(y.abs()), F64::ZERO));
+            let (c, s) = (x / r, y / r);
+
+            // T Q on the rows above and in the block, U Q on all of them
+            for (mat, rows) in [(&mut *t, k + 2), (&mut *u, n)] {
+                for i in 0..rows {
+                    let (a, b) = (mat[(i, k)], mat[(i, k + 1)]);
+                    mat[(i, k)] = a * c + b * s;
+                    mat[(i, k + 1)] = b * c.conjugate() - a * s.conjugate();
+                }
+            }
+            // Q^H T on the columns from the block on
+            for j in k..n {
+                let (a, b) = (t[(k, j)], t[(k + 1, j)]);
+                t[(k, j)] = c.conjugate() * a + s.conjugate() * b;
+                t[(k + 1, j)] = c * b - s * a;
+            }
+            t[(k, k)] = t22;
+            t[(k + 1, k + 1)] = t11;
+            t[(k + 1, k)] = Complex64::ZERO;
+        }
+        stable += 1;
+    }
+    stable
+}
+
+// A X + X B = C with A m x m, B n x n and C m x n. B is reduced to complex
+// Schur form, so that the columns of the transformed X come out one after
+// the other from shifted systems in the reduced A (Bartels and Stewart;
+// Golub, Nash and Van Loan). None when A and -B share an eigenvalue and the
+// solution is not unique.
+pub fn sylvester(
+    a: &FullMat<F64>,
+    b: &FullMat<F64>,
+    c: &FullMat<F64>,
+    method: SylvesterMethod,
+) -> Option<MatrixEquationSolution> {
+    assert!(a.is_square() && b.is_square());
+    assert!(c.shape() == (a.row_count(), b.row_count()));
+
+    let (q, reduced) = match method {
+        SylvesterMethod::BartelsStewart => complex_schur(a)?,
+        SylvesterMethod::HessenbergSchur => {
+            let (q, h) = a.hessenberg();
+            (to_complex(&q), to_complex(&h))
+        }
+    };
+    let (v, t) = complex_schur(b)?;
+    let f = complex_mul(&complex_mul(&conjugate_transpose(&q), &to_complex(c)), &v);
+
+    let (m, n) = c.shape();
+    let mut y = FullMat::from_vec(n, vec![Complex64::ZERO; m * n]);
+    for j in 0..n {
+        let rhs = (0..m)
+            .map(|i| f[(i, j)] - (0..j).map(|k| y[(i, k)] * t[(k, j)]).sum())
+            .collect();
+        let y_j = solve_shifted_hessenberg(&reduced, Complex64::ONE, t[(j, j)], rhs)?;
+        for (i, y_ij) in y_j.into_iter().enumerate() {
+            y[(i, j)] = y_ij;
+        }
+    }
+    let x = real_similarity(&q, &y, &v);
+
+    let residual = a.mul_mat(&x).add(&x.mul_mat(b)).sub(c);
+    let terms = (a.norm1() + b.norm1()) * x.norm1() + c.norm1();
+    Some(MatrixEquationSolution {
+        residual: relative_residual(&residual, terms),
+        x,
+    })
+}
+
+// The continuous Lyapunov equation A X + X A^T + Q = 0, as the Sylvester
+// equation with B = A^T. X is symmetric when Q is.
+pub fn lyapunov(
+    a: &FullMat<F64>,
+    q: &FullMat<F64>,
+    method: SylvesterMethod,
+) -> Option<MatrixEquationSolution> {
+    assert!(a.shape() == q.shape());
+
+    let minus_q = FullMat::zeros(q.row_count(), q.col_count()).sub(q);
+    sylvester(a, &a.transpose(), &minus_q, method)
+}
+
+// The discrete Lyapunov (Stein) equation A X A^T - X + Q = 0. With
+// A = U S U^H and Y = U^H X U, column j of Y solves
+// (conj(s_jj) S - I) y_j = -f_j - S sum_{l > j} conj(s_jl) y_l,
+// last column first. None when two eigenvalues of A have a product of 1.
+pub fn stein(a: &FullMat<F64>, q: &FullMat<F64>) -> Option<MatrixEquationSolution> {
+    assert!(a.is_square());
+    assert!(a.shape() == q.shape());
+
+    let (u, s) = complex_schur(a)?;
+    let f = complex_mul(&complex_mul(&conjugate_transpose(&u), &to_complex(q)), &u);
+
+    let n = a.col_count();
+    let mut y = FullMat::from_vec(n, vec![Complex64::ZERO; n * n]);
+    for j in (0..n).rev() {
+        let w: Vec<Complex64> = (0..n)
+            .map(|i| {
+                ((j + 1)..n)
+                    .map(|l| s[(j, l)].conjugate() * y[(i, l)])
+                    .sum()
+            })
+            .collect();
+        let rhs = (0..n)
+            .map(|i| -f[(i, j)] - (i..n).map(|k| s[(i, k)] * w[k]).sum())
+            .collect();
+        let y_j = solve_shifted_hessenberg(&s, s[(j, j)].conjugate(), -Complex64::ONE, rhs)?;
+        for (i, y_ij) in y_j.into_iter().enumerate() {
+            y[(i, j)] = y_ij;
+        }
+    }
+    let x = real_similarity(&u, &y, &u);
+
+    let residual = a.mul_mat(&x).mul_mat(&a.transpose()).sub(&x).add(q);
+    let a_norm = a.norm1();
+    let terms = (a_norm * a_norm + F64::ONE) * x.norm1() + q.norm1();
+    Some(MatrixEquationSolution {
+        residual: relative_residual(&residual, terms),
+        x,
+    })
+}
+
+// The stabilizing solution of the continuous algebraic Riccati equation
+// A^T X + X A - X B R^-1 B^T X + Q = 0, from the stable invariant subspace
+// [U1; U2] of the Hamiltonian [[A, -G], [-Q, -A^T]] with G = B R^-1 B^T:
+// X = U2 U1^-1 (Laub). A - G X then has all its eigenvalues in the left half
+// plane. None when the Hamiltonian has eigenvalues on the imaginary axis or
+// U1 is singular, e.g. when (A, B) is not stabilizable.
+pub fn care(
+    a: &FullMat<F64>,
+    b: &FullMat<F64>,
+    q: &FullMat<F64>,
+    r: &FullMat<F64>,
+) -> Option<MatrixEquationSolution> {
+    assert!(a.is_square() && r.is_square());
+    assert!(q.shape() == a.shape());
+    assert!(b.shape() == (a.row_count(), r.row_count()));
+
+    let n = a.col_count();
+    let g = b.mul_mat(&solve_mat(r, &b.transpose()).ok()?);
+    let mut hamiltonian = FullMat::zeros(2 * n, 2 * n);
+    for i in 0..n {
+        for j in 0..n {
+            hamiltonian[(i, j)] = a[(i, j)];
+            hamiltonian[(i, j + n)] = -g[(i, j)];
+            hamiltonian[(i + n, j)] = -q[(i, j)];
+            hamiltonian[(i + n, j + n)] = -a[(j, i)];
+        }
+    }
+
+    let (mut u, mut t) = complex_schur(&hamiltonian)?;
+    if reorder_stable_first(&mut u, &mut t) != n {
+        return None;
+    }
+    // X U1 = U2, solved as U1^T X^T = U2^T
+    let u = &u;
+    let transposed_block = |offset: usize| {
+        let v = (0..n).flat_map(|i| (0..n).map(move |j| u[(j + offset, i)]));
+        FullMat::from_vec(n, v.collect())
+    };
+    let (u1_t, u2_t) = (transposed_block(0), transposed_block(n));
+    let x_t = complex_solve(&u1_t, &u2_t)?;
+    let mut x = FullMat::zeros(n, n);
+    for i in 0..n {
+        for j in 0..n {
+            x[(i, j)] = (x_t[(i, j)].real + x_t[(j, i)].real) / F64::from(2.0);
+        }
+    }
+
+    let at = a.transpose();
+    let residual = at
+        .mul_mat(&x)
+        .add(&x.mul_mat(a))
+        .sub(&x.mul_mat(&g).mul_mat(&x))
+        .add(q);
+    let x_norm = x.norm1();
+    let terms = (F64::from(2.0) * a.norm1() + g.norm1() * x_norm) * x_norm + q.norm1();
+    Some(MatrixEquationSolution {
+        residual: relative_residual(&residual, terms),
+        x,
+    })
+}
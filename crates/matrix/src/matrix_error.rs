@@ -0,0 +1,35 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixError {
+    // the two operands, as (rows, columns), cannot be combined
+    ShapeMismatch {
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    // an operation needing a square matrix got one of the given shape
+    NotSquare((usize, usize)),
+    // elimination found a zero pivot in the given column
+    Singular(usize),
+    // the number of elements is not n (n + 1) / 2 for any n
+    NonTriangularLength(usize),
+    EmptyInput,
+}
+
+impl std::fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ShapeMismatch { left, right } => write!(
+                f,
+                "shapes {}x{} and {}x{} do not match",
+                left.0, left.1, right.0, right.1
+            ),
+            Self::NotSquare((m, n)) => write!(f, "matrix of shape {}x{} is not square", m, n),
+            Self::Singular(k) => write!(f, "matrix is singular at pivot {}", k),
+            Self::NonTriangularLength(len) => {
+                write!(f, "{} elements do not fill a triangular matrix", len)
+            }
+            Self::EmptyInput => write!(f, "empty input"),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
@@ -0,0 +1,528 @@
+use floating_point::{Complex64, F64};
+
+use crate::{nonsymmetric_eigen::Schur, FullMat, Matrix, MatrixError};
+
+// Coefficients of the [m/m] Pade approximants to exp, lowest degree first,
+// and the largest 1-norm for which each one is accurate to double precision
+// (Higham, "The scaling and squaring method for the matrix exponential
+// revisited", 2005, Table 2.3).
+const PADE_3: [f64; 4] = [120.0, 60.0, 12.0, 1.0];
+const PADE_5: [f64; 6] = [30240.0, 15120.0, 3360.0, 420.0, 30.0, 1.0];
+const PADE_7: [f64; 8] = [
+    17297280.0, 8648640.0, 1995840.0, 277200.0, 25200.0, 1512.0, 56.0, 1.0,
+];
+const PADE_9: [f64; 10] = [
+    17643225600.0,
+    8821612800.0,
+    2075673600.0,
+    302702400.0,
+    30270240.0,
+    2162160.0,
+    110880.0,
+    3960.0,
+    90.0,
+    1.0,
+];
+const PADE_13: [f64; 14] = [
+    64764752532480000.0,
+    32382376266240000.0,
+    7771770303897600.0,
+    1187353796428800.0,
+    129060195264000.0,
+    10559470521600.0,
+    670442572800.0,
+    33522128640.0,
+    1323241920.0,
+    40840800.0,
+    960960.0,
+    16380.0,
+    182.0,
+    1.0,
+];
+const PADE_THETAS: [(&[f64], f64); 4] = [
+    (&PADE_3, 1.495585217958292e-2),
+    (&PADE_5, 2.53939833006323e-1),
+    (&PADE_7, 9.504178996162932e-1),
+    (&PADE_9, 2.097847961257068e0),
+];
+const THETA_13: f64 = 5.371920351148152;
+
+// Gauss-Legendre nodes and weights on [0, 1] for
+// log(I + X) = int_0^1 X (I + t X)^-1 dt, which give the [8/8] Pade
+// approximant to the logarithm.
+const LOG_NODES: [(f64, f64); 8] = [
+    (0.019855071751231856, 0.05061426814518809),
+    (0.10166676129318658, 0.11119051722668723),
+    (0.2372337950418355, 0.1568533229389437),
+    (0.4082826787521751, 0.181341891689181),
+    (0.591717321247825, 0.181341891689181),
+    (0.7627662049581645, 0.1568533229389437),
+    (0.8983332387068135, 0.11119051722668723),
+    (0.9801449282487682, 0.05061426814518809),
+];
+// ||T - I||_1 below which the [8/8] approximant is used
+const LOG_PADE_RADIUS: f64 = 0.25;
+const MAX_SQUARE_ROOTS: i32 = 64;
+
+// Largest 1-norm for which m terms of the Taylor series of exp, applied s
+// times, keep the backward error below 2^-53 (Al-Mohy and Higham, "Computing
+// the action of the matrix exponential", 2011, Table 3.1).
+const TAYLOR_THETAS: [(usize, f64); 35] = [
+    (1, 2.29e-16),
+    (2, 2.58e-8),
+    (3, 1.39e-5),
+    (4, 3.40e-4),
+    (5, 2.40e-3),
+    (6, 9.07e-3),
+    (7, 2.38e-2),
+    (8, 5.00e-2),
+    (9, 8.96e-2),
+    (10, 1.44e-1),
+    (11, 2.14e-1),
+    (12, 3.00e-1),
+    (13, 4.00e-1),
+    (14, 5.14e-1),
+    (15, 6.41e-1),
+    (16, 7.81e-1),
+    (17, 9.31e-1),
+    (18, 1.09),
+    (19, 1.26),
+    (20, 1.44),
+    (21, 1.62),
+    (22, 1.82),
+    (23, 2.01),
+    (24, 2.22),
+    (25, 2.43),
+    (26, 2.64),
+    (27, 2.86),
+    (28, 3.08),
+    (29, 3.31),
+    (30, 3.54),
+    (35, 4.7),
+    (40, 6.0),
+    (45, 7.2),
+    (50, 8.5),
+    (55, 9.9),
+];
+
+pub(crate) type CMat = FullMat<Complex64>;
+
+fn linear_combination(terms: &[(f64, &FullMat<F64>)]) -> FullMat<F64> {
+    let (m, n) = terms[0].1.shape();
+    let mut sum = FullMat::zeros(m, n);
+    for (c, mat) in terms {
+        let c = F64::from(*c);
+        for i in 0..m {
+            for j in 0..n {
+                sum[(i, j)] += c * mat[(i, j)];
+            }
+        }
+    }
+    sum
+}
+
+// X with A X = B, one column at a time from a single factorization
+pub(crate) fn solve_mat(a: &FullMat<F64>, b: &FullMat<F64>) -> Result<FullMat<F64>, MatrixError> {
+    let (l, u, p) = a.try_lu()?;
+    let cols = (0..b.col_count())
+        .map(|j| u.solve(l.solve(p.iter().map(|i| b[(*i, j)]).collect())))
+        .collect();
+    Ok(FullMat::from_cols(cols))
+}
+
+fn complex(x: f64) -> Complex64 {
+    Complex64::new(x, 0.0)
+}
+
+// the principal square root, with a branch cut along the negative real axis
+fn complex_sqrt(z: Complex64) -> Complex64 {
+    let r = z.abs();
+    if r == F64::ZERO {
+        return Complex64::ZERO;
+    }
+    // the larger part first, the other one without cancellation
+    let root = ((r + z.real.abs()) / F64::from(2.0)).sqrt();
+    let other = z.imag / (F64::from(2.0) * root);
+    if z.real >= F64::ZERO {
+        Complex64::from((root, other))
+    } else {
+        Complex64::from((other.abs(), root.copysign(z.imag)))
+    }
+}
+
+fn complex_ln(z: Complex64) -> Complex64 {
+    let (x, y) = (z.real.to_f64(), z.imag.to_f64());
+    Complex64::new(z.abs().to_f64().ln(), y.atan2(x))
+}
+
+pub(crate) fn to_complex(a: &FullMat<F64>) -> CMat {
+    let n = a.col_count();
+    FullMat::from_vec(
+        n,
+        a.iter().map(|x| Complex64::from((*x, F64::ZERO))).collect(),
+    )
+}
+
+fn complex_identity(n: usize) -> CMat {
+    let mut mat = FullMat::from_vec(n, vec![Complex64::ZERO; n * n]);
+    for i in 0..n {
+        mat[(i, i)] = Complex64::ONE;
+    }
+    mat
+}
+
+pub(crate) fn complex_mul(a: &CMat, b: &CMat) -> CMat {
+    let (m, l, n) = (a.row_count(), a.col_count(), b.col_count());
+    let mut v = Vec::with_capacity(m * n);
+    for i in 0..m {
+        for j in 0..n {
+            v.push((0..l).map(|k| a[(i, k)] * b[(k, j)]).sum());
+        }
+    }
+    FullMat::from_vec(n, v)
+}
+
+fn complex_norm1(a: &CMat) -> F64 {
+    (0..a.col_count()).fold(F64::ZERO, |max, j| {
+        let sum = a.col_iter(j).fold(F64::ZERO, |sum, x| sum + x.abs());
+        max.max(sum)
+    })
+}
+
+// The real part of U R V^H. With V = U it is the whole result when R is a
+// primary function of the complex Schur form of a real matrix.
+pub(crate) fn real_similarity(u: &CMat, r: &CMat, v: &CMat) -> FullMat<F64> {
+    let product = complex_mul(&complex_mul(u, r), &conjugate_transpose(v));
+    FullMat::from_vec(
+        product.col_count(),
+        product.iter().map(|z| z.real).collect(),
+    )
+}
+
+pub(crate) fn conjugate_transpose(a: &CMat) -> CMat {
+    let (m, n) = a.shape();
+    FullMat::from_vec(
+        m,
+        (0..n)
+            .flat_map(|i| (0..m).map(move |j| a[(j, i)].conjugate()))
+            .collect(),
+    )
+}
+
+// Complex Schur form A = U T U^H from the real one, each 2 x 2 block split by
+// a complex Givens rotation that moves one of its eigenvalues to the top
+// (as MATLAB's rsf2csf).
+pub(crate) fn complex_schur(a: &FullMat<F64>) -> Option<(CMat, CMat)> {
+    let Schur { z, t } = a.schur()?;
+    let n = a.col_count();
+    let (mut u, mut t) = (to_complex(&z), to_complex(&t));
+
+    for m in (1..n).rev() {
+        if t[(m, m - 1)] == Complex64::ZERO {
+            continue;
+        }
+        let (a, b, c, d) = (t[(m - 1, m - 1)], t[(m - 1, m)], t[(m, m - 1)], t[(m, m)]);
+        let half = (a - d) * complex(0.5);
+        let mu = half + complex_sqrt(half * half + b * c);
+        let r = complex(mu.abs().hypot(c.abs()).to_f64());
+        let (cs, sn) = (mu / r, c / r);
+
+        for j in (m - 1)..n {
+            let (x, y) = (t[(m - 1, j)], t[(m, j)]);
+            t[(m - 1, j)] = cs.conjugate() * x + sn * y;
+            t[(m, j)] = cs * y - sn * x;
+        }
+        // T G^H on the rows above the block, U G^H on all of them
+        for (mat, rows) in [(&mut t, m + 1), (&mut u, n)] {
+            for i in 0..rows {
+                let (x, y) = (mat[(i, m - 1)], mat[(i, m)]);
+                mat[(i, m - 1)] = x * cs + y * sn;
+                mat[(i, m)] = y * cs.conjugate() - x * sn;
+            }
+        }
+        t[(m, m - 1)] = Complex64::ZERO;
+    }
+    Some((u, t))
+}
+
+// The principal square root of an upper triangular matrix, column by column
+// (Bjorck and Hammarling). None when two diagonal roots cancel.
+fn sqrtm_triangular(t: &CMat) -> Option<CMat> {
+    let n = t.col_count();
+    let mut r = FullMat::from_vec(n, vec![Complex64::ZERO; n * n]);
+    for j in 0..n {
+        r[(j, j)] = complex_sqrt(t[(j, j)]);
+        for i in (0..j).rev() {
+            let s: Complex64 = ((i + 1)..j).map(|k| r[(i, k)] * r[(k, j)]).sum();
+            let denominator = r[(i, i)] + r[(j, j)];
+            if denominator == Complex64::ZERO {
+                return None;
+            }
+            r[(i, j)] = (t[(i, j)] - s) / denominator;
+        }
+    }
+    Some(r)
+}
+
+// Y with M Y = X for upper triangular M and X
+fn solve_upper_triangular(m: &CMat, x: &CMat) -> CMat {
+    let n = m.col_count();
+    let mut y = FullMat::from_vec(n, vec![Complex64::ZERO; n * n]);
+    for j in 0..n {
+        for i in (0..=j).rev() {
+            let s: Complex64 = ((i + 1)..=j).map(|k| m[(i, k)] * y[(k, j)]).sum();
+            y[(i, j)] = (x[(i, j)] - s) / m[(i, i)];
+        }
+    }
+    y
+}
+
+// The principal logarithm of an upper triangular matrix by inverse scaling
+// and squaring: square roots until T is close to I, the Pade approximant of
+// log(I + X) there, and the result scaled back by 2^k. None when an
+// eigenvalue is on the closed negative real axis.
+fn logm_triangular(t: &CMat) -> Option<CMat> {
+    let n = t.col_count();
+    let diagonal: Vec<Complex64> = (0..n).map(|i| t[(i, i)]).collect();
+    if diagonal
+        .iter()
+        .any(|z| z.imag == F64::ZERO && z.real <= F64::ZERO)
+    {
+        return None;
+    }
+
+    let identity = complex_identity(n);
+    let minus_identity = |t: &CMat| {
+        FullMat::from_vec(
+            n,
+            t.iter()
+                .zip(identity.iter())
+                .map(|(a, b)| *a - *b)
+                .collect(),
+        )
+    };
+    let mut root = t.clone();
+    let mut k = 0;
+    while complex_norm1(&minus_identity(&root)) > F64::from(LOG_PADE_RADIUS) {
+        if k == MAX_SQUARE_ROOTS {
+            return None;
+        }
+        root = sqrtm_triangular(&root)?;
+        k += 1;
+    }
+
+    let x = minus_identity(&root);
+    let mut log = FullMat::from_vec(n, vec![Complex64::ZERO; n * n]);
+    for (node, weight) in LOG_NODES {
+        let m = FullMat::from_vec(
+            n,
+            identity
+                .iter()
+                .zip(x.iter())
+                .map(|(i, x)| *i + complex(node) * *x)
+                .collect(),
+        );
+        let y = solve_upper_triangular(&m, &x);
+        for i in 0..n {
+            for j in i..n {
+                log[(i, j)] += complex(weight) * y[(i, j)];
+            }
+        }
+    }
+    let scale = complex(2f64.powi(k));
+    for i in 0..n {
+        for j in i..n {
+            log[(i, j)] *= scale;
+        }
+        // the scalar logarithms are known to full accuracy
+        log[(i, i)] = complex_ln(diagonal[i]);
+    }
+    Some(log)
+}
+
+impl FullMat<F64> {
+    // exp(A) by scaling and squaring with Pade approximants of degree 3 to 13
+    // (Higham 2005). The degree and the number of squarings are chosen from
+    // ||A||_1 so that the backward error is at the level of rounding.
+    pub fn expm(&self) -> FullMat<F64> {
+        assert!(self.is_square());
+        assert!(self.iter().all(|x| x.is_finite()));
+
+        let n = self.col_count();
+        let identity = FullMat::identity(n);
+        let norm = self.norm1().to_f64();
+        // finite entries can still sum to an infinite norm, which would ask
+        // for endless squarings
+        assert!(norm.is_finite());
+
+        let low_degree = PADE_THETAS.iter().find(|(_, theta)| norm <= *theta);
+        let (u, v, squarings) = if let Some((b, _)) = low_degree {
+            // the even powers A^2, A^4, ... up to the degree
+            let mut powers = vec![identity, self.mul_mat(self)];
+            while 2 * powers.len() < b.len() {
+                let next = powers[powers.len() - 1].mul_mat(&powers[1]);
+                powers.push(next);
+            }
+            let odd: Vec<(f64, &FullMat<F64>)> = powers
+                .iter()
+                .enumerate()
+                .map(|(k, p)| (b[2 * k + 1], p))
+                .collect();
+            let even: Vec<(f64, &FullMat<F64>)> = powers
+                .iter()
+                .enumerate()
+                .map(|(k, p)| (b[2 * k], p))
+                .collect();
+            let u = self.mul_mat(&linear_combination(&odd));
+            (u, linear_combination(&even), 0)
+        } else {
+            let squarings = (norm / THETA_13).log2().ceil().max(0.0) as i32;
+            let a = linear_combination(&[(0.5f64.powi(squarings), self)]);
+            // A^2 of the scaled A, as that of A itself may overflow
+            let a2 = a.mul_mat(&a);
+            let a4 = a2.mul_mat(&a2);
+            let a6 = a4.mul_mat(&a2);
+            let b = PADE_13;
+
+            let inner = linear_combination(&[(b[13], &a6), (b[11], &a4), (b[9], &a2)]);
+            let outer =
+                linear_combination(&[(b[7], &a6), (b[5], &a4), (b[3], &a2), (b[1], &identity)]);
+            let u = a.mul_mat(&a6.mul_mat(&inner).add(&outer));
+
+            let inner = linear_combination(&[(b[12], &a6), (b[10], &a4), (b[8], &a2)]);
+            let outer =
+                linear_combination(&[(b[6], &a6), (b[4], &a4), (b[2], &a2), (b[0], &identity)]);
+            let v = a6.mul_mat(&inner).add(&outer);
+            (u, v, squarings)
+        };
+
+        // (V - U) R = V + U; V - U is well conditioned within the thetas
+        let mut r = solve_mat(&v.sub(&u), &v.add(&u)).unwrap();
+        for _ in 0..squarings {
+            r = r.mul_mat(&r);
+        }
+        r
+    }
+
+    // The principal square root, the one whose eigenvalues have positive real
+    // parts, from the complex Schur form. None when A has a negative real
+    // eigenvalue or a repeated zero one, where no real principal root exists.
+    pub fn sqrtm(&self) -> Option<FullMat<F64>> {
+        assert!(self.is_square());
+
+        let (u, t) = complex_schur(self)?;
+        if (0..t.col_count()).any(|i| t[(i, i)].imag == F64::ZERO && t[(i, i)].real < F64::ZERO) {
+            return None;
+        }
+        let r = sqrtm_triangular(&t)?;
+        Some(real_similarity(&u, &r, &u))
+    }
+
+    // The principal logarithm, whose eigenvalues have imaginary parts in
+    // (-pi, pi), by inverse scaling and squaring on the complex Schur form.
+    // None when A has an eigenvalue on the closed negative real axis.
+    pub fn logm(&self) -> Option<FullMat<F64>> {
+        assert!(self.is_square());
+
+        let (u, t) = complex_schur(self)?;
+        let log = logm_triangular(&t)?;
+        Some(real_similarity(&u, &log, &u))
+    }
+
+    // A^k by repeated squaring, through the inverse for negative k. None when
+    // k < 0 and A is singular.
+    pub fn powi(&self, k: i32) -> Option<FullMat<F64>> {
+        assert!(self.is_square());
+
+        let mut base = if k < 0 { self.inv()? } else { self.clone() };
+        let mut power = FullMat::identity(self.col_count());
+        let mut k = k.unsigned_abs();
+        while k > 0 {
+            if k & 1 == 1 {
+                power = power.mul_mat(&base);
+            }
+            k >>= 1;
+            if k > 0 {
+                base = base.mul_mat(&base);
+            }
+        }
+        Some(power)
+    }
+
+    // The principal power A^p = A^q exp(f log A) with q = floor(p) and
+    // f = p - q. Integer p goes through `powi`. None when p is not an integer
+    // and `logm` fails, or when the integer part needs the inverse of a
+    // singular A.
+    pub fn powm(&self, p: F64) -> Option<FullMat<F64>> {
+        assert!(self.is_square());
+        assert!(p.is_finite());
+
+        let p = p.to_f64();
+        let q = p.floor();
+        assert!(q.abs() <= i32::MAX as f64);
+        let integer = self.powi(q as i32)?;
+        if p == q {
+            return Some(integer);
+        }
+        let log = self.logm()?;
+        let fraction = linear_combination(&[(p - q, &log)]).expm();
+        Some(integer.mul_mat(&fraction))
+    }
+
+    // exp(A) v without forming exp(A), by s steps of a truncated Taylor
+    // series of exp(A / s), after shifting A by the mean of its eigenvalues
+    // (Al-Mohy and Higham 2011). Only products A x are needed.
+    pub fn expm_multiply(&self, v: &[F64]) -> Vec<F64> {
+        assert!(self.is_square());
+        assert!(v.len() == self.col_count());
+
+        let n = self.col_count();
+        let trace = (0..n).fold(F64::ZERO, |sum, i| sum + self[(i, i)]);
+        let mu = trace / F64::from(n as f64);
+        let mut shifted = self.clone();
+        for i in 0..n {
+            shifted[(i, i)] -= mu;
+        }
+        let (a, mu) = if shifted.norm1() < self.norm1() {
+            (shifted, mu)
+        } else {
+            (self.clone(), F64::ZERO)
+        };
+
+        let norm = a.norm1().to_f64();
+        let (m, s) = if norm == 0.0 {
+            (0, 1)
+        } else {
+            TAYLOR_THETAS
+                .iter()
+                .map(|(m, theta)| (*m, ((norm / theta).ceil() as usize).max(1)))
+                .min_by_key(|(m, s)| m * s)
+                .unwrap()
+        };
+
+        let tolerance = F64::from(2.0f64.powi(-53));
+        let norm_inf = |x: &[F64]| x.iter().fold(F64::ZERO, |max, x| max.max(x.abs()));
+        let eta = F64::from((mu.to_f64() / s as f64).exp());
+        let mut f = v.to_vec();
+        for _ in 0..s {
+            let mut b = f.clone();
+            let mut c1 = norm_inf(&b);
+            for j in 1..=m {
+                let coefficient = F64::from(1.0 / (s * j) as f64);
+                b = a.mul_vec(&b).into_iter().map(|x| x * coefficient).collect();
+                let c2 = norm_inf(&b);
+                for (f, b) in f.iter_mut().zip(&b) {
+                    *f += *b;
+                }
+                if c1 + c2 <= tolerance * norm_inf(&f) {
+                    break;
+                }
+                c1 = c2;
+            }
+            for f in f.iter_mut() {
+                *f *= eta;
+            }
+        }
+        f
+    }
+}
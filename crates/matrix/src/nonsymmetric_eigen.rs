@@ -0,0 +1,651 @@
+use floating_point::{Complex64, F64};
+
+use crate::{FullMat, Matrix};
+
+const MAX_QR_ITERATIONS: usize = 60;
+
+// Real Schur decomposition A = Z * T * Z^T. T is quasi upper triangular: the
+// real eigenvalues sit on its diagonal and every complex conjugate pair forms
+// a 2 x 2 diagonal block.
+#[derive(Debug, Clone)]
+pub struct Schur {
+    pub z: FullMat<F64>,
+    pub t: FullMat<F64>,
+}
+
+// Eigenvalues in the order they appear on the diagonal of the Schur form,
+// with complex conjugate pairs adjacent and the positive imaginary part
+// first. eigenvectors[k] has unit 2-norm.
+#[derive(Debug, Clone)]
+pub struct Eigen {
+    pub eigenvalues: Vec<Complex64>,
+    pub eigenvectors: Vec<Vec<Complex64>>,
+}
+
+// Reduces `h` to upper Hessenberg form by Householder similarity transforms
+// and returns the accumulated orthogonal transform.
+fn hessenberg(h: &mut FullMat<F64>) -> FullMat<F64> {
+    let n = h.col_count();
+    let mut ort = vec![F64::ZERO; n];
+
+    for m in 1..n.saturating_sub(1) {
+        let mut scale = F64::ZERO;
+        for i in m..n {
+            scale += h[(i, m - 1)].abs();
+        }
+        if scale == F64::ZERO {
+            continue;
+        }
+
+        let mut sum = F64::ZERO;
+        for i in (m..n).rev() {
+            ort[i] = h[(i, m - 1)] / scale;
+            sum += ort[i] * ort[i];
+        }
+        let g = -sum.sqrt().copysign(ort[m]);
+        sum -= ort[m] * g;
+        ort[m] -= g;
+
+        // H = (I - u u^T / sum) * H * (I - u u^T / sum)
+        for j in m..n {
+            let mut f = F64::ZERO;
+            for i in (m..n).rev() {
+                f += ort[i] * h[(i, j)];
+            }
+            f /= sum;
+            for i in m..n {
+                h[(i, j)] -= f * ort[i];
+            }
+        }
+        for i in 0..n {
+            let mut f = F64::ZERO;
+            for j in (m..n).rev() {
+                f += ort[j] * h[(i, j)];
+            }
+            f /= sum;
+            for j in m..n {
+                h[(i, j)] -= f * ort[j];
+            }
+        }
+        ort[m] *= scale;
+        h[(m, m - 1)] = scale * g;
+    }
+
+    let mut v = FullMat::identity(n);
+    for m in (1..n.saturating_sub(1)).rev() {
+        if h[(m, m - 1)] == F64::ZERO {
+            continue;
+        }
+        for i in (m + 1)..n {
+            ort[i] = h[(i, m - 1)];
+        }
+        for j in m..n {
+            let mut g = F64::ZERO;
+            for i in m..n {
+                g += ort[i] * v[(i, j)];
+            }
+            // double division avoids possible underflow
+            g = (g / ort[m]) / h[(m, m - 1)];
+            for i in m..n {
+                v[(i, j)] += g * ort[i];
+            }
+        }
+    }
+
+    for i in 0..n {
+        for j in 0..i.saturating_sub(1) {
+            h[(i, j)] = F64::ZERO;
+        }
+    }
+    v
+}
+
+// Francis double-shift QR on the Hessenberg matrix `h`, accumulating the
+// transforms into `v`. On success `h` holds the real Schur form (up to
+// negligible subdiagonal entries) and the eigenvalues are returned as real
+// and imaginary parts together with the norm of the Hessenberg matrix.
+fn francis_qr(h: &mut FullMat<F64>, v: &mut FullMat<F64>) -> Option<(Vec<F64>, Vec<F64>, F64)> {
+    let nn = h.col_count();
+    let eps = F64::EPSILON;
+    let mut d = vec![F64::ZERO; nn];
+    let mut e = vec![F64::ZERO; nn];
+    let mut exshift = F64::ZERO;
+    let (mut p, mut q, mut r, mut s, mut z);
+    let (mut w, mut x, mut y);
+
+    let mut norm = F64::ZERO;
+    for i in 0..nn {
+        for j in i.saturating_sub(1)..nn {
+            norm += h[(i, j)].abs();
+        }
+    }
+
+    let mut n = nn as isize - 1;
+    let mut iter = 0;
+    while n >= 0 {
+        let nu = n as usize;
+
+        // look for a single small subdiagonal element
+        let mut l = nu;
+        while l > 0 {
+            s = h[(l - 1, l - 1)].abs() + h[(l, l)].abs();
+            if s == F64::ZERO {
+                s = norm;
+            }
+            if h[(l, l - 1)].abs() <= eps * s {
+                break;
+            }
+            l -= 1;
+        }
+
+        if l == nu {
+            // one root found
+            h[(nu, nu)] += exshift;
+            d[nu] = h[(nu, nu)];
+            e[nu] = F64::ZERO;
+            n -= 1;
+            iter = 0;
+        } else if l + 1 == nu {
+            // two roots found
+            w = h[(nu, nu - 1)] * h[(nu - 1, nu)];
+            p = (h[(nu - 1, nu - 1)] - h[(nu, nu)]) / F64::from(2.0);
+            q = p * p + w;
+            z = q.abs().sqrt();
+            h[(nu, nu)] += exshift;
+            h[(nu - 1, nu - 1)] += exshift;
+            x = h[(nu, nu)];
+
+            if q >= F64::ZERO {
+                // real pair, rotate the block to upper triangular form
+                z = if p >= F64::ZERO { p + z } else { p - z };
+                d[nu - 1] = x + z;
+                d[nu] = d[nu - 1];
+                if z != F64::ZERO {
+                    d[nu] = x - w / z;
+                }
+                e[nu - 1] = F64::ZERO;
+                e[nu] = F64::ZERO;
+                x = h[(nu, nu - 1)];
+                s = x.abs() + z.abs();
+                p = x / s;
+                q = z / s;
+                r = (p * p + q * q).sqrt();
+                p /= r;
+                q /= r;
+
+                for j in (nu - 1)..nn {
+                    z = h[(nu - 1, j)];
+                    h[(nu - 1, j)] = q * z + p * h[(nu, j)];
+                    h[(nu, j)] = q * h[(nu, j)] - p * z;
+                }
+                for i in 0..=nu {
+                    z = h[(i, nu - 1)];
+                    h[(i, nu - 1)] = q * z + p * h[(i, nu)];
+                    h[(i, nu)] = q * h[(i, nu)] - p * z;
+                }
+                for i in 0..nn {
+                    z = v[(i, nu - 1)];
+                    v[(i, nu - 1)] = q * z + p * v[(i, nu)];
+                    v[(i, nu)] = q * v[(i, nu)] - p * z;
+                }
+                h[(nu, nu - 1)] = F64::ZERO;
+            } else {
+                // complex pair
+                d[nu - 1] = x + p;
+                d[nu] = x + p;
+                e[nu - 1] = z;
+                e[nu] = -z;
+            }
+            n -= 2;
+            iter = 0;
+        } else {
+            // form shift
+            x = h[(nu, nu)];
+            y = h[(nu - 1, nu - 1)];
+            w = h[(nu, nu - 1)] * h[(nu - 1, nu)];
+
+            // Wilkinson's original ad hoc shift
+            if iter == 10 {
+                exshift += x;
+                for i in 0..=nu {
+                    h[(i, i)] -= x;
+                }
+                s = h[(nu, nu - 1)].abs() + h[(nu - 1, nu - 2)].abs();
+                x = F64::from(0.75) * s;
+                y = x;
+                w = F64::from(-0.4375) * s * s;
+            }
+
+            // MATLAB's ad hoc shift
+            if iter == 30 {
+                s = (y - x) / F64::from(2.0);
+                s = s * s + w;
+                if s > F64::ZERO {
+                    s = s.sqrt();
+                    if y < x {
+                        s = -s;
+                    }
+                    s = x - w / ((y - x) / F64::from(2.0) + s);
+                    for i in 0..=nu {
+                        h[(i, i)] -= s;
+                    }
+                    exshift += s;
+                    x = F64::from(0.964);
+                    y = x;
+                    w = x;
+                }
+            }
+
+            if iter == MAX_QR_ITERATIONS {
+                return None;
+            }
+            iter += 1;
+
+            // look for two consecutive small subdiagonal elements
+            let mut m = nu - 2;
+            loop {
+                z = h[(m, m)];
+                r = x - z;
+                s = y - z;
+                p = (r * s - w) / h[(m + 1, m)] + h[(m, m + 1)];
+                q = h[(m + 1, m + 1)] - z - r - s;
+                r = h[(m + 2, m + 1)];
+                s = p.abs() + q.abs() + r.abs();
+                p /= s;
+                q /= s;
+                r /= s;
+                if m == l {
+                    break;
+                }
+                if h[(m, m - 1)].abs() * (q.abs() + r.abs())
+                    < eps
+                        * (p.abs() * (h[(m - 1, m - 1)].abs() + z.abs() + h[(m + 1, m + 1)].abs()))
+                {
+                    break;
+                }
+                m -= 1;
+            }
+
+            for i in (m + 2)..=nu {
+                h[(i, i - 2)] = F64::ZERO;
+                if i > m + 2 {
+                    h[(i, i - 3)] = F64::ZERO;
+                }
+            }
+
+            // double QR step involving rows l..=n and columns m..=n
+            for k in m..nu {
+                let notlast = k + 1 != nu;
+                if k != m {
+                    p = h[(k, k - 1)];
+                    q = h[(k + 1, k - 1)];
+                    r = if notlast {
+                        h[(k + 2, k - 1)]
+                    } else {
+                        F64::ZERO
+                    };
+                    x = p.abs() + q.abs() + r.abs();
+                    if x == F64::ZERO {
+                        continue;
+                    }
+                    p /= x;
+                    q /= x;
+                    r /= x;
+                }
+
+                s = (p * p + q * q + r * r).sqrt().copysign(p);
+                if s == F64::ZERO {
+                    continue;
+                }
+                if k != m {
+                    h[(k, k - 1)] = -s * x;
+                } else if l != m {
+                    h[(k, k - 1)] = -h[(k, k - 1)];
+                }
+                p += s;
+                x = p / s;
+                y = q / s;
+                z = r / s;
+                q /= p;
+                r /= p;
+
+                for j in k..nn {
+                    p = h[(k, j)] + q * h[(k + 1, j)];
+                    if notlast {
+                        p += r * h[(k + 2, j)];
+                        h[(k + 2, j)] -= p * z;
+                    }
+                    h[(k, j)] -= p * x;
+                    h[(k + 1, j)] -= p * y;
+                }
+                for i in 0..=nu.min(k + 3) {
+                    p = x * h[(i, k)] + y * h[(i, k + 1)];
+                    if notlast {
+                        p += z * h[(i, k + 2)];
+                        h[(i, k + 2)] -= p * r;
+                    }
+                    h[(i, k)] -= p;
+                    h[(i, k + 1)] -= p * q;
+                }
+                for i in 0..nn {
+                    p = x * v[(i, k)] + y * v[(i, k + 1)];
+                    if notlast {
+                        p += z * v[(i, k + 2)];
+                        v[(i, k + 2)] -= p * r;
+                    }
+                    v[(i, k)] -= p;
+                    v[(i, k + 1)] -= p * q;
+                }
+            }
+        }
+    }
+
+    Some((d, e, norm))
+}
+
+// Complex division (xr + i xi) / (yr + i yi) without intermediate overflow.
+fn cdiv(xr: F64, xi: F64, yr: F64, yi: F64) -> (F64, F64) {
+    if yr.abs() > yi.abs() {
+        let r = yi / yr;
+        let d = yr + r * yi;
+        ((xr + r * xi) / d, (xi - r * xr) / d)
+    } else {
+        let r = yr / yi;
+        let d = yi + r * yr;
+        ((r * xr + xi) / d, (r * xi - xr) / d)
+    }
+}
+
+// Computes the eigenvectors of the quasi-triangular `h` by back-substitution
+// and transforms them with `v`. On return the columns of `v` hold the real
+// eigenvectors, and the real and imaginary parts of the complex ones.
+fn back_substitute(h: &mut FullMat<F64>, v: &mut FullMat<F64>, d: &[F64], e: &[F64], norm: F64) {
+    let nn = h.col_count();
+    let eps = F64::EPSILON;
+    if norm == F64::ZERO {
+        return;
+    }
+    let (mut r, mut s, mut z) = (F64::ZERO, F64::ZERO, F64::ZERO);
+
+    for n in (0..nn).rev() {
+        let p = d[n];
+        let q = e[n];
+
+        if q == F64::ZERO {
+            // real vector
+            let mut l = n;
+            h[(n, n)] = F64::ONE;
+            for i in (0..n).rev() {
+                let w = h[(i, i)] - p;
+                r = F64::ZERO;
+                for j in l..=n {
+                    r += h[(i, j)] * h[(j, n)];
+                }
+                if e[i] < F64::ZERO {
+                    z = w;
+                    s = r;
+                    continue;
+                }
+                l = i;
+                if e[i] == F64::ZERO {
+                    h[(i, n)] = if w != F64::ZERO {
+                        -r / w
+                    } else {
+                        -r / (eps * norm)
+                    };
+                } else {
+                    let x = h[(i, i + 1)];
+                    let y = h[(i + 1, i)];
+                    let q = (d[i] - p) * (d[i] - p) + e[i] * e[i];
+                    let t = (x * s - z * r) / q;
+                    h[(i, n)] = t;
+                    h[(i + 1, n)] = if x.abs() > z.abs() {
+                        (-r - w * t) / x
+                    } else {
+                        (-s - y * t) / z
+                    };
+                }
+
+                // overflow control
+                let t = h[(i, n)].abs();
+                if (eps * t) * t > F64::ONE {
+                    for j in i..=n {
+                        h[(j, n)] /= t;
+                    }
+                }
+            }
+        } else if q < F64::ZERO {
+            // complex vector, last component imaginary so the matrix is triangular
+            let mut l = n - 1;
+            if h[(n, n - 1)].abs() > h[(n - 1, n)].abs() {
+                h[(n - 1, n - 1)] = q / h[(n, n - 1)];
+                h[(n - 1, n)] = -(h[(n, n)] - p) / h[(n, n - 1)];
+            } else {
+                let (cr, ci) = cdiv(F64::ZERO, -h[(n - 1, n)], h[(n - 1, n - 1)] - p, q);
+                h[(n - 1, n - 1)] = cr;
+                h[(n - 1, n)] = ci;
+            }
+            h[(n, n - 1)] = F64::ZERO;
+            h[(n, n)] = F64::ONE;
+
+            for i in (0..n.saturating_sub(1)).rev() {
+                let mut ra = F64::ZERO;
+                let mut sa = F64::ZERO;
+                for j in l..=n {
+                    ra += h[(i, j)] * h[(j, n - 1)];
+                    sa += h[(i, j)] * h[(j, n)];
+                }
+                let w = h[(i, i)] - p;
+
+                if e[i] < F64::ZERO {
+                    z = w;
+                    r = ra;
+                    s = sa;
+                    continue;
+                }
+                l = i;
+                if e[i] == F64::ZERO {
+                    let (cr, ci) = cdiv(-ra, -sa, w, q);
+                    h[(i, n - 1)] = cr;
+                    h[(i, n)] = ci;
+                } else {
+                    // solve complex equations
+                    let x = h[(i, i + 1)];
+                    let y = h[(i + 1, i)];
+                    let mut vr = (d[i] - p) * (d[i] - p) + e[i] * e[i] - q * q;
+                    let vi = (d[i] - p) * F64::from(2.0) * q;
+                    if vr == F64::ZERO && vi == F64::ZERO {
+                        vr = eps * norm * (w.abs() + q.abs() + x.abs() + y.abs() + z.abs());
+                    }
+                    let (cr, ci) = cdiv(x * r - z * ra + q * sa, x * s - z * sa - q * ra, vr, vi);
+                    h[(i, n - 1)] = cr;
+                    h[(i, n)] = ci;
+                    if x.abs() > z.abs() + q.abs() {
+                        h[(i + 1, n - 1)] = (-ra - w * h[(i, n - 1)] + q * h[(i, n)]) / x;
+                        h[(i + 1, n)] = (-sa - w * h[(i, n)] - q * h[(i, n - 1)]) / x;
+                    } else {
+                        let (cr, ci) = cdiv(-r - y * h[(i, n - 1)], -s - y * h[(i, n)], z, q);
+                        h[(i + 1, n - 1)] = cr;
+                        h[(i + 1, n)] = ci;
+                    }
+                }
+
+                // overflow control
+                let t = h[(i, n - 1)].abs().max(h[(i, n)].abs());
+                if (eps * t) * t > F64::ONE {
+                    for j in i..=n {
+                        h[(j, n - 1)] /= t;
+                        h[(j, n)] /= t;
+                    }
+                }
+            }
+        }
+    }
+
+    // back transformation to eigenvectors of the original matrix
+    for j in (0..nn).rev() {
+        for i in 0..nn {
+            let mut z = F64::ZERO;
+            for k in 0..=j {
+                z += v[(i, k)] * h[(k, j)];
+            }
+            v[(i, j)] = z;
+        }
+    }
+}
+
+// Parlett-Reinsch balancing by powers of two. Returns the diagonal scaling D
+// with the balanced matrix equal to D^-1 * A * D.
+fn balance(a: &mut FullMat<F64>) -> Vec<F64> {
+    let n = a.col_count();
+    let radix = F64::from(2.0);
+    let sqrdx = radix * radix;
+    let mut scale = vec![F64::ONE; n];
+
+    let mut done = false;
+    while !done {
+        done = true;
+        for i in 0..n {
+            let mut c = F64::ZERO;
+            let mut r = F64::ZERO;
+            for j in (0..n).filter(|j| *j != i) {
+                c += a[(j, i)].abs();
+                r += a[(i, j)].abs();
+            }
+            if c == F64::ZERO || r == F64::ZERO {
+                continue;
+            }
+
+            let s = c + r;
+            let mut f = F64::ONE;
+            let g = r / radix;
+            while c < g {
+                f *= radix;
+                c *= sqrdx;
+            }
+            let g = r * radix;
+            while c > g {
+                f /= radix;
+                c /= sqrdx;
+            }
+            if (c + r) / f < F64::from(0.95) * s {
+                done = false;
+                scale[i] *= f;
+                for j in 0..n {
+                    a[(i, j)] /= f;
+                    a[(j, i)] *= f;
+                }
+            }
+        }
+    }
+
+    scale
+}
+
+fn is_complex_block(t: &FullMat<F64>, e: &[F64], i: usize) -> bool {
+    i + 1 < t.col_count() && e[i] > F64::ZERO
+}
+
+impl FullMat<F64> {
+    // Upper Hessenberg form A = Q * H * Q^T, returned as (Q, H).
+    pub fn hessenberg(&self) -> (FullMat<F64>, FullMat<F64>) {
+        assert!(self.is_square());
+
+        let mut h = self.clone();
+        let q = hessenberg(&mut h);
+        (q, h)
+    }
+
+    pub fn schur(&self) -> Option<Schur> {
+        assert!(self.is_square());
+        if !self.iter().all(|x| x.is_finite()) {
+            return None;
+        }
+
+        let mut t = self.clone();
+        let mut z = hessenberg(&mut t);
+        let (_, e, _) = francis_qr(&mut t, &mut z)?;
+
+        let n = self.col_count();
+        for i in 0..n {
+            for j in 0..i {
+                if j + 1 < i || !is_complex_block(&t, &e, j) {
+                    t[(i, j)] = F64::ZERO;
+                }
+            }
+        }
+        Some(Schur { z, t })
+    }
+
+    pub fn eigen(&self) -> Option<Eigen> {
+        self.eigen_with(true)
+    }
+
+    pub fn eigen_with(&self, balanced: bool) -> Option<Eigen> {
+        assert!(self.is_square());
+        if !self.iter().all(|x| x.is_finite()) {
+            return None;
+        }
+
+        let n = self.col_count();
+        let mut h = self.clone();
+        let scale = if balanced {
+            balance(&mut h)
+        } else {
+            vec![F64::ONE; n]
+        };
+        let mut v = hessenberg(&mut h);
+        let (d, e, norm) = francis_qr(&mut h, &mut v)?;
+        back_substitute(&mut h, &mut v, &d, &e, norm);
+
+        let mut eigenvalues = vec![];
+        let mut eigenvectors = vec![];
+        let mut k = 0;
+        while k < n {
+            if e[k] == F64::ZERO {
+                eigenvalues.push(Complex64::from((d[k], F64::ZERO)));
+                eigenvectors.push(normalize(
+                    (0..n).map(|i| Complex64::from((v[(i, k)] * scale[i], F64::ZERO))),
+                ));
+                k += 1;
+            } else {
+                let x: Vec<Complex64> = (0..n)
+                    .map(|i| Complex64::from((v[(i, k)] * scale[i], v[(i, k + 1)] * scale[i])))
+                    .collect();
+                eigenvalues.push(Complex64::from((d[k], e[k])));
+                eigenvalues.push(Complex64::from((d[k + 1], e[k + 1])));
+                eigenvectors.push(normalize(x.iter().copied()));
+                eigenvectors.push(normalize(x.iter().map(Complex64::conjugate)));
+                k += 2;
+            }
+        }
+
+        Some(Eigen {
+            eigenvalues,
+            eigenvectors,
+        })
+    }
+
+    pub fn eigenvalues(&self) -> Option<Vec<Complex64>> {
+        assert!(self.is_square());
+        if !self.iter().all(|x| x.is_finite()) {
+            return None;
+        }
+
+        let mut h = self.clone();
+        balance(&mut h);
+        let mut v = hessenberg(&mut h);
+        let (d, e, _) = francis_qr(&mut h, &mut v)?;
+        Some(d.into_iter().zip(e).map(Complex64::from).collect())
+    }
+}
+
+fn normalize<I: Iterator<Item = Complex64>>(x: I) -> Vec<Complex64> {
+    let x: Vec<Complex64> = x.collect();
+    let norm = x.iter().fold(F64::ZERO, |acc, c| acc.hypot(c.abs()));
+    if norm == F64::ZERO {
+        return x;
+    }
+    let inv = Complex64::from((F64::ONE / norm, F64::ZERO));
+    x.into_iter().map(|c| c * inv).collect()
+}
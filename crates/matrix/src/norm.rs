@@ -0,0 +1,184 @@
+use floating_point::F64;
+
+use crate::{vector, FullMat, Matrix};
+
+pub fn vector_norm1(x: &[F64]) -> F64 {
+    x.iter().map(|v| v.abs()).sum()
+}
+
+// Euclidean norm, scaled to avoid overflow and underflow
+pub fn vector_norm2(x: &[F64]) -> F64 {
+    vector::norm2(x)
+}
+
+pub fn vector_norm_inf(x: &[F64]) -> F64 {
+    x.iter().fold(F64::ZERO, |max, v| max.max(v.abs()))
+}
+
+// (sum |x_i|^p)^(1/p) for p >= 1, scaled by the largest entry like the
+// 2-norm
+pub fn vector_norm_p(x: &[F64], p: F64) -> F64 {
+    assert!(p >= F64::ONE);
+    let scale = vector_norm_inf(x);
+    if scale == F64::ZERO || !p.is_finite() {
+        return scale;
+    }
+    let p = p.to_f64();
+    let sum: f64 = x.iter().map(|v| (*v / scale).abs().to_f64().powf(p)).sum();
+    scale * F64::from(sum.powf(1.0 / p))
+}
+
+impl FullMat<F64> {
+    // maximum absolute column sum
+    pub fn norm1(&self) -> F64 {
+        let (m, n) = self.shape();
+        let mut max = F64::ZERO;
+        for j in 0..n {
+            let mut sum = F64::ZERO;
+            for i in 0..m {
+                sum += self[(i, j)].abs();
+            }
+            max = max.max(sum);
+        }
+        max
+    }
+
+    // maximum absolute row sum
+    pub fn norm_inf(&self) -> F64 {
+        let (m, n) = self.shape();
+        let mut max = F64::ZERO;
+        for i in 0..m {
+            let mut sum = F64::ZERO;
+            for j in 0..n {
+                sum += self[(i, j)].abs();
+            }
+            max = max.max(sum);
+        }
+        max
+    }
+
+    pub fn norm_frobenius(&self) -> F64 {
+        let (m, n) = self.shape();
+        let mut entries = Vec::with_capacity(m * n);
+        for i in 0..m {
+            for j in 0..n {
+                entries.push(self[(i, j)]);
+            }
+        }
+        vector::norm2(&entries)
+    }
+
+    // ||A||_1 ||A^-1||_1 from the explicit inverse, infinite for a singular
+    // matrix
+    pub fn cond1(&self) -> F64 {
+        assert!(self.is_square());
+        match self.inv() {
+            Some(inv) => self.norm1() * inv.norm1(),
+            None => F64::from(f64::INFINITY),
+        }
+    }
+
+    pub fn cond_inf(&self) -> F64 {
+        assert!(self.is_square());
+        match self.inv() {
+            Some(inv) => self.norm_inf() * inv.norm_inf(),
+            None => F64::from(f64::INFINITY),
+        }
+    }
+
+    // Estimate of cond1 from one LU factorization and a handful of solves,
+    // O(n^2) beyond the factorization instead of the O(n^3) inverse. The
+    // estimate never exceeds the true value and is almost always within a
+    // factor of 3 of it.
+    pub fn cond1_estimate(&self) -> F64 {
+        assert!(self.is_square());
+        self.inverse_norm1_estimate(false)
+            .map_or(F64::from(f64::INFINITY), |inv_norm| self.norm1() * inv_norm)
+    }
+
+    // ||A^-1||_inf = ||A^-T||_1, so this is the same estimator on A^T
+    pub fn cond_inf_estimate(&self) -> F64 {
+        assert!(self.is_square());
+        self.inverse_norm1_estimate(true)
+            .map_or(F64::from(f64::INFINITY), |inv_norm| {
+                self.norm_inf() * inv_norm
+            })
+    }
+
+    // Hager's estimator of ||B||_1 for B = A^-1 (or A^-T if `transposed`),
+    // with Higham's extra alternating sign test vector as in LAPACK's
+    // lacon. Returns None if A is singular.
+    fn inverse_norm1_estimate(&self, transposed: bool) -> Option<F64> {
+        let (l, u, p) = self.lu()?;
+        let (lt, ut) = (l.transpose(), u.transpose());
+        // P A = L U, so A^-1 x = U^-1 L^-1 P x and A^-T x = P^T L^-T U^-T x
+        let solve = |x: &[F64]| -> Vec<F64> {
+            let pb = p.iter().map(|i| x[*i]).collect();
+            u.solve(l.solve(pb))
+        };
+        let solve_transpose = |x: &[F64]| -> Vec<F64> {
+            let v = lt.solve(ut.solve(x.to_vec()));
+            let mut y = vec![F64::ZERO; x.len()];
+            for (i, pi) in p.iter().enumerate() {
+                y[*pi] = v[i];
+            }
+            y
+        };
+        let apply = |x: &[F64], transpose: bool| {
+            if transpose != transposed {
+                solve_transpose(x)
+            } else {
+                solve(x)
+            }
+        };
+
+        let n = self.row_count();
+        let mut x = vec![F64::ONE / F64::from(n as f64); n];
+        let mut estimate = F64::ZERO;
+        let mut last_index = None;
+        for _ in 0..5 {
+            let y = apply(&x, false);
+            let norm = vector_norm1(&y);
+            if norm <= estimate {
+                break;
+            }
+            estimate = norm;
+            let sign: Vec<F64> = y
+                .iter()
+                .map(|v| if *v >= F64::ZERO { F64::ONE } else { -F64::ONE })
+                .collect();
+            let z = apply(&sign, true);
+            let (j, z_max) = z
+                .iter()
+                .enumerate()
+                .fold((0, F64::ZERO), |(j, max), (i, v)| {
+                    if v.abs() > max {
+                        (i, v.abs())
+                    } else {
+                        (j, max)
+                    }
+                });
+            if z_max <= vector::dot(&z, &x) || last_index == Some(j) {
+                break;
+            }
+            last_index = Some(j);
+            x = vec![F64::ZERO; n];
+            x[j] = F64::ONE;
+        }
+
+        // guards against the rare matrices fooling the gradient steps
+        let alternating: Vec<F64> = (0..n)
+            .map(|i| {
+                let magnitude = if n > 1 {
+                    1.0 + i as f64 / (n - 1) as f64
+                } else {
+                    1.0
+                };
+                F64::from(if i % 2 == 0 { magnitude } else { -magnitude })
+            })
+            .collect();
+        let alternative =
+            F64::from(2.0) * vector_norm1(&apply(&alternating, false)) / F64::from(3.0 * n as f64);
+        Some(estimate.max(alternative))
+    }
+}
@@ -0,0 +1,185 @@
+use floating_point::F64;
+
+use crate::{CsrMat, FullMat, RowAccess, TriFullMat};
+
+// An approximation M of the system matrix that is cheap to invert.
+pub trait Preconditioner {
+    // Returns z with M z = r.
+    fn solve(&self, r: &[F64]) -> Vec<F64>;
+}
+
+// M = I, i.e. no preconditioning.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdentityPreconditioner;
+
+impl Preconditioner for IdentityPreconditioner {
+    fn solve(&self, r: &[F64]) -> Vec<F64> {
+        r.to_vec()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreconditionerError {
+    // zero, missing or non-finite pivot in the given row
+    ZeroPivot(usize),
+    // nonpositive pivot in the given row of an incomplete Cholesky factor
+    NotPositiveDefinite(usize),
+    // the diagonal block starting at the given row is singular
+    SingularBlock(usize),
+}
+
+impl std::fmt::Display for PreconditionerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZeroPivot(i) => write!(f, "zero pivot in row {}", i),
+            Self::NotPositiveDefinite(i) => write!(f, "nonpositive pivot in row {}", i),
+            Self::SingularBlock(i) => write!(f, "singular diagonal block at row {}", i),
+        }
+    }
+}
+
+impl std::error::Error for PreconditionerError {}
+
+// The stored rows of A as CSR. Exact zeros are dropped, except on the
+// diagonal, so a dense matrix gives its true nonzero pattern.
+pub(crate) fn stored_rows<A: RowAccess>(a: &A) -> CsrMat<F64> {
+    let n = a.dim();
+    let mut row_ptr = vec![0];
+    let mut col_indices = vec![];
+    let mut values = vec![];
+    for i in 0..n {
+        a.for_each_in_row(i, |j, v| {
+            if v != F64::ZERO || i == j {
+                col_indices.push(j);
+                values.push(v);
+            }
+        });
+        row_ptr.push(col_indices.len());
+    }
+    CsrMat::from_raw_parts(n, n, row_ptr, col_indices, values)
+}
+
+// M = diag(A)
+#[derive(Debug, Clone)]
+pub struct JacobiPreconditioner {
+    inv_diagonal: Vec<F64>,
+}
+
+impl JacobiPreconditioner {
+    pub fn new<A: RowAccess>(a: &A) -> Result<Self, PreconditionerError> {
+        let diagonal = a.diagonal();
+        if let Some(i) = diagonal.iter().position(|d| *d == F64::ZERO) {
+            return Err(PreconditionerError::ZeroPivot(i));
+        }
+        Ok(Self {
+            inv_diagonal: diagonal.into_iter().map(|d| F64::ONE / d).collect(),
+        })
+    }
+}
+
+impl Preconditioner for JacobiPreconditioner {
+    fn solve(&self, r: &[F64]) -> Vec<F64> {
+        assert!(r.len() == self.inv_diagonal.len());
+        r.iter()
+            .zip(self.inv_diagonal.iter())
+            .map(|(r, d)| *r * *d)
+            .collect()
+    }
+}
+
+// M is the block diagonal part of A, with contiguous blocks of `block_size`
+// rows (the last one may be smaller), each factored by dense LU.
+#[derive(Debug, Clone)]
+pub struct BlockJacobiPreconditioner {
+    starts: Vec<usize>,
+    factors: Vec<(TriFullMat<F64>, TriFullMat<F64>, Vec<usize>)>,
+}
+
+impl BlockJacobiPreconditioner {
+    pub fn new<A: RowAccess>(a: &A, block_size: usize) -> Result<Self, PreconditionerError> {
+        assert!(block_size > 0);
+        let n = a.dim();
+        let starts: Vec<usize> = (0..n).step_by(block_size).collect();
+        let mut factors = vec![];
+        for start in starts.iter() {
+            let end = (start + block_size).min(n);
+            let mut block = FullMat::zeros(end - start, end - start);
+            for i in *start..end {
+                a.for_each_in_row(i, |j, v| {
+                    if (*start..end).contains(&j) {
+                        block[(i - start, j - start)] = v;
+                    }
+                });
+            }
+            factors.push(
+                block
+                    .lu()
+                    .ok_or(PreconditionerError::SingularBlock(*start))?,
+            );
+        }
+        Ok(Self { starts, factors })
+    }
+}
+
+impl Preconditioner for BlockJacobiPreconditioner {
+    fn solve(&self, r: &[F64]) -> Vec<F64> {
+        let mut z = Vec::with_capacity(r.len());
+        for (start, (l, u, p)) in self.starts.iter().zip(self.factors.iter()) {
+            let pr = p.iter().map(|i| r[start + i]).collect();
+            z.extend(u.solve(l.solve(pr)));
+        }
+        assert!(z.len() == r.len());
+        z
+    }
+}
+
+// M = (D + omega L) D^-1 (D + omega U) / (omega (2 - omega)), where D, L and
+// U are the diagonal, strictly lower and strictly upper parts of A. For a
+// symmetric positive definite A, M is too, so it can be used with CG.
+#[derive(Debug, Clone)]
+pub struct SsorPreconditioner {
+    a: CsrMat<F64>,
+    diagonal: Vec<F64>,
+    omega: F64,
+}
+
+impl SsorPreconditioner {
+    pub fn new<A: RowAccess>(a: &A, omega: F64) -> Result<Self, PreconditionerError> {
+        assert!(omega > F64::ZERO && omega < F64::from(2.0));
+        let a = stored_rows(a);
+        let diagonal = a.diagonal();
+        if let Some(i) = diagonal.iter().position(|d| *d == F64::ZERO) {
+            return Err(PreconditionerError::ZeroPivot(i));
+        }
+        Ok(Self { a, diagonal, omega })
+    }
+}
+
+impl Preconditioner for SsorPreconditioner {
+    fn solve(&self, r: &[F64]) -> Vec<F64> {
+        let n = self.diagonal.len();
+        assert!(r.len() == n);
+        let omega = self.omega;
+        let scale = omega * (F64::from(2.0) - omega);
+
+        // (D + omega L) y = omega (2 - omega) r
+        let mut y = vec![F64::ZERO; n];
+        for i in 0..n {
+            let mut sum = scale * r[i];
+            for (j, v) in self.a.row(i).take_while(|(j, _)| *j < i) {
+                sum -= omega * v * y[j];
+            }
+            y[i] = sum / self.diagonal[i];
+        }
+        // (D + omega U) z = D y
+        let mut z = vec![F64::ZERO; n];
+        for i in (0..n).rev() {
+            let mut sum = self.diagonal[i] * y[i];
+            for (j, v) in self.a.row(i).filter(|(j, _)| *j > i) {
+                sum -= omega * v * z[j];
+            }
+            z[i] = sum / self.diagonal[i];
+        }
+        z
+    }
+}
@@ -0,0 +1,133 @@
+use floating_point::F64;
+
+use crate::{vector, FullMat, Matrix, TriFullMat, TriangleMatType};
+
+// Householder vectors are stored normalized, so each reflector is I - 2 v v^T
+// acting on the trailing m - k entries.
+pub(crate) struct HouseholderQr {
+    row_count: usize,
+    reflectors: Vec<Vec<F64>>,
+    r: FullMat<F64>,
+}
+
+impl HouseholderQr {
+    pub(crate) fn new(mat: &FullMat<F64>) -> Self {
+        let (m, n) = mat.shape();
+        assert!(m >= n);
+
+        let mut a = mat.clone();
+        let mut reflectors = vec![];
+        for k in 0..n {
+            let mut v: Vec<F64> = (k..m).map(|i| a[(i, k)]).collect();
+            let alpha = vector::norm2(&v);
+            if alpha == F64::ZERO {
+                reflectors.push(vec![F64::ZERO; m - k]);
+                continue;
+            }
+            let v0 = v[0];
+            v[0] += alpha.copysign(v0);
+            let v_norm = vector::norm2(&v);
+            v.iter_mut().for_each(|x| *x /= v_norm);
+
+            for j in k..n {
+                let s: F64 = (k..m).map(|i| v[i - k] * a[(i, j)]).sum();
+                for i in k..m {
+                    a[(i, j)] -= F64::from(2.0) * s * v[i - k];
+                }
+            }
+            reflectors.push(v);
+        }
+
+        let mut r = FullMat::zeros(n, n);
+        for i in 0..n {
+            for j in i..n {
+                r[(i, j)] = a[(i, j)];
+            }
+        }
+
+        Self {
+            row_count: m,
+            reflectors,
+            r,
+        }
+    }
+
+    // Computes Q^T * b in place.
+    pub(crate) fn apply_qt(&self, b: &mut [F64]) {
+        for (k, v) in self.reflectors.iter().enumerate() {
+            Self::reflect(v, &mut b[k..]);
+        }
+    }
+
+    // Computes Q * b in place.
+    pub(crate) fn apply_q(&self, b: &mut [F64]) {
+        for (k, v) in self.reflectors.iter().enumerate().rev() {
+            Self::reflect(v, &mut b[k..]);
+        }
+    }
+
+    fn reflect(v: &[F64], x: &mut [F64]) {
+        let s = F64::from(2.0) * vector::dot(v, x);
+        x.iter_mut().zip(v.iter()).for_each(|(x, v)| *x -= s * *v);
+    }
+
+    pub(crate) fn r(&self) -> &FullMat<F64> {
+        &self.r
+    }
+
+    pub(crate) fn is_rank_deficient(&self) -> bool {
+        let n = self.r.col_count();
+        let max_diag = (0..n).fold(F64::ZERO, |max, i| max.max(self.r[(i, i)].abs()));
+        let tol = F64::from(self.row_count as f64) * F64::EPSILON * max_diag;
+        max_diag == F64::ZERO || (0..n).any(|i| self.r[(i, i)].abs() <= tol)
+    }
+
+    // Least squares solution of min ||A x - b||, None if R is numerically singular.
+    pub(crate) fn solve(&self, b: &[F64]) -> Option<Vec<F64>> {
+        if self.is_rank_deficient() {
+            return None;
+        }
+        let n = self.r.col_count();
+        let mut qtb = b.to_vec();
+        self.apply_qt(&mut qtb);
+        qtb.truncate(n);
+        Some(upper_tri(&self.r).solve(qtb))
+    }
+}
+
+fn upper_tri(r: &FullMat<F64>) -> TriFullMat<F64> {
+    let n = r.col_count();
+    let mut v = vec![];
+    for i in 0..n {
+        for j in i..n {
+            v.push(r[(i, j)]);
+        }
+    }
+    TriFullMat::from_vec(TriangleMatType::Upper, v)
+}
+
+impl FullMat<F64> {
+    // Thin QR factorization of an m x n matrix with m >= n: Q is m x n with
+    // orthonormal columns and R is n x n upper triangular.
+    pub fn qr(&self) -> (FullMat<F64>, TriFullMat<F64>) {
+        let (m, n) = self.shape();
+        let qr = HouseholderQr::new(self);
+
+        let mut cols = vec![];
+        for j in 0..n {
+            let mut e = vec![F64::ZERO; m];
+            e[j] = F64::ONE;
+            qr.apply_q(&mut e);
+            cols.push(e);
+        }
+        let q = FullMat::from_cols(cols);
+
+        (q, upper_tri(qr.r()))
+    }
+
+    pub fn qr_solve(&self, b: &[F64]) -> Option<Vec<F64>> {
+        assert!(b.len() == self.row_count());
+
+        HouseholderQr::new(self).solve(b)
+    }
+}
@@ -0,0 +1,212 @@
+use floating_point::F64;
+
+use crate::sparse_ordering::inverse_permutation;
+use crate::{CooMat, CscMat, CsrMat, Matrix, SparseOrdering, SparseTriMat, TriangleMatType};
+
+// Upper triangle of A(perm, perm) stored by columns. A is assumed symmetric,
+// so row perm[k] of A provides column k.
+fn permuted_upper(a: &CsrMat<F64>, perm: &[usize], pinv: &[usize]) -> CscMat<F64> {
+    let n = perm.len();
+    let mut coo = CooMat::with_capacity(n, n, a.nnz());
+    for (k, i) in perm.iter().enumerate() {
+        for (j, v) in a.row(*i) {
+            if pinv[j] <= k {
+                coo.push(pinv[j], k, v);
+            }
+        }
+    }
+    coo.to_csc()
+}
+
+// Elimination tree of the symmetric matrix whose upper triangle is given, with
+// path compression through `ancestor`. The roots have no parent.
+fn elimination_tree(upper: &CscMat<F64>) -> Vec<Option<usize>> {
+    let n = upper.col_count();
+    let mut parent = vec![None; n];
+    let mut ancestor: Vec<Option<usize>> = vec![None; n];
+    for k in 0..n {
+        for (i, _) in upper.col(k) {
+            let mut node = Some(i);
+            while let Some(i) = node.filter(|i| *i < k) {
+                node = ancestor[i];
+                ancestor[i] = Some(k);
+                if node.is_none() {
+                    parent[i] = Some(k);
+                }
+            }
+        }
+    }
+    parent
+}
+
+// Nonzero pattern of row k of L, in topological order: the union of the
+// paths from each entry of column k of the upper triangle up to k in the
+// elimination tree. `flag` must not contain k before the call.
+fn row_pattern(
+    upper: &CscMat<F64>,
+    parent: &[Option<usize>],
+    k: usize,
+    flag: &mut [usize],
+    pattern: &mut Vec<usize>,
+) {
+    pattern.clear();
+    flag[k] = k;
+    for (i, _) in upper.col(k) {
+        let start = pattern.len();
+        let mut node = i;
+        while flag[node] != k {
+            pattern.push(node);
+            flag[node] = k;
+            node = parent[node].unwrap();
+        }
+        pattern[start..].reverse();
+    }
+    // each path was stored top down and later paths hang below earlier ones
+    pattern.reverse();
+}
+
+// Symbolic phase of the sparse Cholesky factorization: the ordering, the
+// elimination tree and the column counts of L. It only depends on the
+// sparsity pattern, so it can be reused for every matrix with the same one.
+#[derive(Debug, Clone)]
+pub struct SymbolicCholesky {
+    perm: Vec<usize>,
+    parent: Vec<Option<usize>>,
+    col_ptr: Vec<usize>,
+    row_ptr: Vec<usize>,
+    col_indices: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SparseCholesky {
+    perm: Vec<usize>,
+    l: SparseTriMat,
+}
+
+impl SymbolicCholesky {
+    pub fn analyze(a: &CsrMat<F64>, ordering: SparseOrdering) -> Self {
+        assert!(a.is_square());
+        let n = a.row_count();
+        let perm = ordering.permutation(a);
+        let upper = permuted_upper(a, &perm, &inverse_permutation(&perm));
+        let parent = elimination_tree(&upper);
+
+        let mut counts = vec![1; n];
+        let mut flag = vec![usize::MAX; n];
+        let mut pattern = vec![];
+        for k in 0..n {
+            row_pattern(&upper, &parent, k, &mut flag, &mut pattern);
+            for j in pattern.iter() {
+                counts[*j] += 1;
+            }
+        }
+        let mut col_ptr = vec![0; n + 1];
+        for j in 0..n {
+            col_ptr[j + 1] = col_ptr[j] + counts[j];
+        }
+
+        Self {
+            perm,
+            parent,
+            col_ptr,
+            row_ptr: a.row_ptr().to_vec(),
+            col_indices: a.col_indices().to_vec(),
+        }
+    }
+
+    pub fn perm(&self) -> &[usize] {
+        &self.perm
+    }
+
+    pub fn elimination_tree(&self) -> &[Option<usize>] {
+        &self.parent
+    }
+
+    // number of entries of L, diagonal included
+    pub fn nnz_l(&self) -> usize {
+        self.col_ptr[self.col_ptr.len() - 1]
+    }
+
+    // Up-looking numeric factorization: row k of L is found by a sparse
+    // triangular solve with the rows above it. Returns None if the matrix is
+    // not positive definite.
+    pub fn factor(&self, a: &CsrMat<F64>) -> Option<SparseCholesky> {
+        assert!(a.row_ptr() == self.row_ptr && a.col_indices() == self.col_indices);
+
+        let n = self.perm.len();
+        let upper = permuted_upper(a, &self.perm, &inverse_permutation(&self.perm));
+        let nnz = self.nnz_l();
+        let mut row_indices = vec![0; nnz];
+        let mut values = vec![F64::ZERO; nnz];
+        // next free position in each column of L
+        let mut next = self.col_ptr[..n].to_vec();
+        let mut x = vec![F64::ZERO; n];
+        let mut flag = vec![usize::MAX; n];
+        let mut pattern = vec![];
+
+        for k in 0..n {
+            row_pattern(&upper, &self.parent, k, &mut flag, &mut pattern);
+            for (i, v) in upper.col(k) {
+                x[i] = v;
+            }
+            let mut d = x[k];
+            x[k] = F64::ZERO;
+            for j in pattern.iter() {
+                let j = *j;
+                let lkj = x[j] / values[self.col_ptr[j]];
+                x[j] = F64::ZERO;
+                for p in (self.col_ptr[j] + 1)..next[j] {
+                    x[row_indices[p]] -= values[p] * lkj;
+                }
+                d -= lkj * lkj;
+                row_indices[next[j]] = k;
+                values[next[j]] = lkj;
+                next[j] += 1;
+            }
+            if d <= F64::ZERO || !d.is_finite() {
+                return None;
+            }
+            row_indices[next[k]] = k;
+            values[next[k]] = d.sqrt();
+            next[k] += 1;
+        }
+
+        let l = CscMat::from_raw_parts(n, n, self.col_ptr.clone(), row_indices, values);
+        Some(SparseCholesky {
+            perm: self.perm.clone(),
+            l: SparseTriMat::from_csc(TriangleMatType::Lower, l),
+        })
+    }
+}
+
+impl SparseCholesky {
+    // the factor of the reordered matrix, A(perm, perm) = L L^T
+    pub fn l(&self) -> &SparseTriMat {
+        &self.l
+    }
+
+    pub fn perm(&self) -> &[usize] {
+        &self.perm
+    }
+
+    pub fn solve(&self, b: &[F64]) -> Vec<F64> {
+        assert!(b.len() == self.perm.len());
+
+        let pb = self.perm.iter().map(|i| b[*i]).collect();
+        let y = self.l.solve(pb);
+        let z = self.l.solve_transpose(y);
+        let mut x = vec![F64::ZERO; b.len()];
+        for (k, i) in self.perm.iter().enumerate() {
+            x[*i] = z[k];
+        }
+        x
+    }
+}
+
+impl CsrMat<F64> {
+    // symbolic and numeric phases in one go, for a single symmetric positive
+    // definite matrix
+    pub fn cholesky(&self, ordering: SparseOrdering) -> Option<SparseCholesky> {
+        SymbolicCholesky::analyze(self, ordering).factor(self)
+    }
+}
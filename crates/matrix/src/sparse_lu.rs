@@ -0,0 +1,246 @@
+use floating_point::F64;
+
+use crate::{CscMat, CsrMat, Matrix, SparseOrdering, SparseTriMat, TriangleMatType};
+
+// Columns of L under construction. Row indices are original row indices until
+// the factorization ends, and `pinv` maps a pivotal row to its L column.
+struct PartialL {
+    col_ptr: Vec<usize>,
+    row_indices: Vec<usize>,
+    values: Vec<F64>,
+}
+
+// Depth first search in the graph of L from every nonzero of b, so that the
+// nonzero pattern of x = L \ b comes out in topological order. Nodes are
+// row indices and only pivotal rows have outgoing edges.
+fn reach(
+    l: &PartialL,
+    b: &CscMat<F64>,
+    col: usize,
+    pinv: &[Option<usize>],
+    marked: &mut [bool],
+    pattern: &mut Vec<usize>,
+) {
+    pattern.clear();
+    let mut stack: Vec<(usize, usize)> = vec![];
+    for (root, _) in b.col(col) {
+        if marked[root] {
+            continue;
+        }
+        marked[root] = true;
+        stack.push((root, 0));
+        while let Some((node, child)) = stack.pop() {
+            let edges = match pinv[node] {
+                Some(j) => (l.col_ptr[j] + 1)..l.col_ptr[j + 1],
+                None => 0..0,
+            };
+            match Some(edges.start + child).filter(|p| *p < edges.end) {
+                Some(p) => {
+                    stack.push((node, child + 1));
+                    let next = l.row_indices[p];
+                    if !marked[next] {
+                        marked[next] = true;
+                        stack.push((next, 0));
+                    }
+                }
+                None => pattern.push(node),
+            }
+        }
+    }
+    // nodes were finished children first
+    pattern.reverse();
+    for i in pattern.iter() {
+        marked[*i] = false;
+    }
+}
+
+// Symbolic phase of the sparse LU factorization. With pivoting the pattern of
+// the factors is only known during the numeric phase, so the analysis keeps
+// the column ordering, computed on the pattern of A + A^T.
+#[derive(Debug, Clone)]
+pub struct SymbolicLu {
+    col_perm: Vec<usize>,
+    row_ptr: Vec<usize>,
+    col_indices: Vec<usize>,
+}
+
+// P A Q = L U, where row k of P A is row `row_perm[k]` of A and column k of
+// A Q is column `col_perm[k]` of A. L has a unit diagonal.
+#[derive(Debug, Clone)]
+pub struct SparseLu {
+    l: SparseTriMat,
+    u: SparseTriMat,
+    row_perm: Vec<usize>,
+    col_perm: Vec<usize>,
+}
+
+fn sorted_csc(n: usize, col_ptr: Vec<usize>, entries: Vec<(usize, F64)>) -> CscMat<F64> {
+    let mut entries = entries;
+    for j in 0..n {
+        entries[col_ptr[j]..col_ptr[j + 1]].sort_unstable_by_key(|e| e.0);
+    }
+    let (rows, values) = entries.into_iter().unzip();
+    CscMat::from_raw_parts(n, n, col_ptr, rows, values)
+}
+
+impl SymbolicLu {
+    pub fn analyze(a: &CsrMat<F64>, ordering: SparseOrdering) -> Self {
+        assert!(a.is_square());
+        Self {
+            col_perm: ordering.permutation(a),
+            row_ptr: a.row_ptr().to_vec(),
+            col_indices: a.col_indices().to_vec(),
+        }
+    }
+
+    pub fn col_perm(&self) -> &[usize] {
+        &self.col_perm
+    }
+
+    // Left-looking Gilbert-Peierls factorization. Each column of A Q is
+    // solved against the columns of L found so far, then the pivot is chosen
+    // by threshold partial pivoting: the diagonal candidate is kept when its
+    // magnitude is at least `pivot_threshold` times the largest one, so 1.0
+    // gives plain partial pivoting and smaller values preserve the ordering
+    // better. Returns None if the matrix is singular.
+    pub fn factor(&self, a: &CsrMat<F64>, pivot_threshold: F64) -> Option<SparseLu> {
+        assert!(a.row_ptr() == self.row_ptr && a.col_indices() == self.col_indices);
+        assert!(pivot_threshold > F64::ZERO && pivot_threshold <= F64::ONE);
+
+        let n = a.row_count();
+        let a = a.to_csc();
+        let mut l = PartialL {
+            col_ptr: vec![0],
+            row_indices: vec![],
+            values: vec![],
+        };
+        let mut u_ptr = vec![0];
+        let mut u_entries: Vec<(usize, F64)> = vec![];
+        let mut pinv: Vec<Option<usize>> = vec![None; n];
+        let mut x = vec![F64::ZERO; n];
+        let mut marked = vec![false; n];
+        let mut pattern = vec![];
+
+        for (k, col) in self.col_perm.iter().enumerate() {
+            // x = L \ A(:, col) on the pattern given by the reach
+            reach(&l, &a, *col, &pinv, &mut marked, &mut pattern);
+            for (i, v) in a.col(*col) {
+                x[i] = v;
+            }
+            for i in pattern.iter() {
+                if let Some(j) = pinv[*i] {
+                    let xi = x[*i];
+                    for p in (l.col_ptr[j] + 1)..l.col_ptr[j + 1] {
+                        x[l.row_indices[p]] -= l.values[p] * xi;
+                    }
+                }
+            }
+
+            let mut pivot_row = None;
+            let mut max_abs = F64::ZERO;
+            for i in pattern.iter() {
+                match pinv[*i] {
+                    Some(j) => u_entries.push((j, x[*i])),
+                    None => {
+                        if x[*i].abs() > max_abs {
+                            max_abs = x[*i].abs();
+                            pivot_row = Some(*i);
+                        }
+                    }
+                }
+            }
+            let mut pivot_row = pivot_row?;
+            if !max_abs.is_finite() {
+                return None;
+            }
+            // prefer the diagonal of A Q to keep the fill of the ordering
+            if pinv[*col].is_none() && x[*col].abs() >= pivot_threshold * max_abs {
+                pivot_row = *col;
+            }
+
+            let pivot = x[pivot_row];
+            u_entries.push((k, pivot));
+            u_ptr.push(u_entries.len());
+            pinv[pivot_row] = Some(k);
+            l.row_indices.push(pivot_row);
+            l.values.push(F64::ONE);
+            for i in pattern.iter() {
+                if pinv[*i].is_none() {
+                    l.row_indices.push(*i);
+                    l.values.push(x[*i] / pivot);
+                }
+                x[*i] = F64::ZERO;
+            }
+            l.col_ptr.push(l.row_indices.len());
+        }
+
+        let mut row_perm = vec![0; n];
+        for (i, k) in pinv.iter().enumerate() {
+            row_perm[k.unwrap()] = i;
+        }
+        let l_entries = l
+            .row_indices
+            .iter()
+            .map(|i| pinv[*i].unwrap())
+            .zip(l.values)
+            .collect();
+
+        Some(SparseLu {
+            l: SparseTriMat::from_csc(TriangleMatType::Lower, sorted_csc(n, l.col_ptr, l_entries)),
+            u: SparseTriMat::from_csc(TriangleMatType::Upper, sorted_csc(n, u_ptr, u_entries)),
+            row_perm,
+            col_perm: self.col_perm.clone(),
+        })
+    }
+}
+
+impl SparseLu {
+    pub fn l(&self) -> &SparseTriMat {
+        &self.l
+    }
+
+    pub fn u(&self) -> &SparseTriMat {
+        &self.u
+    }
+
+    pub fn row_perm(&self) -> &[usize] {
+        &self.row_perm
+    }
+
+    pub fn col_perm(&self) -> &[usize] {
+        &self.col_perm
+    }
+
+    pub fn solve(&self, b: &[F64]) -> Vec<F64> {
+        assert!(b.len() == self.row_perm.len());
+
+        let pb = self.row_perm.iter().map(|i| b[*i]).collect();
+        let y = self.l.solve(pb);
+        let z = self.u.solve(y);
+        let mut x = vec![F64::ZERO; b.len()];
+        for (k, j) in self.col_perm.iter().enumerate() {
+            x[*j] = z[k];
+        }
+        x
+    }
+
+    // A^T x = b, using Q^T A^T P^T = U^T L^T
+    pub fn solve_transpose(&self, b: &[F64]) -> Vec<F64> {
+        assert!(b.len() == self.col_perm.len());
+
+        let qb = self.col_perm.iter().map(|j| b[*j]).collect();
+        let y = self.u.solve_transpose(qb);
+        let z = self.l.solve_transpose(y);
+        let mut x = vec![F64::ZERO; b.len()];
+        for (k, i) in self.row_perm.iter().enumerate() {
+            x[*i] = z[k];
+        }
+        x
+    }
+}
+
+impl CsrMat<F64> {
+    pub fn lu(&self, ordering: SparseOrdering, pivot_threshold: F64) -> Option<SparseLu> {
+        SymbolicLu::analyze(self, ordering).factor(self, pivot_threshold)
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use floating_point::F64;
+
+use crate::{CsrMat, Matrix};
+
+// Fill-reducing symmetric orderings. A permutation `perm` lists the original
+// index of each new index, so the reordered matrix is A(perm, perm).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SparseOrdering {
+    Natural,
+    // reduces the profile, good for matrices from meshes with a natural band
+    ReverseCuthillMcKee,
+    // greedy minimum degree on the exact elimination graph, whose cost grows
+    // with the fill; mostly a reference for the approximate variant
+    MinimumDegree,
+    // approximate minimum degree on the quotient graph, which needs no more
+    // storage than the graph of A
+    ApproximateMinimumDegree,
+}
+
+impl SparseOrdering {
+    pub fn permutation(&self, a: &CsrMat<F64>) -> Vec<usize> {
+        match self {
+            SparseOrdering::Natural => {
+                assert!(a.is_square());
+                (0..a.row_count()).collect()
+            }
+            SparseOrdering::ReverseCuthillMcKee => reverse_cuthill_mckee(a),
+            SparseOrdering::MinimumDegree => minimum_degree(a),
+            SparseOrdering::ApproximateMinimumDegree => approximate_minimum_degree(a),
+        }
+    }
+}
+
+pub(crate) fn inverse_permutation(perm: &[usize]) -> Vec<usize> {
+    let mut inv = vec![0; perm.len()];
+    for (k, i) in perm.iter().enumerate() {
+        inv[*i] = k;
+    }
+    inv
+}
+
+// adjacency lists of the graph of A + A^T without self loops
+fn symmetric_graph(a: &CsrMat<F64>) -> Vec<Vec<usize>> {
+    assert!(a.is_square());
+    let mut adj = vec![vec![]; a.row_count()];
+    for (i, j, _) in a.iter() {
+        if i != j {
+            adj[i].push(j);
+            adj[j].push(i);
+        }
+    }
+    for list in adj.iter_mut() {
+        list.sort_unstable();
+        list.dedup();
+    }
+    adj
+}
+
+// Breadth first search from `root` over unvisited nodes, visiting neighbours
+// by increasing degree. Returns the level structure.
+fn level_structure(adj: &[Vec<usize>], root: usize, visited: &mut [bool]) -> Vec<Vec<usize>> {
+    let mut levels = vec![vec![root]];
+    let mut marked = vec![root];
+    visited[root] = true;
+    loop {
+        let mut next = vec![];
+        for u in levels.last().unwrap() {
+            let mut neighbours: Vec<usize> =
+                adj[*u].iter().copied().filter(|v| !visited[*v]).collect();
+            neighbours.sort_by_key(|v| adj[*v].len());
+            for v in neighbours {
+                if !visited[v] {
+                    visited[v] = true;
+                    marked.push(v);
+                    next.push(v);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        levels.push(next);
+    }
+    // only the caller decides what ends up visited
+    for v in marked {
+        visited[v] = false;
+    }
+    levels
+}
+
+// George-Liu heuristic: restart from a minimum degree node of the last level
+// while the eccentricity keeps growing.
+fn pseudo_peripheral_node(adj: &[Vec<usize>], start: usize, visited: &mut [bool]) -> usize {
+    let mut root = start;
+    let mut levels = level_structure(adj, root, visited);
+    loop {
+        let candidate = *levels
+            .last()
+            .unwrap()
+            .iter()
+            .min_by_key(|v| adj[**v].len())
+            .unwrap();
+        let candidate_levels = level_structure(adj, candidate, visited);
+        if candidate_levels.len() <= levels.len() {
+            return root;
+        }
+        root = candidate;
+        levels = candidate_levels;
+    }
+}
+
+pub fn reverse_cuthill_mckee(a: &CsrMat<F64>) -> Vec<usize> {
+    let adj = symmetric_graph(a);
+    let n = adj.len();
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+
+    // components are started from their lowest degree node
+    let mut starts: Vec<usize> = (0..n).collect();
+    starts.sort_by_key(|v| adj[*v].len());
+    for start in starts {
+        if visited[start] {
+            continue;
+        }
+        let root = pseudo_peripheral_node(&adj, start, &mut visited);
+        let mut queue = VecDeque::from([root]);
+        visited[root] = true;
+        while let Some(u) = queue.pop_front() {
+            order.push(u);
+            let mut neighbours: Vec<usize> =
+                adj[u].iter().copied().filter(|v| !visited[*v]).collect();
+            neighbours.sort_by_key(|v| adj[*v].len());
+            for v in neighbours {
+                visited[v] = true;
+                queue.push_back(v);
+            }
+        }
+    }
+
+    order.reverse();
+    order
+}
+
+// Eliminates a node of minimum current degree at each step and turns its
+// neighbours into a clique, ties broken by the smaller index. The elimination
+// graph is kept explicitly, so the cost grows with the fill.
+pub fn minimum_degree(a: &CsrMat<F64>) -> Vec<usize> {
+    let mut adj: Vec<BTreeSet<usize>> = symmetric_graph(a)
+        .into_iter()
+        .map(|list| list.into_iter().collect())
+        .collect();
+    let n = adj.len();
+    let mut queue: BTreeSet<(usize, usize)> = (0..n).map(|v| (adj[v].len(), v)).collect();
+    let mut order = Vec::with_capacity(n);
+
+    while let Some((_, v)) = queue.pop_first() {
+        order.push(v);
+        let neighbours = std::mem::take(&mut adj[v]);
+        for u in neighbours.iter() {
+            queue.remove(&(adj[*u].len(), *u));
+            adj[*u].remove(&v);
+            for w in neighbours.iter() {
+                if w != u {
+                    adj[*u].insert(*w);
+                }
+            }
+            queue.insert((adj[*u].len(), *u));
+        }
+    }
+
+    order
+}
+
+// The approximate minimum degree ordering of Amestoy, Davis and Duff. An
+// eliminated node becomes an element that stands for the clique on its
+// neighbours, so the fill is never formed, and the degrees are the AMD upper
+// bounds computed from the sizes of the elements. Elements that fall inside
+// the new one are absorbed. There is no supervariable detection, so
+// indistinguishable nodes are still eliminated one at a time.
+pub fn approximate_minimum_degree(a: &CsrMat<F64>) -> Vec<usize> {
+    // neighbours of each variable that no element covers yet
+    let mut vars = symmetric_graph(a);
+    let n = vars.len();
+    // the elements next to each variable, and the variables of each element
+    let mut elems: Vec<Vec<usize>> = vec![vec![]; n];
+    let mut members: Vec<Vec<usize>> = vec![vec![]; n];
+    let mut degree: Vec<usize> = vars.iter().map(|list| list.len()).collect();
+    let mut queue: BTreeSet<(usize, usize)> = (0..n).map(|v| (degree[v], v)).collect();
+    let mut eliminated = vec![false; n];
+    let mut absorbed = vec![false; n];
+    let mut in_element = vec![false; n];
+    // |L_e \ L_p| for the elements next to L_p, None elsewhere
+    let mut external: Vec<Option<usize>> = vec![None; n];
+    let mut order = Vec::with_capacity(n);
+
+    while let Some((_, p)) = queue.pop_first() {
+        order.push(p);
+        eliminated[p] = true;
+
+        // L_p is the union of the neighbours of p and of its elements, which
+        // all become part of the new element p
+        let mut lp = vec![];
+        for i in vars[p]
+            .iter()
+            .chain(elems[p].iter().flat_map(|e| members[*e].iter()))
+        {
+            if !eliminated[*i] && !in_element[*i] {
+                in_element[*i] = true;
+                lp.push(*i);
+            }
+        }
+        for e in std::mem::take(&mut elems[p]) {
+            absorbed[e] = true;
+            members[e] = vec![];
+        }
+        vars[p] = vec![];
+
+        let mut touched = vec![];
+        for i in lp.iter() {
+            elems[*i].retain(|e| !absorbed[*e]);
+            for e in elems[*i].iter() {
+                let w = external[*e].get_or_insert_with(|| {
+                    touched.push(*e);
+                    members[*e].len()
+                });
+                *w -= 1;
+            }
+        }
+        for e in touched.iter() {
+            if external[*e] == Some(0) {
+                absorbed[*e] = true;
+                members[*e] = vec![];
+            }
+        }
+
+        let remaining = n - order.len();
+        for i in lp.iter() {
+            // the edges inside L_p are covered by the element p
+            vars[*i].retain(|j| !in_element[*j] && !eliminated[*j]);
+            elems[*i].retain(|e| !absorbed[*e]);
+            let outside: usize = elems[*i].iter().map(|e| external[*e].unwrap()).sum();
+            elems[*i].push(p);
+
+            let bound = (degree[*i] + lp.len() - 1)
+                .min(vars[*i].len() + lp.len() - 1 + outside)
+                .min(remaining - 1);
+            queue.remove(&(degree[*i], *i));
+            degree[*i] = bound;
+            queue.insert((bound, *i));
+        }
+
+        for e in touched {
+            external[e] = None;
+        }
+        for i in lp.iter() {
+            in_element[*i] = false;
+        }
+        members[p] = lp;
+    }
+
+    order
+}
+
+// Bandwidth and profile (the sum over rows of the distance from the first
+// stored entry to the diagonal) of A(perm, perm), useful to compare orderings.
+pub fn bandwidth_and_profile(a: &CsrMat<F64>, perm: &[usize]) -> (usize, usize) {
+    let pinv = inverse_permutation(perm);
+    let (mut bandwidth, mut profile) = (0, 0);
+    for (k, i) in perm.iter().enumerate() {
+        let first = a.row(*i).map(|(j, _)| pinv[j]).min().unwrap_or(k).min(k);
+        let last = a.row(*i).map(|(j, _)| pinv[j]).max().unwrap_or(k).max(k);
+        bandwidth = bandwidth.max(k - first).max(last - k);
+        profile += k - first;
+    }
+    (bandwidth, profile)
+}
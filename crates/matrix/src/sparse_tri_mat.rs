@@ -0,0 +1,119 @@
+use core::ops::Index;
+use std::fmt::Display;
+
+use floating_point::F64;
+
+use crate::{CscMat, FullMat, Matrix, TriangleMatType};
+
+// Sparse triangular matrix stored by columns, the sparse counterpart of
+// TriFullMat returned by the sparse factorizations. Every diagonal entry has
+// to be stored.
+#[derive(Debug, Clone)]
+pub struct SparseTriMat {
+    pub ty: TriangleMatType,
+    storage: CscMat<F64>,
+}
+
+impl SparseTriMat {
+    pub fn from_csc(ty: TriangleMatType, storage: CscMat<F64>) -> Self {
+        assert!(storage.is_square());
+        let ptr = storage.col_ptr();
+        let rows = storage.row_indices();
+        for j in 0..storage.col_count() {
+            let col = &rows[ptr[j]..ptr[j + 1]];
+            // the row indices are sorted, so the diagonal is at the end of
+            // the column for upper and at the start for lower triangles
+            match ty {
+                TriangleMatType::Upper => assert!(col.last() == Some(&j)),
+                TriangleMatType::Lower => assert!(col.first() == Some(&j)),
+            }
+        }
+        Self { ty, storage }
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.storage.nnz()
+    }
+
+    pub fn as_csc(&self) -> &CscMat<F64> {
+        &self.storage
+    }
+
+    pub fn to_dense(&self) -> FullMat<F64> {
+        self.storage.to_dense()
+    }
+
+    // stored entries of column j without the diagonal, and the diagonal
+    fn split_col(&self, j: usize) -> (std::ops::Range<usize>, F64) {
+        let ptr = self.storage.col_ptr();
+        let values = self.storage.values();
+        match self.ty {
+            TriangleMatType::Upper => (ptr[j]..(ptr[j + 1] - 1), values[ptr[j + 1] - 1]),
+            TriangleMatType::Lower => ((ptr[j] + 1)..ptr[j + 1], values[ptr[j]]),
+        }
+    }
+
+    pub fn solve(&self, b: Vec<F64>) -> Vec<F64> {
+        let n = self.col_count();
+        assert!(n == b.len());
+
+        let rows = self.storage.row_indices();
+        let values = self.storage.values();
+        let mut x = b;
+        let mut eliminate = |j: usize| {
+            let (range, diag) = self.split_col(j);
+            x[j] /= diag;
+            let xj = x[j];
+            for k in range {
+                x[rows[k]] -= values[k] * xj;
+            }
+        };
+        match self.ty {
+            TriangleMatType::Lower => (0..n).for_each(&mut eliminate),
+            TriangleMatType::Upper => (0..n).rev().for_each(&mut eliminate),
+        }
+        x
+    }
+
+    // solves with the transpose, which reads each column as a row
+    pub fn solve_transpose(&self, b: Vec<F64>) -> Vec<F64> {
+        let n = self.col_count();
+        assert!(n == b.len());
+
+        let rows = self.storage.row_indices();
+        let values = self.storage.values();
+        let mut x = b;
+        let mut substitute = |j: usize| {
+            let (range, diag) = self.split_col(j);
+            let mut sum = x[j];
+            for k in range {
+                sum -= values[k] * x[rows[k]];
+            }
+            x[j] = sum / diag;
+        };
+        match self.ty {
+            TriangleMatType::Lower => (0..n).rev().for_each(&mut substitute),
+            TriangleMatType::Upper => (0..n).for_each(&mut substitute),
+        }
+        x
+    }
+}
+
+impl Index<(usize, usize)> for SparseTriMat {
+    type Output = F64;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.storage[index]
+    }
+}
+
+impl Matrix<F64> for SparseTriMat {
+    fn shape(&self) -> (usize, usize) {
+        self.storage.shape()
+    }
+}
+
+impl Display for SparseTriMat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(self, f)
+    }
+}
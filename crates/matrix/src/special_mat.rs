@@ -0,0 +1,95 @@
+use floating_point::F64;
+
+use crate::FullMat;
+
+// Classic test matrices. Most are notoriously ill-conditioned and have
+// known determinants or inverses, which makes them good stress tests for
+// the factorizations.
+impl FullMat<F64> {
+    // H_ij = 1 / (i + j + 1), with condition number growing like e^(3.5 n)
+    pub fn hilbert(n: usize) -> Self {
+        assert!(n > 0);
+        let mut mat = Self::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                mat[(i, j)] = F64::ONE / F64::from((i + j + 1) as f64);
+            }
+        }
+        mat
+    }
+
+    // the exact inverse of `hilbert(n)`, which has integer entries
+    pub fn inverse_hilbert(n: usize) -> Self {
+        assert!(n > 0);
+        let binomial = |n: usize, k: usize| -> f64 {
+            let mut b = 1.0;
+            for i in 0..k {
+                b = b * (n - i) as f64 / (i + 1) as f64;
+            }
+            b.round()
+        };
+        let mut mat = Self::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                let sign = if (i + j) % 2 == 0 { 1.0 } else { -1.0 };
+                let v = sign
+                    * (i + j + 1) as f64
+                    * binomial(n + i, n - j - 1)
+                    * binomial(n + j, n - i - 1)
+                    * binomial(i + j, i).powi(2);
+                mat[(i, j)] = v.into();
+            }
+        }
+        mat
+    }
+
+    // symmetric Pascal matrix P_ij = binomial(i + j, i). It is positive
+    // definite with determinant 1, and P = L L^T with L the lower triangular
+    // Pascal matrix of binomial(i, j).
+    pub fn pascal(n: usize) -> Self {
+        assert!(n > 0);
+        let mut mat = Self::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                mat[(i, j)] = if i == 0 || j == 0 {
+                    F64::ONE
+                } else {
+                    mat[(i - 1, j)] + mat[(i, j - 1)]
+                };
+            }
+        }
+        mat
+    }
+
+    // Frank matrix: upper Hessenberg with F_ij = n - max(i, j) for j >= i - 1
+    // (counting from zero). Its determinant is 1, yet the smallest
+    // eigenvalues are very sensitive to perturbations.
+    pub fn frank(n: usize) -> Self {
+        assert!(n > 0);
+        let mut mat = Self::zeros(n, n);
+        for i in 0..n {
+            for j in i.saturating_sub(1)..n {
+                mat[(i, j)] = F64::from((n - i.max(j)) as f64);
+            }
+        }
+        mat
+    }
+
+    // Kahan matrix: upper triangular, with s^i on the diagonal and -c s^i
+    // above it in row i, where s = sin(theta) and c = cos(theta). Column
+    // pivoted QR fails to reveal its near rank deficiency.
+    pub fn kahan(n: usize, theta: F64) -> Self {
+        assert!(n > 0);
+        let (s, c) = (theta.to_f64().sin(), theta.to_f64().cos());
+        let mut mat = Self::zeros(n, n);
+        let mut scale = 1.0;
+        for i in 0..n {
+            mat[(i, i)] = scale.into();
+            for j in (i + 1)..n {
+                mat[(i, j)] = (-c * scale).into();
+            }
+            scale *= s;
+        }
+        mat
+    }
+}
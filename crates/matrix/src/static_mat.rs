@@ -0,0 +1,402 @@
+// Fixed-size matrices and vectors with the shape in the type, stored inline
+// in arrays. Meant for the many 2x2 to 6x6 blocks of geometry and control
+// code, where a heap-allocated `FullMat` costs more than the arithmetic.
+// Products only compile when the inner dimensions agree, and the solvers
+// work in place on a copy without allocating.
+
+use core::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+use std::fmt::Display;
+
+use floating_point::F64;
+
+use crate::{FullMat, Matrix, MatrixError};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SMat<T, const R: usize, const C: usize> {
+    rows: [[T; C]; R],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SVec<T, const N: usize> {
+    elements: [T; N],
+}
+
+impl<T: Copy, const R: usize, const C: usize> SMat<T, R, C> {
+    pub fn from_rows(rows: [[T; C]; R]) -> Self {
+        assert!(R > 0 && C > 0);
+        Self { rows }
+    }
+
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(mut f: F) -> Self {
+        Self::from_rows(core::array::from_fn(|i| core::array::from_fn(|j| f(i, j))))
+    }
+
+    pub fn transpose(&self) -> SMat<T, C, R> {
+        SMat::from_fn(|i, j| self.rows[j][i])
+    }
+
+    pub fn row(&self, i: usize) -> SVec<T, C> {
+        SVec::from_array(self.rows[i])
+    }
+
+    pub fn col(&self, j: usize) -> SVec<T, R> {
+        SVec::from_array(core::array::from_fn(|i| self.rows[i][j]))
+    }
+
+    pub fn to_rows(self) -> [[T; C]; R] {
+        self.rows
+    }
+}
+
+impl<T: Copy, const N: usize> SVec<T, N> {
+    pub fn from_array(elements: [T; N]) -> Self {
+        assert!(N > 0);
+        Self { elements }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.elements
+    }
+
+    pub fn to_array(self) -> [T; N] {
+        self.elements
+    }
+}
+
+impl<const R: usize, const C: usize> SMat<F64, R, C> {
+    pub fn zeros() -> Self {
+        Self::from_rows([[F64::ZERO; C]; R])
+    }
+
+    pub fn element_max_abs(&self) -> F64 {
+        self.rows
+            .iter()
+            .flatten()
+            .fold(F64::ZERO, |max, x| max.max(x.abs()))
+    }
+}
+
+impl<const N: usize> SVec<F64, N> {
+    pub fn zeros() -> Self {
+        Self::from_array([F64::ZERO; N])
+    }
+
+    pub fn dot(&self, rhs: &Self) -> F64 {
+        self.elements
+            .iter()
+            .zip(&rhs.elements)
+            .map(|(x, y)| x * y)
+            .sum()
+    }
+
+    pub fn norm2(&self) -> F64 {
+        crate::vector::norm2(&self.elements)
+    }
+}
+
+impl<const N: usize> SMat<F64, N, N> {
+    pub fn identity() -> Self {
+        Self::from_fn(|i, j| if i == j { F64::ONE } else { F64::ZERO })
+    }
+
+    // LU with partial pivoting, None on a zero pivot
+    pub fn lu(&self) -> Option<SLu<N>> {
+        let mut lu = *self;
+        let mut pivots = [0; N];
+        for (k, pivot_row) in pivots.iter_mut().enumerate() {
+            let mut p = k;
+            for i in (k + 1)..N {
+                if lu.rows[i][k].abs() > lu.rows[p][k].abs() {
+                    p = i;
+                }
+            }
+            *pivot_row = p;
+            lu.rows.swap(k, p);
+
+            let pivot = lu.rows[k][k];
+            if pivot == F64::ZERO {
+                return None;
+            }
+            for i in (k + 1)..N {
+                let m = lu.rows[i][k] / pivot;
+                lu.rows[i][k] = m;
+                for j in (k + 1)..N {
+                    let ukj = lu.rows[k][j];
+                    lu.rows[i][j] -= m * ukj;
+                }
+            }
+        }
+        Some(SLu { lu, pivots })
+    }
+
+    pub fn lu_solve(&self, b: &SVec<F64, N>) -> Option<SVec<F64, N>> {
+        self.lu().map(|lu| lu.solve(b))
+    }
+
+    // zero when elimination meets a zero pivot
+    pub fn det(&self) -> F64 {
+        self.lu().map_or(F64::ZERO, |lu| lu.det())
+    }
+
+    pub fn inv(&self) -> Option<Self> {
+        let lu = self.lu()?;
+        let mut inv = Self::zeros();
+        for j in 0..N {
+            let x = lu.solve(&Self::identity().col(j));
+            for i in 0..N {
+                inv.rows[i][j] = x[i];
+            }
+        }
+        Some(inv)
+    }
+
+    // The lower triangular L with A = L L^T, zeros above the diagonal. None
+    // if the matrix is not symmetric positive definite; only the lower
+    // triangle of A is read.
+    pub fn cholesky(&self) -> Option<Self> {
+        let mut l = Self::zeros();
+        for i in 0..N {
+            for j in 0..=i {
+                let mut sum = self.rows[i][j];
+                for k in 0..j {
+                    sum -= l.rows[i][k] * l.rows[j][k];
+                }
+                if i == j {
+                    if sum <= F64::ZERO || !sum.is_finite() {
+                        return None;
+                    }
+                    l.rows[i][i] = sum.sqrt();
+                } else {
+                    l.rows[i][j] = sum / l.rows[j][j];
+                }
+            }
+        }
+        Some(l)
+    }
+
+    pub fn cholesky_solve(&self, b: &SVec<F64, N>) -> Option<SVec<F64, N>> {
+        let l = self.cholesky()?;
+        let mut x = b.elements;
+        // L y = b, then L^T x = y
+        for i in 0..N {
+            let sum: F64 = (0..i).map(|k| l.rows[i][k] * x[k]).sum();
+            x[i] = (x[i] - sum) / l.rows[i][i];
+        }
+        for i in (0..N).rev() {
+            let sum: F64 = ((i + 1)..N).map(|k| l.rows[k][i] * x[k]).sum();
+            x[i] = (x[i] - sum) / l.rows[i][i];
+        }
+        Some(SVec::from_array(x))
+    }
+}
+
+// Factors of a fixed-size LU: the multipliers of L below the diagonal of
+// `lu` and U on and above it. At step k, row k was interchanged with row
+// `pivots[k]` before elimination, so P A = L U with P the product of the
+// interchanges.
+#[derive(Clone, Copy, Debug)]
+pub struct SLu<const N: usize> {
+    lu: SMat<F64, N, N>,
+    pivots: [usize; N],
+}
+
+impl<const N: usize> SLu<N> {
+    pub fn pivots(&self) -> &[usize; N] {
+        &self.pivots
+    }
+
+    pub fn l(&self) -> SMat<F64, N, N> {
+        SMat::from_fn(|i, j| match i.cmp(&j) {
+            core::cmp::Ordering::Greater => self.lu[(i, j)],
+            core::cmp::Ordering::Equal => F64::ONE,
+            core::cmp::Ordering::Less => F64::ZERO,
+        })
+    }
+
+    pub fn u(&self) -> SMat<F64, N, N> {
+        SMat::from_fn(|i, j| if i <= j { self.lu[(i, j)] } else { F64::ZERO })
+    }
+
+    pub fn solve(&self, b: &SVec<F64, N>) -> SVec<F64, N> {
+        let lu = &self.lu.rows;
+        let mut x = b.elements;
+        // whole rows were swapped, so L is in the final row order
+        for (k, p) in self.pivots.iter().enumerate() {
+            x.swap(k, *p);
+        }
+        for k in 0..N {
+            let xk = x[k];
+            for i in (k + 1)..N {
+                x[i] -= lu[i][k] * xk;
+            }
+        }
+        for k in (0..N).rev() {
+            let mut sum = x[k];
+            for j in (k + 1)..N {
+                sum -= lu[k][j] * x[j];
+            }
+            x[k] = sum / lu[k][k];
+        }
+        SVec::from_array(x)
+    }
+
+    pub fn det(&self) -> F64 {
+        let mut det = F64::ONE;
+        for (k, p) in self.pivots.iter().enumerate() {
+            det *= self.lu[(k, k)];
+            if *p != k {
+                det = -det;
+            }
+        }
+        det
+    }
+}
+
+impl<const R: usize, const C: usize> Add for SMat<F64, R, C> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::from_fn(|i, j| self.rows[i][j] + rhs.rows[i][j])
+    }
+}
+
+impl<const R: usize, const C: usize> Sub for SMat<F64, R, C> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_fn(|i, j| self.rows[i][j] - rhs.rows[i][j])
+    }
+}
+
+impl<const R: usize, const C: usize> Neg for SMat<F64, R, C> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::from_fn(|i, j| -self.rows[i][j])
+    }
+}
+
+impl<const R: usize, const C: usize> Mul<F64> for SMat<F64, R, C> {
+    type Output = Self;
+    fn mul(self, rhs: F64) -> Self {
+        Self::from_fn(|i, j| self.rows[i][j] * rhs)
+    }
+}
+
+// (R x K) (K x C), the inner dimensions are checked by the compiler
+impl<const R: usize, const K: usize, const C: usize> Mul<SMat<F64, K, C>> for SMat<F64, R, K> {
+    type Output = SMat<F64, R, C>;
+    fn mul(self, rhs: SMat<F64, K, C>) -> SMat<F64, R, C> {
+        SMat::from_fn(|i, j| (0..K).map(|k| self.rows[i][k] * rhs.rows[k][j]).sum())
+    }
+}
+
+impl<const R: usize, const C: usize> Mul<SVec<F64, C>> for SMat<F64, R, C> {
+    type Output = SVec<F64, R>;
+    fn mul(self, rhs: SVec<F64, C>) -> SVec<F64, R> {
+        SVec::from_array(core::array::from_fn(|i| self.row(i).dot(&rhs)))
+    }
+}
+
+impl<const N: usize> Add for SVec<F64, N> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::from_array(core::array::from_fn(|i| self[i] + rhs[i]))
+    }
+}
+
+impl<const N: usize> Sub for SVec<F64, N> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_array(core::array::from_fn(|i| self[i] - rhs[i]))
+    }
+}
+
+impl<const N: usize> Neg for SVec<F64, N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::from_array(self.elements.map(|x| -x))
+    }
+}
+
+impl<const N: usize> Mul<F64> for SVec<F64, N> {
+    type Output = Self;
+    fn mul(self, rhs: F64) -> Self {
+        Self::from_array(self.elements.map(|x| x * rhs))
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for SMat<T, R, C> {
+    type Output = T;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.rows[index.0][index.1]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<(usize, usize)> for SMat<T, R, C> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self.rows[index.0][index.1]
+    }
+}
+
+impl<T, const N: usize> Index<usize> for SVec<T, N> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.elements[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for SVec<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.elements[index]
+    }
+}
+
+impl<T: Display, const R: usize, const C: usize> Matrix<T> for SMat<T, R, C> {
+    fn shape(&self) -> (usize, usize) {
+        (R, C)
+    }
+}
+
+impl<T: Display, const R: usize, const C: usize> Display for SMat<T, R, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(self, f)
+    }
+}
+
+impl<T: Copy, const R: usize, const C: usize> From<SMat<T, R, C>> for FullMat<T> {
+    fn from(mat: SMat<T, R, C>) -> Self {
+        FullMat::from_vec(C, mat.rows.concat())
+    }
+}
+
+// fails with the two shapes when the matrix is not R x C
+impl<T: Copy + Display, const R: usize, const C: usize> TryFrom<&FullMat<T>> for SMat<T, R, C> {
+    type Error = MatrixError;
+    fn try_from(mat: &FullMat<T>) -> Result<Self, MatrixError> {
+        if mat.shape() != (R, C) {
+            return Err(MatrixError::ShapeMismatch {
+                left: mat.shape(),
+                right: (R, C),
+            });
+        }
+        Ok(Self::from_fn(|i, j| mat[(i, j)]))
+    }
+}
+
+impl<T, const N: usize> From<SVec<T, N>> for Vec<T> {
+    fn from(v: SVec<T, N>) -> Self {
+        v.elements.into()
+    }
+}
+
+// fails with the shapes of both as columns when the length is not N
+impl<T: Copy, const N: usize> TryFrom<&[T]> for SVec<T, N> {
+    type Error = MatrixError;
+    fn try_from(v: &[T]) -> Result<Self, MatrixError> {
+        match <[T; N]>::try_from(v) {
+            Ok(elements) => Ok(Self::from_array(elements)),
+            Err(_) => Err(MatrixError::ShapeMismatch {
+                left: (v.len(), 1),
+                right: (N, 1),
+            }),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use floating_point::F64;
+
+use crate::{vector, RowAccess};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StationaryMethod {
+    Jacobi,
+    GaussSeidel,
+    // successive over-relaxation with relaxation factor omega in (0, 2)
+    SOR(F64),
+    // a forward SOR sweep followed by a backward one
+    SSOR(F64),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StationaryOptions {
+    // stop once ||b - A x||_2 <= tolerance * ||b||_2
+    pub tolerance: F64,
+    pub max_iterations: usize,
+    // refuse to start unless the matrix is strictly diagonally dominant,
+    // which guarantees convergence of Jacobi and Gauss-Seidel
+    pub require_diagonal_dominance: bool,
+}
+
+impl Default for StationaryOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: F64::from(1e-10),
+            max_iterations: 1000,
+            require_diagonal_dominance: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StationaryError {
+    ZeroDiagonal(usize),
+    NotDiagonallyDominant(usize),
+}
+
+impl std::fmt::Display for StationaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZeroDiagonal(i) => write!(f, "zero diagonal entry in row {}", i),
+            Self::NotDiagonallyDominant(i) => {
+                write!(f, "row {} is not strictly diagonally dominant", i)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StationaryError {}
+
+#[derive(Debug, Clone)]
+pub struct StationaryResult {
+    pub x: Vec<F64>,
+    pub iterations: usize,
+    pub converged: bool,
+    // residual norm ||b - A x||_2 after every iteration
+    pub residual_history: Vec<F64>,
+}
+
+// Returns the first row whose diagonal entry does not exceed the sum of the
+// other entries in absolute value.
+pub fn check_diagonal_dominance<A: RowAccess>(a: &A) -> Result<(), StationaryError> {
+    for i in 0..a.dim() {
+        let (mut diag, mut off) = (F64::ZERO, F64::ZERO);
+        a.for_each_in_row(i, |j, x| {
+            if i == j {
+                diag += x;
+            } else {
+                off += x.abs();
+            }
+        });
+        if diag.abs() <= off {
+            return Err(StationaryError::NotDiagonallyDominant(i));
+        }
+    }
+    Ok(())
+}
+
+fn sweep<A: RowAccess>(
+    a: &A,
+    b: &[F64],
+    diag: &[F64],
+    omega: F64,
+    x: &mut [F64],
+    rows: impl Iterator<Item = usize>,
+) {
+    for i in rows {
+        let mut sum = F64::ZERO;
+        a.for_each_in_row(i, |j, aij| {
+            if i != j {
+                sum += aij * x[j];
+            }
+        });
+        x[i] = (F64::ONE - omega) * x[i] + omega * (b[i] - sum) / diag[i];
+    }
+}
+
+pub fn stationary_solve<A: RowAccess>(
+    a: &A,
+    b: &[F64],
+    x0: &[F64],
+    method: StationaryMethod,
+    options: StationaryOptions,
+) -> Result<StationaryResult, StationaryError> {
+    let n = a.dim();
+    assert!(b.len() == n && x0.len() == n);
+
+    let diag = a.diagonal();
+    if let Some(i) = diag.iter().position(|d| *d == F64::ZERO) {
+        return Err(StationaryError::ZeroDiagonal(i));
+    }
+    if options.require_diagonal_dominance {
+        check_diagonal_dominance(a)?;
+    }
+
+    let b_norm = vector::norm2(b);
+    let mut x = x0.to_vec();
+    let mut result = StationaryResult {
+        x: vec![],
+        iterations: 0,
+        converged: false,
+        residual_history: vec![],
+    };
+
+    for _ in 0..options.max_iterations {
+        match method {
+            StationaryMethod::Jacobi => {
+                let ax = a.apply(&x);
+                for i in 0..n {
+                    x[i] += (b[i] - ax[i]) / diag[i];
+                }
+            }
+            StationaryMethod::GaussSeidel => sweep(a, b, &diag, F64::ONE, &mut x, 0..n),
+            StationaryMethod::SOR(omega) => sweep(a, b, &diag, omega, &mut x, 0..n),
+            StationaryMethod::SSOR(omega) => {
+                sweep(a, b, &diag, omega, &mut x, 0..n);
+                sweep(a, b, &diag, omega, &mut x, (0..n).rev());
+            }
+        }
+
+        let residual_norm = vector::norm2(&vector::sub(b, &a.apply(&x)));
+        result.iterations += 1;
+        result.residual_history.push(residual_norm);
+        if residual_norm <= options.tolerance * b_norm {
+            result.converged = true;
+            break;
+        }
+    }
+
+    result.x = x;
+    Ok(result)
+}
+
+// Young's optimal relaxation factor 2 / (1 + sqrt(1 - rho^2)) for a
+// consistently ordered matrix, where rho is the spectral radius of the
+// Jacobi iteration matrix B = I - D^-1 A. rho is estimated by power
+// iteration on B^2, whose dominant eigenvalue is not split into a +-rho
+// pair. Returns None if the estimate does not converge or rho >= 1.
+pub fn optimal_sor_omega<A: RowAccess>(a: &A, options: StationaryOptions) -> Option<F64> {
+    let n = a.dim();
+    let diag = a.diagonal();
+    if diag.contains(&F64::ZERO) {
+        return None;
+    }
+    let jacobi = |x: &[F64]| -> Vec<F64> {
+        let ax = a.apply(x);
+        (0..n).map(|i| x[i] - ax[i] / diag[i]).collect()
+    };
+
+    // a start vector unlikely to be orthogonal to the dominant eigenvector
+    let mut u: Vec<F64> = (0..n).map(|i| F64::from(1.0 + (i % 7) as f64)).collect();
+    let norm = vector::norm2(&u);
+    u.iter_mut().for_each(|x| *x /= norm);
+
+    let mut rho2 = F64::ZERO;
+    for _ in 0..options.max_iterations {
+        let y = jacobi(&jacobi(&u));
+        let next = vector::norm2(&y);
+        if next == F64::ZERO {
+            return Some(F64::ONE);
+        }
+        let done = (next - rho2).abs() <= options.tolerance * next;
+        rho2 = next;
+        u = y.into_iter().map(|x| x / next).collect();
+        if done {
+            if rho2 >= F64::ONE {
+                return None;
+            }
+            return Some(F64::from(2.0) / (F64::ONE + (F64::ONE - rho2).sqrt()));
+        }
+    }
+    None
+}
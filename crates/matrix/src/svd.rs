@@ -0,0 +1,489 @@
+use floating_point::F64;
+
+use crate::{qr::HouseholderQr, FullMat, Matrix};
+
+const MAX_SWEEPS: usize = 75;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvdMode {
+    // U is m x min(m, n) and V is n x min(m, n)
+    Thin,
+    // U is m x m and V is n x n
+    Full,
+}
+
+#[derive(Clone, Debug)]
+pub struct Svd {
+    pub u: FullMat<F64>,
+    pub singular_values: Vec<F64>,
+    pub v: FullMat<F64>,
+}
+
+impl Svd {
+    // Singular values below this are treated as zero.
+    pub fn tolerance(&self) -> F64 {
+        let max_dim = self.u.row_count().max(self.v.row_count());
+        F64::from(max_dim as f64) * F64::EPSILON * self.singular_values[0]
+    }
+
+    pub fn rank(&self) -> usize {
+        let tol = self.tolerance();
+        self.singular_values.iter().filter(|s| **s > tol).count()
+    }
+}
+
+// Golub-Kahan-Reinsch SVD of an m x n matrix with m >= n. On success `a` is
+// overwritten by the thin U, and the singular values and V are returned
+// unsorted.
+fn golub_kahan(
+    a: &mut FullMat<F64>,
+    want_u: bool,
+    want_v: bool,
+) -> Option<(Vec<F64>, FullMat<F64>)> {
+    let (m, n) = a.shape();
+    debug_assert!(m >= n);
+
+    let two = F64::from(2.0);
+    let mut w = vec![F64::ZERO; n];
+    let mut v = FullMat::zeros(n, n);
+    let mut rv1 = vec![F64::ZERO; n];
+    let (mut g, mut scale, mut anorm) = (F64::ZERO, F64::ZERO, F64::ZERO);
+    let mut l = 0;
+
+    // Householder reduction to bidiagonal form
+    for i in 0..n {
+        l = i + 1;
+        rv1[i] = scale * g;
+        g = F64::ZERO;
+        scale = F64::ZERO;
+        let mut s = F64::ZERO;
+
+        for k in i..m {
+            scale += a[(k, i)].abs();
+        }
+        if scale != F64::ZERO {
+            for k in i..m {
+                a[(k, i)] /= scale;
+                s += a[(k, i)] * a[(k, i)];
+            }
+            let f = a[(i, i)];
+            g = -s.sqrt().copysign(f);
+            let h = f * g - s;
+            a[(i, i)] = f - g;
+            for j in l..n {
+                let mut s = F64::ZERO;
+                for k in i..m {
+                    s += a[(k, i)] * a[(k, j)];
+                }
+                let f = s / h;
+                for k in i..m {
+                    let aki = a[(k, i)];
+                    a[(k, j)] += f * aki;
+                }
+            }
+            for k in i..m {
+                a[(k, i)] *= scale;
+            }
+        }
+        w[i] = scale * g;
+
+        g = F64::ZERO;
+        scale = F64::ZERO;
+        s = F64::ZERO;
+        if i + 1 != n {
+            for k in l..n {
+                scale += a[(i, k)].abs();
+            }
+            if scale != F64::ZERO {
+                for k in l..n {
+                    a[(i, k)] /= scale;
+                    s += a[(i, k)] * a[(i, k)];
+                }
+                let f = a[(i, l)];
+                g = -s.sqrt().copysign(f);
+                let h = f * g - s;
+                a[(i, l)] = f - g;
+                for k in l..n {
+                    rv1[k] = a[(i, k)] / h;
+                }
+                for j in l..m {
+                    let mut s = F64::ZERO;
+                    for k in l..n {
+                        s += a[(j, k)] * a[(i, k)];
+                    }
+                    for k in l..n {
+                        a[(j, k)] += s * rv1[k];
+                    }
+                }
+                for k in l..n {
+                    a[(i, k)] *= scale;
+                }
+            }
+        }
+        anorm = anorm.max(w[i].abs() + rv1[i].abs());
+    }
+
+    // accumulation of right-hand transformations
+    if want_v {
+        for i in (0..n).rev() {
+            if i + 1 < n {
+                if g != F64::ZERO {
+                    for j in l..n {
+                        // double division avoids possible underflow
+                        v[(j, i)] = (a[(i, j)] / a[(i, l)]) / g;
+                    }
+                    for j in l..n {
+                        let mut s = F64::ZERO;
+                        for k in l..n {
+                            s += a[(i, k)] * v[(k, j)];
+                        }
+                        for k in l..n {
+                            let vki = v[(k, i)];
+                            v[(k, j)] += s * vki;
+                        }
+                    }
+                }
+                for j in l..n {
+                    v[(i, j)] = F64::ZERO;
+                    v[(j, i)] = F64::ZERO;
+                }
+            }
+            v[(i, i)] = F64::ONE;
+            g = rv1[i];
+            l = i;
+        }
+    }
+
+    // accumulation of left-hand transformations
+    if want_u {
+        for i in (0..n).rev() {
+            let l = i + 1;
+            let mut g = w[i];
+            for j in l..n {
+                a[(i, j)] = F64::ZERO;
+            }
+            if g != F64::ZERO {
+                g = F64::ONE / g;
+                for j in l..n {
+                    let mut s = F64::ZERO;
+                    for k in l..m {
+                        s += a[(k, i)] * a[(k, j)];
+                    }
+                    let f = (s / a[(i, i)]) * g;
+                    for k in i..m {
+                        let aki = a[(k, i)];
+                        a[(k, j)] += f * aki;
+                    }
+                }
+                for j in i..m {
+                    a[(j, i)] *= g;
+                }
+            } else {
+                for j in i..m {
+                    a[(j, i)] = F64::ZERO;
+                }
+            }
+            a[(i, i)] += F64::ONE;
+        }
+    }
+
+    // diagonalization of the bidiagonal form by implicit-shift QR
+    let eps = F64::EPSILON;
+    for k in (0..n).rev() {
+        let mut its = 0;
+        loop {
+            let mut flag = true;
+            let mut l = k;
+            loop {
+                if l == 0 || rv1[l].abs() <= eps * anorm {
+                    flag = false;
+                    break;
+                }
+                if w[l - 1].abs() <= eps * anorm {
+                    break;
+                }
+                l -= 1;
+            }
+
+            if flag {
+                // cancellation of rv1[l] when w[l - 1] is negligible
+                let nm = l - 1;
+                let (mut c, mut s) = (F64::ZERO, F64::ONE);
+                for i in l..=k {
+                    let f = s * rv1[i];
+                    rv1[i] = c * rv1[i];
+                    if f.abs() <= eps * anorm {
+                        break;
+                    }
+                    let g = w[i];
+                    let h = f.hypot(g);
+                    w[i] = h;
+                    c = g / h;
+                    s = -f / h;
+                    if want_u {
+                        for j in 0..m {
+                            let y = a[(j, nm)];
+                            let z = a[(j, i)];
+                            a[(j, nm)] = y * c + z * s;
+                            a[(j, i)] = z * c - y * s;
+                        }
+                    }
+                }
+            }
+
+            let z = w[k];
+            if l == k {
+                if z < F64::ZERO {
+                    w[k] = -z;
+                    if want_v {
+                        for j in 0..n {
+                            v[(j, k)] = -v[(j, k)];
+                        }
+                    }
+                }
+                break;
+            }
+            if its == MAX_SWEEPS {
+                return None;
+            }
+            its += 1;
+
+            // shift from the bottom 2 x 2 minor
+            let mut x = w[l];
+            let nm = k - 1;
+            let mut y = w[nm];
+            let mut g = rv1[nm];
+            let mut h = rv1[k];
+            let mut f = ((y - z) * (y + z) + (g - h) * (g + h)) / (two * h * y);
+            g = f.hypot(F64::ONE);
+            f = ((x - z) * (x + z) + h * ((y / (f + g.copysign(f))) - h)) / x;
+
+            // next QR transformation
+            let (mut c, mut s) = (F64::ONE, F64::ONE);
+            for j in l..=nm {
+                let i = j + 1;
+                g = rv1[i];
+                y = w[i];
+                h = s * g;
+                g *= c;
+                let mut z = f.hypot(h);
+                rv1[j] = z;
+                c = f / z;
+                s = h / z;
+                f = x * c + g * s;
+                g = g * c - x * s;
+                h = y * s;
+                y *= c;
+                if want_v {
+                    for jj in 0..n {
+                        let x = v[(jj, j)];
+                        let z = v[(jj, i)];
+                        v[(jj, j)] = x * c + z * s;
+                        v[(jj, i)] = z * c - x * s;
+                    }
+                }
+                z = f.hypot(h);
+                w[j] = z;
+                if z != F64::ZERO {
+                    c = f / z;
+                    s = h / z;
+                }
+                f = c * g + s * y;
+                x = c * y - s * g;
+                if want_u {
+                    for jj in 0..m {
+                        let y = a[(jj, j)];
+                        let z = a[(jj, i)];
+                        a[(jj, j)] = y * c + z * s;
+                        a[(jj, i)] = z * c - y * s;
+                    }
+                }
+            }
+            rv1[l] = F64::ZERO;
+            rv1[k] = f;
+            w[k] = x;
+        }
+    }
+
+    Some((w, v))
+}
+
+// Extends the orthonormal columns of `q` to an orthonormal basis of R^m. The
+// last m - k columns of the full Q of a Householder QR of `q` span the
+// orthogonal complement of its range, and each costs one application of Q.
+fn complete_basis(q: &FullMat<F64>) -> FullMat<F64> {
+    let (m, k) = q.shape();
+    let qr = HouseholderQr::new(q);
+    let mut cols: Vec<Vec<F64>> = (0..k).map(|j| q.col(j)).collect();
+    for j in k..m {
+        let mut e = vec![F64::ZERO; m];
+        e[j] = F64::ONE;
+        qr.apply_q(&mut e);
+        cols.push(e);
+    }
+
+    FullMat::from_cols(cols)
+}
+
+fn sort_descending(
+    u: &FullMat<F64>,
+    w: &[F64],
+    v: &FullMat<F64>,
+) -> (FullMat<F64>, Vec<F64>, FullMat<F64>) {
+    let mut order: Vec<usize> = (0..w.len()).collect();
+    order.sort_by(|i, j| w[*j].to_f64().total_cmp(&w[*i].to_f64()));
+
+    let permute_cols = |mat: &FullMat<F64>| {
+        let mut out = mat.clone();
+        for i in 0..mat.row_count() {
+            for (j, k) in order.iter().enumerate() {
+                out[(i, j)] = mat[(i, *k)];
+            }
+        }
+        out
+    };
+
+    (
+        permute_cols(u),
+        order.iter().map(|k| w[*k]).collect(),
+        permute_cols(v),
+    )
+}
+
+impl FullMat<F64> {
+    // Thin singular value decomposition A = U * diag(s) * V^T with the
+    // singular values in descending order. Returns None for non-finite input
+    // or if the QR sweeps fail to converge.
+    pub fn svd(&self) -> Option<Svd> {
+        self.svd_with(SvdMode::Thin)
+    }
+
+    pub fn svd_with(&self, mode: SvdMode) -> Option<Svd> {
+        if !self.iter().all(|x| x.is_finite()) {
+            return None;
+        }
+        let (m, n) = self.shape();
+        let transposed = m < n;
+        let mut a = if transposed {
+            self.transpose()
+        } else {
+            self.clone()
+        };
+
+        let (w, v) = golub_kahan(&mut a, true, true)?;
+        let (mut u, s, v) = sort_descending(&a, &w, &v);
+        if mode == SvdMode::Full {
+            u = complete_basis(&u);
+        }
+
+        Some(if transposed {
+            Svd {
+                u: v,
+                singular_values: s,
+                v: u,
+            }
+        } else {
+            Svd {
+                u,
+                singular_values: s,
+                v,
+            }
+        })
+    }
+
+    // Singular values in descending order, without accumulating U and V. None
+    // as for `svd`.
+    pub fn singular_values(&self) -> Option<Vec<F64>> {
+        if !self.iter().all(|x| x.is_finite()) {
+            return None;
+        }
+        let (m, n) = self.shape();
+        let mut a = if m < n {
+            self.transpose()
+        } else {
+            self.clone()
+        };
+
+        let (mut w, _) = golub_kahan(&mut a, false, false)?;
+        w.sort_by(|x, y| y.to_f64().total_cmp(&x.to_f64()));
+        Some(w)
+    }
+
+    // Moore-Penrose pseudo-inverse V * diag(1 / s) * U^T, dropping singular
+    // values below the rank tolerance.
+    pub fn pinv(&self) -> Option<Self> {
+        let svd = self.svd()?;
+        let (m, n) = self.shape();
+        let rank = svd.rank();
+
+        let mut mat = FullMat::zeros(n, m);
+        for k in 0..rank {
+            let inv_s = F64::ONE / svd.singular_values[k];
+            for i in 0..n {
+                let vik = svd.v[(i, k)] * inv_s;
+                for j in 0..m {
+                    mat[(i, j)] += vik * svd.u[(j, k)];
+                }
+            }
+        }
+        Some(mat)
+    }
+
+    pub fn rank(&self) -> Option<usize> {
+        let s = self.singular_values()?;
+        let tol = F64::from(self.row_count().max(self.col_count()) as f64) * F64::EPSILON * s[0];
+        Some(s.iter().filter(|x| **x > tol).count())
+    }
+
+    pub fn norm2(&self) -> Option<F64> {
+        self.singular_values().map(|s| s[0])
+    }
+
+    // Ratio of the largest to the smallest singular value, infinite for a
+    // rank-deficient matrix.
+    pub fn cond2(&self) -> Option<F64> {
+        self.singular_values().map(|s| {
+            let min = s[s.len() - 1];
+            if min == F64::ZERO {
+                F64::from(f64::INFINITY)
+            } else {
+                s[0] / min
+            }
+        })
+    }
+
+    // Orthonormal basis of the null space, one vector per column of V beyond
+    // the numerical rank.
+    pub fn null_space(&self) -> Option<Vec<Vec<F64>>> {
+        let svd = self.svd_with(SvdMode::Full)?;
+        Some(
+            (svd.rank()..self.col_count())
+                .map(|k| svd.v.col(k))
+                .collect(),
+        )
+    }
+
+    // Orthonormal basis of the column space.
+    pub fn range(&self) -> Option<Vec<Vec<F64>>> {
+        let svd = self.svd()?;
+        Some((0..svd.rank()).map(|k| svd.u.col(k)).collect())
+    }
+
+    // Best approximation of rank at most k in both the 2-norm and the
+    // Frobenius norm (Eckart-Young).
+    pub fn low_rank_approx(&self, k: usize) -> Option<Self> {
+        let svd = self.svd()?;
+        let (m, n) = self.shape();
+
+        let mut mat = FullMat::zeros(m, n);
+        for (l, s) in svd.singular_values.iter().enumerate().take(k) {
+            for i in 0..m {
+                let uil = svd.u[(i, l)] * *s;
+                for j in 0..n {
+                    mat[(i, j)] += uil * svd.v[(j, l)];
+                }
+            }
+        }
+        Some(mat)
+    }
+}
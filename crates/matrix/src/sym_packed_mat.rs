@@ -0,0 +1,342 @@
+use core::ops::{Index, IndexMut};
+use std::fmt::Display;
+
+use floating_point::{Complex64, F64};
+
+use crate::{FullMat, Matrix, MatrixError, TriangleMatType};
+
+// A symmetric matrix with one triangle stored row by row, in the order of
+// `TriFullMat`. Entries of the other triangle are read through their mirror
+// image, so writing (i, j) also changes (j, i).
+#[derive(Debug, Clone)]
+pub struct SymPackedMat<T> {
+    pub ty: TriangleMatType,
+    n: usize,
+    storage: Vec<T>,
+}
+
+// A Hermitian matrix stored like `SymPackedMat`. The mirrored entries are the
+// conjugates of the stored ones, so they are read with `get` instead of
+// `Index`.
+#[derive(Debug, Clone)]
+pub struct HermPackedMat {
+    pub ty: TriangleMatType,
+    n: usize,
+    storage: Vec<Complex64>,
+}
+
+fn triangle_order(len: usize) -> Option<usize> {
+    let (mut left, mut n) = (len, 0);
+    while left > n {
+        left -= n + 1;
+        n += 1;
+    }
+    (left == 0 && n > 0).then_some(n)
+}
+
+fn index_in_vec(ty: TriangleMatType, n: usize, (i, j): (usize, usize)) -> usize {
+    assert!(i < n && j < n);
+    match ty {
+        TriangleMatType::Upper => {
+            let (i, j) = (i.min(j), i.max(j));
+            (2 * n - 1 - i) * i / 2 + j
+        }
+        TriangleMatType::Lower => {
+            let (i, j) = (i.max(j), i.min(j));
+            (1 + i) * i / 2 + j
+        }
+    }
+}
+
+// (i, j) of the stored entries in storage order
+fn positions(ty: TriangleMatType, n: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..n).flat_map(move |i| {
+        let cols = match ty {
+            TriangleMatType::Upper => i..n,
+            TriangleMatType::Lower => 0..(i + 1),
+        };
+        cols.map(move |j| (i, j))
+    })
+}
+
+// y = A x for the symmetric matrix of order n stored in `storage`, each
+// off-diagonal entry read once and used twice
+pub(crate) fn packed_mul_vec(
+    ty: TriangleMatType,
+    n: usize,
+    storage: &[F64],
+    x: &[F64],
+) -> Vec<F64> {
+    assert!(x.len() == n);
+    let mut y = vec![F64::ZERO; n];
+    for ((i, j), a) in positions(ty, n).zip(storage) {
+        y[i] += *a * x[j];
+        if i != j {
+            y[j] += *a * x[i];
+        }
+    }
+    y
+}
+
+// A += alpha (x y^T + y x^T)
+pub(crate) fn packed_rank2_update(
+    ty: TriangleMatType,
+    n: usize,
+    storage: &mut [F64],
+    alpha: F64,
+    x: &[F64],
+    y: &[F64],
+) {
+    assert!(x.len() == n && y.len() == n);
+    for ((i, j), a) in positions(ty, n).zip(storage) {
+        *a += alpha * (x[i] * y[j] + y[i] * x[j]);
+    }
+}
+
+impl<T> SymPackedMat<T> {
+    pub fn storage(&self) -> &[T] {
+        &self.storage
+    }
+
+    pub fn into_storage(self) -> Vec<T> {
+        self.storage
+    }
+}
+
+impl<T: Clone> SymPackedMat<T> {
+    // the stored triangle, row by row as for `TriFullMat::from_rows`
+    pub fn from_rows(ty: TriangleMatType, rows: Vec<Vec<T>>) -> Self {
+        assert!(!rows.is_empty());
+
+        let n = rows.len();
+        for (i, row) in rows.iter().enumerate() {
+            assert!(
+                row.len()
+                    == match ty {
+                        TriangleMatType::Upper => n - i,
+                        TriangleMatType::Lower => i + 1,
+                    }
+            );
+        }
+        Self::from_vec(ty, rows.concat())
+    }
+
+    pub fn from_vec(ty: TriangleMatType, elements: Vec<T>) -> Self {
+        let n = triangle_order(elements.len()).unwrap();
+        Self {
+            ty,
+            n,
+            storage: elements,
+        }
+    }
+
+    pub fn try_from_vec(ty: TriangleMatType, elements: Vec<T>) -> Result<Self, MatrixError> {
+        if elements.is_empty() {
+            return Err(MatrixError::EmptyInput);
+        }
+        match triangle_order(elements.len()) {
+            Some(_) => Ok(Self::from_vec(ty, elements)),
+            None => Err(MatrixError::NonTriangularLength(elements.len())),
+        }
+    }
+
+    // Only the `ty` triangle of the square matrix is read.
+    pub fn from_full(ty: TriangleMatType, mat: &FullMat<T>) -> Self
+    where
+        T: Copy + Display,
+    {
+        assert!(mat.is_square());
+        let n = mat.row_count();
+        Self {
+            ty,
+            n,
+            storage: positions(ty, n).map(|index| mat[index]).collect(),
+        }
+    }
+}
+
+impl SymPackedMat<F64> {
+    pub fn mul_vec(&self, x: &[F64]) -> Vec<F64> {
+        packed_mul_vec(self.ty, self.n, &self.storage, x)
+    }
+
+    // A += alpha x x^T
+    pub fn rank1_update(&mut self, alpha: F64, x: &[F64]) {
+        assert!(x.len() == self.n);
+        for ((i, j), a) in positions(self.ty, self.n).zip(&mut self.storage) {
+            *a += alpha * x[i] * x[j];
+        }
+    }
+
+    // A += alpha (x y^T + y x^T)
+    pub fn rank2_update(&mut self, alpha: F64, x: &[F64], y: &[F64]) {
+        packed_rank2_update(self.ty, self.n, &mut self.storage, alpha, x, y);
+    }
+
+    // the same matrix with the other triangle stored
+    pub fn to_other_triangle(&self) -> Self {
+        let ty = match self.ty {
+            TriangleMatType::Upper => TriangleMatType::Lower,
+            TriangleMatType::Lower => TriangleMatType::Upper,
+        };
+        Self {
+            ty,
+            n: self.n,
+            storage: positions(ty, self.n).map(|index| self[index]).collect(),
+        }
+    }
+}
+
+impl HermPackedMat {
+    pub fn from_vec(ty: TriangleMatType, elements: Vec<Complex64>) -> Self {
+        let n = triangle_order(elements.len()).unwrap();
+        Self {
+            ty,
+            n,
+            storage: elements,
+        }
+    }
+
+    // Only the `ty` triangle of the square matrix is read, and the imaginary
+    // parts of its diagonal are dropped.
+    pub fn from_full(ty: TriangleMatType, mat: &FullMat<Complex64>) -> Self {
+        assert!(mat.is_square());
+        let n = mat.row_count();
+        let storage = positions(ty, n)
+            .map(|(i, j)| {
+                if i == j {
+                    Complex64::new(mat[(i, i)].real.to_f64(), 0.0)
+                } else {
+                    mat[(i, j)]
+                }
+            })
+            .collect();
+        Self { ty, n, storage }
+    }
+
+    pub fn storage(&self) -> &[Complex64] {
+        &self.storage
+    }
+
+    pub fn order(&self) -> usize {
+        self.n
+    }
+
+    fn is_stored(&self, (i, j): (usize, usize)) -> bool {
+        match self.ty {
+            TriangleMatType::Upper => i <= j,
+            TriangleMatType::Lower => i >= j,
+        }
+    }
+
+    pub fn get(&self, index: (usize, usize)) -> Complex64 {
+        let a = self.storage[index_in_vec(self.ty, self.n, index)];
+        if self.is_stored(index) {
+            a
+        } else {
+            a.conjugate()
+        }
+    }
+
+    // Stores a at (i, j) and its conjugate at (j, i). Diagonal entries are
+    // real.
+    pub fn set(&mut self, index: (usize, usize), a: Complex64) {
+        assert!(index.0 != index.1 || a.imag == F64::ZERO);
+        let k = index_in_vec(self.ty, self.n, index);
+        self.storage[k] = if self.is_stored(index) {
+            a
+        } else {
+            a.conjugate()
+        };
+    }
+
+    pub fn mul_vec(&self, x: &[Complex64]) -> Vec<Complex64> {
+        assert!(x.len() == self.n);
+        let mut y = vec![Complex64::new(0.0, 0.0); self.n];
+        for ((i, j), a) in positions(self.ty, self.n).zip(&self.storage) {
+            y[i] += *a * x[j];
+            if i != j {
+                y[j] += a.conjugate() * x[i];
+            }
+        }
+        y
+    }
+
+    // A += alpha x x^H, with a real alpha to keep A Hermitian
+    pub fn rank1_update(&mut self, alpha: F64, x: &[Complex64]) {
+        assert!(x.len() == self.n);
+        let alpha = Complex64::new(alpha.to_f64(), 0.0);
+        for ((i, j), a) in positions(self.ty, self.n).zip(&mut self.storage) {
+            *a += alpha * x[i] * x[j].conjugate();
+        }
+        self.make_diagonal_real();
+    }
+
+    // A += alpha x y^H + conj(alpha) y x^H
+    pub fn rank2_update(&mut self, alpha: Complex64, x: &[Complex64], y: &[Complex64]) {
+        assert!(x.len() == self.n && y.len() == self.n);
+        for ((i, j), a) in positions(self.ty, self.n).zip(&mut self.storage) {
+            *a += alpha * x[i] * y[j].conjugate() + alpha.conjugate() * y[i] * x[j].conjugate();
+        }
+        self.make_diagonal_real();
+    }
+
+    // The updates add terms that are conjugates of each other only before
+    // rounding, which leaves an imaginary part of the order of eps on the
+    // diagonal.
+    fn make_diagonal_real(&mut self) {
+        for ((i, j), a) in positions(self.ty, self.n).zip(&mut self.storage) {
+            if i == j {
+                a.imag = F64::ZERO;
+            }
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for SymPackedMat<T> {
+    type Output = T;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.storage[index_in_vec(self.ty, self.n, index)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for SymPackedMat<T> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        let k = index_in_vec(self.ty, self.n, index);
+        &mut self.storage[k]
+    }
+}
+
+impl<T: Display> Matrix<T> for SymPackedMat<T> {
+    fn shape(&self) -> (usize, usize) {
+        (self.n, self.n)
+    }
+}
+
+impl<T: Display> Display for SymPackedMat<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(self, f)
+    }
+}
+
+impl Display for HermPackedMat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(&FullMat::from(self), f)
+    }
+}
+
+impl<T: Copy> From<&SymPackedMat<T>> for FullMat<T> {
+    fn from(mat: &SymPackedMat<T>) -> Self {
+        let n = mat.n;
+        let v = (0..n).flat_map(|i| (0..n).map(move |j| mat[(i, j)]));
+        FullMat::from_vec(n, v.collect())
+    }
+}
+
+impl From<&HermPackedMat> for FullMat<Complex64> {
+    fn from(mat: &HermPackedMat) -> Self {
+        let n = mat.n;
+        let v = (0..n).flat_map(|i| (0..n).map(move |j| mat.get((i, j))));
+        FullMat::from_vec(n, v.collect())
+    }
+}
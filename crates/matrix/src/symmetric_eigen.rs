@@ -0,0 +1,424 @@
+use core::ops::Range;
+
+use floating_point::F64;
+
+use crate::{
+    sym_packed_mat::{packed_mul_vec, packed_rank2_update},
+    vector::{dot, norm2},
+    FullMat, Matrix, SymPackedMat, TriangleMatType,
+};
+
+const MAX_QL_ITERATIONS: usize = 30;
+const MAX_JACOBI_SWEEPS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymmetricEigenMethod {
+    // Householder tridiagonalization followed by implicit QL
+    TridiagonalQL,
+    // cyclic Jacobi rotations, slower but with high relative accuracy
+    Jacobi,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EigenSubset {
+    All,
+    // positions in the ascending order of eigenvalues
+    Index(Range<usize>),
+    // eigenvalues in the half-open interval (lower, upper]
+    Interval(F64, F64),
+}
+
+// Eigenvalues in ascending order, eigenvectors[k] is the unit eigenvector
+// belonging to eigenvalues[k].
+#[derive(Debug, Clone)]
+pub struct SymmetricEigen {
+    pub eigenvalues: Vec<F64>,
+    pub eigenvectors: Vec<Vec<F64>>,
+}
+
+// Reduces the symmetric matrix `a` to tridiagonal form by Householder
+// similarity transforms. On return `a` holds the orthogonal transform, `d`
+// the diagonal and `e[1..]` the subdiagonal.
+fn tridiagonalize(a: &mut FullMat<F64>, d: &mut [F64], e: &mut [F64]) {
+    let n = a.col_count();
+
+    for i in (1..n).rev() {
+        let l = i - 1;
+        let mut h = F64::ZERO;
+        if l > 0 {
+            let mut scale = F64::ZERO;
+            for k in 0..=l {
+                scale += a[(i, k)].abs();
+            }
+            if scale == F64::ZERO {
+                e[i] = a[(i, l)];
+            } else {
+                for k in 0..=l {
+                    a[(i, k)] /= scale;
+                    h += a[(i, k)] * a[(i, k)];
+                }
+                let f = a[(i, l)];
+                let g = -h.sqrt().copysign(f);
+                e[i] = scale * g;
+                h -= f * g;
+                a[(i, l)] = f - g;
+
+                let mut f = F64::ZERO;
+                for j in 0..=l {
+                    a[(j, i)] = a[(i, j)] / h;
+                    let mut g = F64::ZERO;
+                    for k in 0..=j {
+                        g += a[(j, k)] * a[(i, k)];
+                    }
+                    for k in (j + 1)..=l {
+                        g += a[(k, j)] * a[(i, k)];
+                    }
+                    e[j] = g / h;
+                    f += e[j] * a[(i, j)];
+                }
+                let hh = f / (h + h);
+                for j in 0..=l {
+                    let f = a[(i, j)];
+                    let g = e[j] - hh * f;
+                    e[j] = g;
+                    for k in 0..=j {
+                        let aik = a[(i, k)];
+                        a[(j, k)] -= f * e[k] + g * aik;
+                    }
+                }
+            }
+        } else {
+            e[i] = a[(i, l)];
+        }
+        d[i] = h;
+    }
+
+    d[0] = F64::ZERO;
+    e[0] = F64::ZERO;
+    for i in 0..n {
+        if d[i] != F64::ZERO {
+            for j in 0..i {
+                let mut g = F64::ZERO;
+                for k in 0..i {
+                    g += a[(i, k)] * a[(k, j)];
+                }
+                for k in 0..i {
+                    let aki = a[(k, i)];
+                    a[(k, j)] -= g * aki;
+                }
+            }
+        }
+        d[i] = a[(i, i)];
+        a[(i, i)] = F64::ONE;
+        for j in 0..i {
+            a[(j, i)] = F64::ZERO;
+            a[(i, j)] = F64::ZERO;
+        }
+    }
+}
+
+// Implicit QL iteration with Wilkinson shifts on the tridiagonal matrix from
+// `tridiagonalize`, accumulating the rotations into the columns of `z` if
+// eigenvectors are wanted.
+fn tridiagonal_ql(d: &mut [F64], e: &mut [F64], mut z: Option<&mut FullMat<F64>>) -> Option<()> {
+    let n = d.len();
+    for i in 1..n {
+        e[i - 1] = e[i];
+    }
+    e[n - 1] = F64::ZERO;
+
+    for l in 0..n {
+        let mut iter = 0;
+        loop {
+            let mut m = l;
+            while m + 1 < n {
+                let dd = d[m].abs() + d[m + 1].abs();
+                if e[m].abs() <= F64::EPSILON * dd {
+                    break;
+                }
+                m += 1;
+            }
+            if m == l {
+                break;
+            }
+            if iter == MAX_QL_ITERATIONS {
+                return None;
+            }
+            iter += 1;
+
+            let mut g = (d[l + 1] - d[l]) / (F64::from(2.0) * e[l]);
+            let mut r = g.hypot(F64::ONE);
+            g = d[m] - d[l] + e[l] / (g + r.copysign(g));
+            let (mut s, mut c, mut p) = (F64::ONE, F64::ONE, F64::ZERO);
+            let mut underflow = false;
+            for i in (l..m).rev() {
+                let f = s * e[i];
+                let b = c * e[i];
+                r = f.hypot(g);
+                e[i + 1] = r;
+                if r == F64::ZERO {
+                    d[i + 1] -= p;
+                    e[m] = F64::ZERO;
+                    underflow = true;
+                    break;
+                }
+                s = f / r;
+                c = g / r;
+                g = d[i + 1] - p;
+                r = (d[i] - g) * s + F64::from(2.0) * c * b;
+                p = s * r;
+                d[i + 1] = g + p;
+                g = c * r - b;
+                if let Some(z) = z.as_deref_mut() {
+                    for k in 0..n {
+                        let f = z[(k, i + 1)];
+                        let zki = z[(k, i)];
+                        z[(k, i + 1)] = s * zki + c * f;
+                        z[(k, i)] = c * zki - s * f;
+                    }
+                }
+            }
+            if underflow {
+                continue;
+            }
+            d[l] -= p;
+            e[l] = g;
+            e[m] = F64::ZERO;
+        }
+    }
+
+    Some(())
+}
+
+// Cyclic Jacobi. A rotation is skipped only when the off-diagonal entry is
+// negligible relative to its own diagonal entries, which is what gives
+// small eigenvalues their relative accuracy.
+fn jacobi(a: &mut FullMat<F64>, z: &mut FullMat<F64>) -> Option<Vec<F64>> {
+    let n = a.col_count();
+    for sweep in 0.. {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in (p + 1)..n {
+                let apq = a[(p, q)];
+                if apq.abs() <= F64::EPSILON * (a[(p, p)] * a[(q, q)]).abs().sqrt()
+                    || apq == F64::ZERO
+                {
+                    continue;
+                }
+                rotated = true;
+
+                let theta = (a[(q, q)] - a[(p, p)]) / (F64::from(2.0) * apq);
+                let t = F64::ONE.copysign(theta) / (theta.abs() + theta.hypot(F64::ONE));
+                let c = F64::ONE / t.hypot(F64::ONE);
+                let s = t * c;
+
+                for k in 0..n {
+                    let akp = a[(k, p)];
+                    let akq = a[(k, q)];
+                    a[(k, p)] = c * akp - s * akq;
+                    a[(k, q)] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let apk = a[(p, k)];
+                    let aqk = a[(q, k)];
+                    a[(p, k)] = c * apk - s * aqk;
+                    a[(q, k)] = s * apk + c * aqk;
+                }
+                a[(p, q)] = F64::ZERO;
+                a[(q, p)] = F64::ZERO;
+
+                for k in 0..n {
+                    let zkp = z[(k, p)];
+                    let zkq = z[(k, q)];
+                    z[(k, p)] = c * zkp - s * zkq;
+                    z[(k, q)] = s * zkp + c * zkq;
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+        if sweep == MAX_JACOBI_SWEEPS {
+            return None;
+        }
+    }
+
+    Some((0..n).map(|i| a[(i, i)]).collect())
+}
+
+impl FullMat<F64> {
+    // Only the lower triangle of the matrix is referenced.
+    pub fn symmetric_eigen(&self) -> Option<SymmetricEigen> {
+        self.symmetric_eigen_with(SymmetricEigenMethod::TridiagonalQL, EigenSubset::All)
+    }
+
+    // The QL iteration runs without accumulating eigenvectors.
+    pub fn symmetric_eigenvalues(&self) -> Option<Vec<F64>> {
+        assert!(self.is_square());
+
+        let n = self.col_count();
+        let mut a = self.lower_symmetrized();
+        let mut d = vec![F64::ZERO; n];
+        let mut e = vec![F64::ZERO; n];
+        tridiagonalize(&mut a, &mut d, &mut e);
+        tridiagonal_ql(&mut d, &mut e, None)?;
+        Some(select(&d, None, EigenSubset::All).eigenvalues)
+    }
+
+    pub fn symmetric_eigen_with(
+        &self,
+        method: SymmetricEigenMethod,
+        subset: EigenSubset,
+    ) -> Option<SymmetricEigen> {
+        assert!(self.is_square());
+
+        let n = self.col_count();
+        let mut a = self.lower_symmetrized();
+        let (values, vectors) = match method {
+            SymmetricEigenMethod::TridiagonalQL => {
+                let mut d = vec![F64::ZERO; n];
+                let mut e = vec![F64::ZERO; n];
+                tridiagonalize(&mut a, &mut d, &mut e);
+                tridiagonal_ql(&mut d, &mut e, Some(&mut a))?;
+                (d, a)
+            }
+            SymmetricEigenMethod::Jacobi => {
+                let mut z = FullMat::identity(n);
+                let d = jacobi(&mut a, &mut z)?;
+                (d, z)
+            }
+        };
+
+        Some(select(&values, Some(&vectors), subset))
+    }
+
+    fn lower_symmetrized(&self) -> FullMat<F64> {
+        let mut a = self.clone();
+        for i in 0..a.col_count() {
+            for j in (i + 1)..a.col_count() {
+                a[(i, j)] = a[(j, i)];
+            }
+        }
+        a
+    }
+}
+
+// The eigenpairs in `subset`, sorted. Without `vectors` only the eigenvalues
+// are filled in.
+fn select(values: &[F64], vectors: Option<&FullMat<F64>>, subset: EigenSubset) -> SymmetricEigen {
+    let n = values.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|i, j| values[*i].partial_cmp(&values[*j]).unwrap());
+    let selected: Vec<usize> = match subset {
+        EigenSubset::All => order,
+        EigenSubset::Index(range) => {
+            assert!(range.end <= n);
+            order[range].to_vec()
+        }
+        EigenSubset::Interval(lower, upper) => order
+            .into_iter()
+            .filter(|k| values[*k] > lower && values[*k] <= upper)
+            .collect(),
+    };
+
+    SymmetricEigen {
+        eigenvalues: selected.iter().map(|k| values[*k]).collect(),
+        eigenvectors: match vectors {
+            Some(vectors) => selected.iter().map(|k| vectors.col(*k)).collect(),
+            None => vec![],
+        },
+    }
+}
+
+// Householder reduction of a packed matrix to tridiagonal form, as LAPACK's
+// dsptrd does it. The upper triangle is stored row by row, so the trailing
+// block left to reduce after row k is again a packed matrix at the end of
+// `a`, and each step is one packed product and one rank-2 update of it. The
+// reflector I - tau[k] v v^T of step k has v = (0, .., 0, 1, a[k, k + 2..])
+// with its 1 at k + 1. Fills `d` and `e[1..]` as `tridiagonalize`.
+fn tridiagonalize_packed(a: &mut [F64], d: &mut [F64], e: &mut [F64], tau: &mut [F64]) {
+    let n = d.len();
+    e[0] = F64::ZERO;
+    for k in 0..n - 1 {
+        let start = (2 * n - 1 - k) * k / 2 + k;
+        let m = n - k - 1;
+        let (head, trailing) = a.split_at_mut(start + m + 1);
+        let row = &mut head[start..];
+        d[k] = row[0];
+
+        let v = &mut row[1..];
+        let alpha = v[0];
+        let x_norm = norm2(&v[1..]);
+        if x_norm == F64::ZERO {
+            tau[k] = F64::ZERO;
+            e[k + 1] = alpha;
+            continue;
+        }
+        let beta = -alpha.hypot(x_norm).copysign(alpha);
+        tau[k] = (beta - alpha) / beta;
+        e[k + 1] = beta;
+        let scale = F64::ONE / (alpha - beta);
+        v[1..].iter_mut().for_each(|x| *x *= scale);
+        v[0] = F64::ONE;
+
+        // A22 = H A22 H = A22 - v w^T - w v^T
+        let p: Vec<F64> = packed_mul_vec(TriangleMatType::Upper, m, trailing, v)
+            .into_iter()
+            .map(|x| tau[k] * x)
+            .collect();
+        let gamma = -F64::from(0.5) * tau[k] * dot(&p, v);
+        let w: Vec<F64> = p
+            .iter()
+            .zip(v.iter())
+            .map(|(p, v)| *p + gamma * *v)
+            .collect();
+        packed_rank2_update(TriangleMatType::Upper, m, trailing, -F64::ONE, v, &w);
+    }
+    d[n - 1] = a[a.len() - 1];
+}
+
+// Q = H_0 H_1 .. H_{n-2} from the reflectors of `tridiagonalize_packed`.
+fn packed_reflectors_to_q(a: &[F64], tau: &[F64], n: usize) -> FullMat<F64> {
+    let mut q = FullMat::identity(n);
+    for k in (0..n.saturating_sub(1)).rev() {
+        if tau[k] == F64::ZERO {
+            continue;
+        }
+        let start = (2 * n - 1 - k) * k / 2 + k;
+        let v = &a[start + 1..start + n - k];
+        // only the columns right of k have been touched so far
+        for c in (k + 1)..n {
+            let s: F64 = (0..v.len()).map(|r| v[r] * q[(k + 1 + r, c)]).sum();
+            let s = tau[k] * s;
+            for (r, vr) in v.iter().enumerate() {
+                q[(k + 1 + r, c)] -= s * *vr;
+            }
+        }
+    }
+    q
+}
+
+impl SymPackedMat<F64> {
+    // Tridiagonalizes a packed copy of the matrix, so no n x n matrix is
+    // formed unless the eigenvectors are wanted.
+    pub fn symmetric_eigen(&self) -> Option<SymmetricEigen> {
+        self.packed_eigen(true)
+    }
+
+    pub fn symmetric_eigenvalues(&self) -> Option<Vec<F64>> {
+        self.packed_eigen(false).map(|eigen| eigen.eigenvalues)
+    }
+
+    fn packed_eigen(&self, vectors: bool) -> Option<SymmetricEigen> {
+        let n = self.row_count();
+        let mut a = match self.ty {
+            TriangleMatType::Upper => self.storage().to_vec(),
+            TriangleMatType::Lower => self.to_other_triangle().into_storage(),
+        };
+        let (mut d, mut e, mut tau) = (vec![F64::ZERO; n], vec![F64::ZERO; n], vec![F64::ZERO; n]);
+        tridiagonalize_packed(&mut a, &mut d, &mut e, &mut tau);
+        let mut q = vectors.then(|| packed_reflectors_to_q(&a, &tau, n));
+        tridiagonal_ql(&mut d, &mut e, q.as_mut())?;
+        Some(select(&d, q.as_ref(), EigenSubset::All))
+    }
+}
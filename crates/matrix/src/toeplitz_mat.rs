@@ -0,0 +1,187 @@
+use core::ops::Index;
+use std::fmt::Display;
+
+use floating_point::F64;
+
+use crate::{FullMat, LinearOperator, Matrix};
+
+// Square matrix that is constant along its diagonals, stored by its first
+// column and first row: the entry (i, j) is `first_col[i - j]` for i >= j
+// and `first_row[j - i]` otherwise.
+#[derive(Debug, Clone)]
+pub struct ToeplitzMat<T> {
+    first_col: Vec<T>,
+    first_row: Vec<T>,
+}
+
+impl<T: Clone + PartialEq> ToeplitzMat<T> {
+    pub fn new(first_col: Vec<T>, first_row: Vec<T>) -> Self {
+        assert!(!first_col.is_empty());
+        assert!(first_col.len() == first_row.len());
+        assert!(first_col[0] == first_row[0]);
+        Self {
+            first_col,
+            first_row,
+        }
+    }
+
+    pub fn symmetric(first_col: Vec<T>) -> Self {
+        Self::new(first_col.clone(), first_col)
+    }
+
+    pub fn first_col(&self) -> &[T] {
+        &self.first_col
+    }
+
+    pub fn first_row(&self) -> &[T] {
+        &self.first_row
+    }
+}
+
+impl ToeplitzMat<F64> {
+    pub fn mul_vec(&self, rhs: &[F64]) -> Vec<F64> {
+        let n = self.first_col.len();
+        assert!(rhs.len() == n);
+        (0..n)
+            .map(|i| {
+                let mut sum = F64::ZERO;
+                for (j, x) in rhs.iter().enumerate() {
+                    sum += self[(i, j)] * *x;
+                }
+                sum
+            })
+            .collect()
+    }
+
+    // Levinson recursion in O(n^2). The forward and backward vectors f and b
+    // solving T_k f = e_1 and T_k b = e_k for the leading k x k block are
+    // grown together with the solution. Returns None if a leading principal
+    // submatrix is singular, in which case the dense LU is needed instead.
+    pub fn solve(&self, y: &[F64]) -> Option<Vec<F64>> {
+        let n = self.first_col.len();
+        assert!(y.len() == n);
+        let (col, row) = (&self.first_col, &self.first_row);
+
+        if col[0] == F64::ZERO {
+            return None;
+        }
+        let mut f = vec![F64::ONE / col[0]];
+        let mut b = f.clone();
+        let mut x = vec![y[0] / col[0]];
+        for k in 1..n {
+            // T_{k+1} [f; 0] = e_1 + error_f e_{k+1} and
+            // T_{k+1} [0; b] = error_b e_1 + e_{k+1}
+            let mut error_f = F64::ZERO;
+            let mut error_b = F64::ZERO;
+            let mut error_x = F64::ZERO;
+            for j in 0..k {
+                error_f += col[k - j] * f[j];
+                error_b += row[j + 1] * b[j];
+                error_x += col[k - j] * x[j];
+            }
+            let denominator = F64::ONE - error_f * error_b;
+            if denominator == F64::ZERO {
+                return None;
+            }
+
+            let mut next_f = Vec::with_capacity(k + 1);
+            let mut next_b = Vec::with_capacity(k + 1);
+            for j in 0..=k {
+                let fj = if j < k { f[j] } else { F64::ZERO };
+                let bj = if j > 0 { b[j - 1] } else { F64::ZERO };
+                next_f.push((fj - error_f * bj) / denominator);
+                next_b.push((bj - error_b * fj) / denominator);
+            }
+            f = next_f;
+            b = next_b;
+
+            x.push(F64::ZERO);
+            let correction = y[k] - error_x;
+            for (xj, bj) in x.iter_mut().zip(b.iter()) {
+                *xj += correction * *bj;
+            }
+        }
+        Some(x)
+    }
+}
+
+// Durbin's algorithm for the Yule-Walker equations: given r_0, ..., r_n,
+// solves T y = -(r_1, ..., r_n) where T is the symmetric Toeplitz matrix with
+// first column (r_0, ..., r_{n-1}). Returns None if T is not positive
+// definite.
+pub fn durbin(r: &[F64]) -> Option<Vec<F64>> {
+    assert!(r.len() >= 2);
+    let n = r.len() - 1;
+    if r[0] <= F64::ZERO {
+        return None;
+    }
+    let r: Vec<F64> = r.iter().map(|v| *v / r[0]).collect();
+
+    let mut y = vec![-r[1]];
+    let mut beta = F64::ONE;
+    let mut alpha = -r[1];
+    for k in 1..n {
+        beta *= F64::ONE - alpha * alpha;
+        if beta <= F64::ZERO {
+            return None;
+        }
+        let mut sum = r[k + 1];
+        for j in 0..k {
+            sum += r[k - j] * y[j];
+        }
+        alpha = -sum / beta;
+        let z: Vec<F64> = (0..k).map(|j| y[j] + alpha * y[k - 1 - j]).collect();
+        y = z;
+        y.push(alpha);
+    }
+    Some(y)
+}
+
+impl Index<(usize, usize)> for ToeplitzMat<F64> {
+    type Output = F64;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        let (i, j) = index;
+        let n = self.first_col.len();
+        assert!(i < n && j < n);
+        if i >= j {
+            &self.first_col[i - j]
+        } else {
+            &self.first_row[j - i]
+        }
+    }
+}
+
+impl Matrix<F64> for ToeplitzMat<F64> {
+    fn shape(&self) -> (usize, usize) {
+        (self.first_col.len(), self.first_col.len())
+    }
+}
+
+impl Display for ToeplitzMat<F64> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(self, f)
+    }
+}
+
+impl From<ToeplitzMat<F64>> for FullMat<F64> {
+    fn from(mat: ToeplitzMat<F64>) -> Self {
+        let n = mat.first_col.len();
+        let mut full = FullMat::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                full[(i, j)] = mat[(i, j)];
+            }
+        }
+        full
+    }
+}
+
+impl LinearOperator for ToeplitzMat<F64> {
+    fn dim(&self) -> usize {
+        self.first_col.len()
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        self.mul_vec(x)
+    }
+}
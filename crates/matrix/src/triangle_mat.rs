@@ -3,7 +3,7 @@ use std::{fmt::Display, ops::IndexMut};
 
 use floating_point::F64;
 
-use crate::Matrix;
+use crate::{Matrix, MatrixError};
 
 #[derive(Debug, Clone, Copy)]
 pub enum TriangleMatType {
@@ -65,9 +65,46 @@ impl<T: Clone> TriFullMat<T> {
             storage: elements,
         }
     }
+
+    pub fn try_from_vec(ty: TriangleMatType, elements: Vec<T>) -> Result<Self, MatrixError> {
+        if elements.is_empty() {
+            return Err(MatrixError::EmptyInput);
+        }
+        let (mut left, mut n) = (elements.len(), 0);
+        while left > n {
+            left -= n + 1;
+            n += 1;
+        }
+        if left != 0 {
+            return Err(MatrixError::NonTriangularLength(elements.len()));
+        }
+        Ok(Self::from_vec(ty, elements))
+    }
 }
 
 impl TriFullMat<F64> {
+    pub fn transpose(&self) -> Self {
+        let mut v = vec![];
+        match self.ty {
+            TriangleMatType::Lower => {
+                for i in 0..self.n {
+                    for j in i..self.n {
+                        v.push(self[(j, i)]);
+                    }
+                }
+                Self::from_vec(TriangleMatType::Upper, v)
+            }
+            TriangleMatType::Upper => {
+                for i in 0..self.n {
+                    for j in 0..=i {
+                        v.push(self[(j, i)]);
+                    }
+                }
+                Self::from_vec(TriangleMatType::Lower, v)
+            }
+        }
+    }
+
     pub fn solve(&self, b: Vec<F64>) -> Vec<F64> {
         assert!(self.n == b.len());
 
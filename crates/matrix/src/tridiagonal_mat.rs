@@ -0,0 +1,268 @@
+use core::ops::Index;
+use std::fmt::Display;
+
+use floating_point::F64;
+
+use crate::{FullMat, LinearOperator, Matrix, RowAccess};
+
+// Square tridiagonal matrix stored as its three diagonals: `lower[i]` is the
+// entry (i + 1, i), `diagonal[i]` is (i, i) and `upper[i]` is (i, i + 1).
+#[derive(Debug, Clone)]
+pub struct TridiagonalMat<T> {
+    lower: Vec<T>,
+    diagonal: Vec<T>,
+    upper: Vec<T>,
+}
+
+impl<T: Clone> TridiagonalMat<T> {
+    pub fn from_diagonals(lower: Vec<T>, diagonal: Vec<T>, upper: Vec<T>) -> Self {
+        assert!(!diagonal.is_empty());
+        assert!(lower.len() + 1 == diagonal.len());
+        assert!(upper.len() + 1 == diagonal.len());
+        Self {
+            lower,
+            diagonal,
+            upper,
+        }
+    }
+
+    pub fn sub_diagonal(&self) -> &[T] {
+        &self.lower
+    }
+
+    pub fn main_diagonal(&self) -> &[T] {
+        &self.diagonal
+    }
+
+    pub fn super_diagonal(&self) -> &[T] {
+        &self.upper
+    }
+}
+
+impl TridiagonalMat<F64> {
+    // panics if A has nonzero entries outside the three diagonals
+    pub fn from_full(mat: &FullMat<F64>) -> Self {
+        assert!(mat.is_square());
+        let n = mat.row_count();
+        for i in 0..n {
+            for j in 0..n {
+                if i.abs_diff(j) > 1 {
+                    assert!(mat[(i, j)] == F64::ZERO);
+                }
+            }
+        }
+        Self::from_diagonals(
+            (1..n).map(|i| mat[(i, i - 1)]).collect(),
+            (0..n).map(|i| mat[(i, i)]).collect(),
+            (1..n).map(|i| mat[(i - 1, i)]).collect(),
+        )
+    }
+
+    pub fn mul_vec(&self, rhs: &[F64]) -> Vec<F64> {
+        let n = self.diagonal.len();
+        assert!(rhs.len() == n);
+        (0..n)
+            .map(|i| {
+                let mut sum = self.diagonal[i] * rhs[i];
+                if i > 0 {
+                    sum += self.lower[i - 1] * rhs[i - 1];
+                }
+                if i + 1 < n {
+                    sum += self.upper[i] * rhs[i + 1];
+                }
+                sum
+            })
+            .collect()
+    }
+
+    // Thomas algorithm: Gaussian elimination without pivoting in O(n). Stable
+    // for diagonally dominant or symmetric positive definite matrices, and
+    // returns None when a pivot vanishes.
+    pub fn solve(&self, b: &[F64]) -> Option<Vec<F64>> {
+        let n = self.diagonal.len();
+        assert!(b.len() == n);
+
+        let mut c = vec![F64::ZERO; n];
+        let mut x = b.to_vec();
+        let mut pivot = self.diagonal[0];
+        for i in 0..n {
+            if i > 0 {
+                pivot = self.diagonal[i] - self.lower[i - 1] * c[i - 1];
+            }
+            if pivot == F64::ZERO {
+                return None;
+            }
+            if i + 1 < n {
+                c[i] = self.upper[i] / pivot;
+            }
+            x[i] = if i > 0 {
+                (x[i] - self.lower[i - 1] * x[i - 1]) / pivot
+            } else {
+                x[i] / pivot
+            };
+        }
+        for i in (0..(n - 1)).rev() {
+            let next = x[i + 1];
+            x[i] -= c[i] * next;
+        }
+        Some(x)
+    }
+
+    // Gaussian elimination with partial pivoting, as in LAPACK's gtsv. A row
+    // interchange fills in a second superdiagonal. Returns None if the matrix
+    // is singular.
+    pub fn solve_pivoted(&self, b: &[F64]) -> Option<Vec<F64>> {
+        let n = self.diagonal.len();
+        assert!(b.len() == n);
+
+        let mut d = self.diagonal.clone();
+        let mut du = self.upper.clone();
+        let mut du2 = vec![F64::ZERO; n.saturating_sub(2)];
+        let mut x = b.to_vec();
+        for k in 0..(n - 1) {
+            let dl = self.lower[k];
+            if d[k].abs() >= dl.abs() {
+                if d[k] == F64::ZERO {
+                    return None;
+                }
+                let m = dl / d[k];
+                d[k + 1] -= m * du[k];
+                let xk = x[k];
+                x[k + 1] -= m * xk;
+            } else {
+                // swap rows k and k + 1
+                let m = d[k] / dl;
+                d[k] = dl;
+                let temp = d[k + 1];
+                d[k + 1] = du[k] - m * temp;
+                if k + 2 < n {
+                    du2[k] = du[k + 1];
+                    du[k + 1] = -m * du2[k];
+                }
+                du[k] = temp;
+                let (xk, xk1) = (x[k], x[k + 1]);
+                x[k] = xk1;
+                x[k + 1] = xk - m * xk1;
+            }
+        }
+        if d[n - 1] == F64::ZERO {
+            return None;
+        }
+
+        for k in (0..n).rev() {
+            let mut sum = x[k];
+            if k + 1 < n {
+                sum -= du[k] * x[k + 1];
+            }
+            if k + 2 < n {
+                sum -= du2[k] * x[k + 2];
+            }
+            x[k] = sum / d[k];
+        }
+        Some(x)
+    }
+
+    // Solves the periodic system in which row 0 also has `top_right` in the
+    // last column and row n - 1 has `bottom_left` in the first one, by the
+    // Sherman-Morrison formula on top of two tridiagonal solves.
+    pub fn solve_cyclic(&self, top_right: F64, bottom_left: F64, b: &[F64]) -> Option<Vec<F64>> {
+        let n = self.diagonal.len();
+        assert!(n >= 3);
+        assert!(b.len() == n);
+
+        // A = T + u v^T with u = (gamma, 0, ..., 0, bottom_left) and
+        // v = (1, 0, ..., 0, top_right / gamma)
+        let gamma = if self.diagonal[0] == F64::ZERO {
+            F64::ONE
+        } else {
+            -self.diagonal[0]
+        };
+        let mut t = self.clone();
+        t.diagonal[0] -= gamma;
+        t.diagonal[n - 1] -= bottom_left * top_right / gamma;
+
+        let x = t.solve_pivoted(b)?;
+        let mut u = vec![F64::ZERO; n];
+        u[0] = gamma;
+        u[n - 1] = bottom_left;
+        let z = t.solve_pivoted(&u)?;
+
+        let denominator = F64::ONE + z[0] + top_right * z[n - 1] / gamma;
+        if denominator == F64::ZERO {
+            return None;
+        }
+        let factor = (x[0] + top_right * x[n - 1] / gamma) / denominator;
+        Some(
+            x.iter()
+                .zip(z.iter())
+                .map(|(x, z)| *x - factor * *z)
+                .collect(),
+        )
+    }
+}
+
+impl Index<(usize, usize)> for TridiagonalMat<F64> {
+    type Output = F64;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        let (i, j) = index;
+        let n = self.diagonal.len();
+        assert!(i < n && j < n);
+        if i == j {
+            &self.diagonal[i]
+        } else if i == j + 1 {
+            &self.lower[j]
+        } else if j == i + 1 {
+            &self.upper[i]
+        } else {
+            &F64::ZERO
+        }
+    }
+}
+
+impl Matrix<F64> for TridiagonalMat<F64> {
+    fn shape(&self) -> (usize, usize) {
+        (self.diagonal.len(), self.diagonal.len())
+    }
+}
+
+impl Display for TridiagonalMat<F64> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Matrix::fmt(self, f)
+    }
+}
+
+impl From<TridiagonalMat<F64>> for FullMat<F64> {
+    fn from(mat: TridiagonalMat<F64>) -> Self {
+        let n = mat.diagonal.len();
+        let mut full = FullMat::zeros(n, n);
+        for i in 0..n {
+            for j in i.saturating_sub(1)..(i + 2).min(n) {
+                full[(i, j)] = mat[(i, j)];
+            }
+        }
+        full
+    }
+}
+
+impl LinearOperator for TridiagonalMat<F64> {
+    fn dim(&self) -> usize {
+        self.diagonal.len()
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        self.mul_vec(x)
+    }
+}
+
+impl RowAccess for TridiagonalMat<F64> {
+    fn for_each_in_row<F: FnMut(usize, F64)>(&self, i: usize, mut f: F) {
+        let n = self.diagonal.len();
+        for j in i.saturating_sub(1)..(i + 2).min(n) {
+            f(j, self[(i, j)]);
+        }
+    }
+
+    fn diagonal(&self) -> Vec<F64> {
+        self.diagonal.clone()
+    }
+}
@@ -0,0 +1,131 @@
+use floating_point::F64;
+
+use crate::{FullMat, LinearOperator};
+
+// Square Vandermonde matrix of the nodes x_0, ..., x_{n-1}: the entry (i, j)
+// is x_i^j, so V a = f finds the monomial coefficients a of the polynomial
+// interpolating f at the nodes. Entries are computed on demand, so there is
+// no `Index` implementation.
+#[derive(Debug, Clone)]
+pub struct VandermondeMat {
+    nodes: Vec<F64>,
+}
+
+impl VandermondeMat {
+    pub fn new(nodes: Vec<F64>) -> Self {
+        assert!(!nodes.is_empty());
+        Self { nodes }
+    }
+
+    pub fn nodes(&self) -> &[F64] {
+        &self.nodes
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.nodes.len(), self.nodes.len())
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> F64 {
+        let n = self.nodes.len();
+        assert!(i < n && j < n);
+        let mut power = F64::ONE;
+        for _ in 0..j {
+            power *= self.nodes[i];
+        }
+        power
+    }
+
+    // evaluates the polynomial with coefficients `rhs` at every node by
+    // Horner's rule
+    pub fn mul_vec(&self, rhs: &[F64]) -> Vec<F64> {
+        assert!(rhs.len() == self.nodes.len());
+        self.nodes
+            .iter()
+            .map(|x| rhs.iter().rev().fold(F64::ZERO, |sum, a| sum * *x + *a))
+            .collect()
+    }
+
+    // Bjorck-Pereyra algorithm for V a = f in O(n^2): the divided differences
+    // of f give the Newton form of the interpolant, which is then expanded
+    // into monomials. Often far more accurate than Gaussian elimination on
+    // V. Returns None if two nodes coincide.
+    pub fn solve(&self, f: &[F64]) -> Option<Vec<F64>> {
+        let x = &self.nodes;
+        let n = x.len();
+        assert!(f.len() == n);
+
+        let mut a = f.to_vec();
+        for k in 0..(n - 1) {
+            for i in ((k + 1)..n).rev() {
+                let h = x[i] - x[i - k - 1];
+                if h == F64::ZERO {
+                    return None;
+                }
+                a[i] = (a[i] - a[i - 1]) / h;
+            }
+        }
+        for k in (0..(n - 1)).rev() {
+            for i in k..(n - 1) {
+                let next = a[i + 1];
+                a[i] -= next * x[k];
+            }
+        }
+        Some(a)
+    }
+
+    // Bjorck-Pereyra algorithm for the dual system V^T z = b, whose solution
+    // gives the weights of interpolatory quadrature rules. Returns None if
+    // two nodes coincide.
+    pub fn solve_transpose(&self, b: &[F64]) -> Option<Vec<F64>> {
+        let x = &self.nodes;
+        let n = x.len();
+        assert!(b.len() == n);
+
+        let mut z = b.to_vec();
+        for (k, xk) in x.iter().enumerate().take(n - 1) {
+            for i in ((k + 1)..n).rev() {
+                let previous = z[i - 1];
+                z[i] -= *xk * previous;
+            }
+        }
+        for k in (0..(n - 1)).rev() {
+            for i in (k + 1)..n {
+                let h = x[i] - x[i - k - 1];
+                if h == F64::ZERO {
+                    return None;
+                }
+                z[i] /= h;
+            }
+            for i in k..(n - 1) {
+                let next = z[i + 1];
+                z[i] -= next;
+            }
+        }
+        Some(z)
+    }
+}
+
+impl From<VandermondeMat> for FullMat<F64> {
+    fn from(mat: VandermondeMat) -> Self {
+        let n = mat.nodes.len();
+        let mut full = FullMat::zeros(n, n);
+        for i in 0..n {
+            let mut power = F64::ONE;
+            for j in 0..n {
+                full[(i, j)] = power;
+                power *= mat.nodes[i];
+            }
+        }
+        full
+    }
+}
+
+impl LinearOperator for VandermondeMat {
+    fn dim(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        self.mul_vec(x)
+    }
+}
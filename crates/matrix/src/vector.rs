@@ -0,0 +1,34 @@
+use floating_point::F64;
+
+pub(crate) fn dot(a: &[F64], b: &[F64]) -> F64 {
+    assert!(a.len() == b.len());
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+pub(crate) fn norm2(a: &[F64]) -> F64 {
+    let scale = a.iter().fold(F64::ZERO, |max, x| max.max(x.abs()));
+    if scale == F64::ZERO {
+        return F64::ZERO;
+    }
+    a.iter()
+        .map(|x| {
+            let y = *x / scale;
+            y * y
+        })
+        .sum::<F64>()
+        .sqrt()
+        * scale
+}
+
+pub(crate) fn sub(a: &[F64], b: &[F64]) -> Vec<F64> {
+    assert!(a.len() == b.len());
+    a.iter().zip(b.iter()).map(|(x, y)| x - y).collect()
+}
+
+// y += alpha * x
+pub(crate) fn axpy(alpha: F64, x: &[F64], y: &mut [F64]) {
+    assert!(x.len() == y.len());
+    y.iter_mut()
+        .zip(x.iter())
+        .for_each(|(y, x)| *y += alpha * *x);
+}
@@ -0,0 +1,234 @@
+use floating_point::F64;
+use matrix::{BandMat, FullMat, Matrix, TridiagonalMat};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn vec_max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x - *y).abs())
+        .fold(0.0.into(), |max, x| if max < x { x } else { max })
+}
+
+fn random_vec(rng: &mut StdRng, n: usize) -> Vec<F64> {
+    (0..n).map(|_| rng.gen_range(-1.0..1.0).into()).collect()
+}
+
+// natural cubic spline system for the second derivatives at the interior
+// knots of a nonuniform grid
+fn spline_system(knots: &[f64]) -> TridiagonalMat<F64> {
+    let h: Vec<f64> = knots.windows(2).map(|w| w[1] - w[0]).collect();
+    let n = h.len() - 1;
+    TridiagonalMat::from_diagonals(
+        (1..n).map(|i| F64::from(h[i])).collect(),
+        (0..n).map(|i| F64::from(2.0 * (h[i] + h[i + 1]))).collect(),
+        (1..n).map(|i| F64::from(h[i])).collect(),
+    )
+}
+
+#[test]
+fn test_tridiagonal_conversions() {
+    let tri = TridiagonalMat::from_diagonals(
+        F64::map_vec(vec![1., 2.]),
+        F64::map_vec(vec![3., 4., 5.]),
+        F64::map_vec(vec![6., 7.]),
+    );
+    println!("tridiagonal = \n{}", tri);
+    assert!(tri.shape() == (3, 3));
+    assert!(tri[(1, 0)] == 1.0.into() && tri[(0, 1)] == 6.0.into());
+    assert!(tri[(2, 0)] == 0.0.into() && tri[(0, 2)] == 0.0.into());
+
+    let full = FullMat::from(tri.clone());
+    let expected = FullMat::from_rows(vec![
+        F64::map_vec(vec![3., 6., 0.]),
+        F64::map_vec(vec![1., 4., 7.]),
+        F64::map_vec(vec![0., 2., 5.]),
+    ]);
+    assert!(full.sub(&expected).element_max_abs() == F64::ZERO);
+    let back = TridiagonalMat::from_full(&full);
+    assert!(back.sub_diagonal() == tri.sub_diagonal());
+    assert!(back.main_diagonal() == tri.main_diagonal());
+    assert!(back.super_diagonal() == tri.super_diagonal());
+
+    let x = F64::map_vec(vec![1., -1., 2.]);
+    assert!(tri.mul_vec(&x) == full.mul_vec(&x));
+
+    let band = BandMat::from(tri);
+    assert!(band.kl() == 1 && band.ku() == 1);
+    assert!(FullMat::from(band).sub(&expected).element_max_abs() == F64::ZERO);
+}
+
+#[test]
+#[should_panic]
+fn test_tridiagonal_from_full_outside_band() {
+    let full = FullMat::from_rows(vec![
+        F64::map_vec(vec![1., 0., 1.]),
+        F64::map_vec(vec![0., 1., 0.]),
+        F64::map_vec(vec![0., 0., 1.]),
+    ]);
+    TridiagonalMat::from_full(&full);
+}
+
+#[test]
+fn test_thomas() {
+    let knots: Vec<f64> = (0..=200).map(|i| (i as f64 * 0.01).powi(2)).collect();
+    let tri = spline_system(&knots);
+    let n = tri.row_count();
+    let mut rng = StdRng::seed_from_u64(37);
+    let expected = random_vec(&mut rng, n);
+    let b = tri.mul_vec(&expected);
+
+    let x = tri.solve(&b).unwrap();
+    assert!(vec_max_diff(&x, &expected) < 1e-10.into());
+    let x = tri.solve_pivoted(&b).unwrap();
+    assert!(vec_max_diff(&x, &expected) < 1e-10.into());
+
+    let one = TridiagonalMat::from_diagonals(vec![], F64::map_vec(vec![4.]), vec![]);
+    assert!(one.solve(&F64::map_vec(vec![2.])).unwrap() == F64::map_vec(vec![0.5]));
+    assert!(one.solve_pivoted(&F64::map_vec(vec![2.])).unwrap() == F64::map_vec(vec![0.5]));
+}
+
+#[test]
+fn test_pivoted_tridiagonal() {
+    // the leading entry is zero, so Thomas breaks down without pivoting
+    let tri = TridiagonalMat::from_diagonals(
+        F64::map_vec(vec![1., 1., 1., 1.]),
+        F64::map_vec(vec![0., 1e-3, 2., 0., 1.]),
+        F64::map_vec(vec![1., 3., 1., 1.]),
+    );
+    let expected = F64::map_vec(vec![1., 2., 3., 4., 5.]);
+    let b = tri.mul_vec(&expected);
+    assert!(tri.solve(&b).is_none());
+    let x = tri.solve_pivoted(&b).unwrap();
+    assert!(vec_max_diff(&x, &expected) < 1e-12.into());
+    let dense = FullMat::from(tri.clone()).lu_solve(&b).unwrap();
+    assert!(vec_max_diff(&x, &dense) < 1e-12.into());
+
+    let singular = TridiagonalMat::from_diagonals(
+        F64::map_vec(vec![1., 0.]),
+        F64::map_vec(vec![1., 1., 1.]),
+        F64::map_vec(vec![1., 0.]),
+    );
+    assert!(singular
+        .solve_pivoted(&F64::map_vec(vec![1., 1., 1.]))
+        .is_none());
+}
+
+#[test]
+fn test_cyclic_tridiagonal() {
+    // periodic second difference plus a shift, as in periodic splines
+    let n = 50;
+    let tri = TridiagonalMat::from_diagonals(
+        vec![F64::from(-1.0); n - 1],
+        vec![F64::from(2.5); n],
+        vec![F64::from(-1.0); n - 1],
+    );
+    let (top_right, bottom_left) = (F64::from(-1.0), F64::from(-0.5));
+    let mut dense = FullMat::from(tri.clone());
+    dense[(0, n - 1)] = top_right;
+    dense[(n - 1, 0)] = bottom_left;
+
+    let mut rng = StdRng::seed_from_u64(370);
+    let expected = random_vec(&mut rng, n);
+    let b = dense.mul_vec(&expected);
+    let x = tri.solve_cyclic(top_right, bottom_left, &b).unwrap();
+    assert!(vec_max_diff(&x, &expected) < 1e-12.into());
+
+    // a zero leading diagonal entry
+    let tri = TridiagonalMat::from_diagonals(
+        F64::map_vec(vec![1., 2.]),
+        F64::map_vec(vec![0., 3., 1.]),
+        F64::map_vec(vec![1., -1.]),
+    );
+    let mut dense = FullMat::from(tri.clone());
+    dense[(0, 2)] = F64::from(2.0);
+    dense[(2, 0)] = F64::from(4.0);
+    let b = F64::map_vec(vec![1., 2., 3.]);
+    let x = tri
+        .solve_cyclic(F64::from(2.0), F64::from(4.0), &b)
+        .unwrap();
+    assert!(vec_max_diff(&x, &dense.lu_solve(&b).unwrap()) < 1e-12.into());
+}
+
+#[test]
+fn test_band_mat() {
+    let mut band = BandMat::zeros(4, 5, 1, 2);
+    for i in 0..4_usize {
+        for j in i.saturating_sub(1)..(i + 3).min(5) {
+            band[(i, j)] = F64::from((10 * i + j) as f64);
+        }
+    }
+    println!("band = \n{}", band);
+    assert!(band.shape() == (4, 5));
+    assert!(band[(3, 0)] == 0.0.into() && band[(0, 3)] == 0.0.into());
+    assert!(band[(2, 4)] == 24.0.into());
+
+    let full = FullMat::from(band.clone());
+    let x = F64::map_vec(vec![1., 2., 3., 4., 5.]);
+    assert!(band.mul_vec(&x) == full.mul_vec(&x));
+    let back = BandMat::from_full(&full, 1, 2);
+    assert!(FullMat::from(back).sub(&full).element_max_abs() == F64::ZERO);
+}
+
+#[test]
+#[should_panic]
+fn test_band_write_outside_band() {
+    let mut band = BandMat::zeros(3, 3, 0, 1);
+    band[(1, 0)] = F64::ONE;
+}
+
+#[test]
+fn test_band_lu() {
+    let mut rng = StdRng::seed_from_u64(3700);
+    for (n, kl, ku) in [(1, 0, 0), (6, 0, 2), (10, 2, 1), (30, 3, 4), (40, 5, 0)] {
+        let mut band = BandMat::zeros(n, n, kl, ku);
+        for i in 0..n {
+            for j in i.saturating_sub(kl)..(i + ku + 1).min(n) {
+                // a small diagonal forces row interchanges
+                band[(i, j)] = if i == j {
+                    rng.gen_range(-0.1..0.1).into()
+                } else {
+                    rng.gen_range(-1.0..1.0).into()
+                };
+            }
+        }
+        let full = FullMat::from(band.clone());
+        let b = random_vec(&mut rng, n);
+
+        let lu = band.lu().unwrap();
+        let x = lu.solve(&b);
+        // without subdiagonals there is nothing to pivot, and x can be large
+        let x_max = x.iter().fold(F64::ONE, |max, v| max.max(v.abs()));
+        assert!(vec_max_diff(&x, &full.lu_solve(&b).unwrap()) < F64::from(1e-9) * x_max);
+        assert!(vec_max_diff(&full.mul_vec(&x), &b) < F64::from(1e-13) * x_max);
+        if kl > 0 {
+            assert!(lu.pivots().iter().enumerate().any(|(k, p)| k != *p));
+        }
+
+        let (l, u, p) = full.lu().unwrap();
+        let mut dense_det = F64::ONE;
+        for k in 0..n {
+            dense_det *= u[(k, k)] * l[(k, k)];
+        }
+        // sign of the row permutation
+        let mut p = p;
+        for k in 0..n {
+            while p[k] != k {
+                let target = p[k];
+                p.swap(k, target);
+                dense_det = -dense_det;
+            }
+        }
+        assert!((lu.det() - dense_det).abs() <= F64::from(1e-12) * dense_det.abs());
+    }
+
+    let singular = BandMat::from_full(
+        &FullMat::from_rows(vec![
+            F64::map_vec(vec![1., 2., 0.]),
+            F64::map_vec(vec![2., 4., 0.]),
+            F64::map_vec(vec![0., 1., 1.]),
+        ]),
+        1,
+        1,
+    );
+    assert!(singular.lu_solve(&F64::map_vec(vec![1., 1., 1.])).is_none());
+}
@@ -0,0 +1,154 @@
+#![cfg_attr(feature = "nightly_bench", feature(test))]
+
+use floating_point::F64;
+use matrix::{FullMat, Matrix, MatrixError, TriFullMat};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+type LuFactors = (TriFullMat<F64>, TriFullMat<F64>, Vec<usize>);
+
+fn random_mat(rng: &mut StdRng, n: usize) -> FullMat<F64> {
+    FullMat::from_vec(
+        n,
+        (0..(n * n))
+            .map(|_| rng.gen_range(-1.0..1.0).into())
+            .collect(),
+    )
+}
+
+// max |P A - L U| relative to n eps ||A||
+fn relative_residual(a: &FullMat<F64>, (l, u, p): &LuFactors) -> F64 {
+    let n = a.row_count();
+    let pa = FullMat::from_rows(p.iter().map(|i| row(a, *i)).collect());
+    let lu = FullMat::from(l.clone()).mul_mat(&FullMat::from(u.clone()));
+    lu.sub(&pa).element_max_abs() / (F64::from(n as f64) * F64::EPSILON * a.element_max_abs())
+}
+
+fn row(a: &FullMat<F64>, i: usize) -> Vec<F64> {
+    (0..a.col_count()).map(|j| a[(i, j)]).collect()
+}
+
+#[test]
+fn test_variants_agree() {
+    let mut rng = StdRng::seed_from_u64(45);
+    for n in [1, 2, 5, 17, 63, 64, 65, 130, 200] {
+        let a = random_mat(&mut rng, n);
+        let reference = a.lu_unblocked().unwrap();
+        let variants = [
+            ("lu", a.lu().unwrap()),
+            ("blocked 1", a.lu_blocked(1).unwrap()),
+            ("blocked 7", a.lu_blocked(7).unwrap()),
+            ("blocked 32", a.lu_blocked(32).unwrap()),
+            ("recursive", a.lu_recursive().unwrap()),
+        ];
+        println!(
+            "n = {}: unblocked residual {}",
+            n,
+            relative_residual(&a, &reference)
+        );
+        for (name, factors) in variants {
+            let residual = relative_residual(&a, &factors);
+            println!("n = {}: {} residual {}", n, name, residual);
+            assert!(residual < F64::from(10.0));
+            // same pivots as the element by element elimination; ties in
+            // magnitude would need an exact rounding coincidence
+            assert!(factors.2 == reference.2);
+        }
+    }
+
+    // a single panel does exactly what the unblocked code does
+    let a = random_mat(&mut rng, 40);
+    let (l, u, p) = a.lu().unwrap();
+    let (l_ref, u_ref, p_ref) = a.lu_unblocked().unwrap();
+    assert!(p == p_ref);
+    assert!(
+        FullMat::from(l)
+            .sub(&FullMat::from(l_ref))
+            .element_max_abs()
+            == F64::ZERO
+    );
+    assert!(
+        FullMat::from(u)
+            .sub(&FullMat::from(u_ref))
+            .element_max_abs()
+            == F64::ZERO
+    );
+}
+
+#[test]
+fn test_lu_solve_large() {
+    let mut rng = StdRng::seed_from_u64(450);
+    let n = 300;
+    let a = random_mat(&mut rng, n);
+    let x: Vec<F64> = (0..n).map(|_| rng.gen_range(-1.0..1.0).into()).collect();
+    let b = a.mul_vec(&x);
+    let x_a = a.lu_solve(&b).unwrap();
+    let residual = b
+        .iter()
+        .zip(a.mul_vec(&x_a))
+        .fold(F64::ZERO, |max, (b, ax)| max.max((*b - ax).abs()));
+    println!("n = {}: residual {:e}", n, residual);
+    assert!(residual < F64::from((n * n) as f64) * F64::EPSILON);
+}
+
+#[test]
+fn test_singular() {
+    let mut rng = StdRng::seed_from_u64(4500);
+    let n = 150;
+    let mut a = random_mat(&mut rng, n);
+    // a zero column stays zero during elimination, whatever the order of the
+    // updates
+    for i in 0..n {
+        a[(i, 100)] = F64::ZERO;
+    }
+    assert!(a.try_lu().unwrap_err() == MatrixError::Singular(100));
+    assert!(a.lu().is_none() && a.lu_unblocked().is_none());
+    assert!(a.lu_blocked(16).is_none() && a.lu_recursive().is_none());
+    assert!(a.try_inv().unwrap_err() == MatrixError::Singular(100));
+}
+
+#[cfg(feature = "nightly_bench")]
+mod benchs {
+    extern crate test;
+
+    use super::*;
+
+    fn prepare_data(n: usize) -> FullMat<F64> {
+        random_mat(&mut StdRng::seed_from_u64(0), n)
+    }
+
+    #[bench]
+    fn bench_lu_unblocked_128(b: &mut test::Bencher) {
+        let a = prepare_data(128);
+        b.iter(|| a.lu_unblocked());
+    }
+
+    #[bench]
+    fn bench_lu_blocked_128(b: &mut test::Bencher) {
+        let a = prepare_data(128);
+        b.iter(|| a.lu());
+    }
+
+    #[bench]
+    fn bench_lu_recursive_128(b: &mut test::Bencher) {
+        let a = prepare_data(128);
+        b.iter(|| a.lu_recursive());
+    }
+
+    #[bench]
+    fn bench_lu_unblocked_512(b: &mut test::Bencher) {
+        let a = prepare_data(512);
+        b.iter(|| a.lu_unblocked());
+    }
+
+    #[bench]
+    fn bench_lu_blocked_512(b: &mut test::Bencher) {
+        let a = prepare_data(512);
+        b.iter(|| a.lu());
+    }
+
+    #[bench]
+    fn bench_lu_recursive_512(b: &mut test::Bencher) {
+        let a = prepare_data(512);
+        b.iter(|| a.lu_recursive());
+    }
+}
@@ -0,0 +1,154 @@
+use floating_point::F64;
+use matrix::{
+    dominant_eigenpairs, inverse_iteration, power_iteration, rayleigh_quotient_iteration,
+    EigenIterationOptions, FullMat, LinearOperator,
+};
+
+// eigenvalues 4 and -1 with eigenvectors (1, 1) and (3, -2)
+fn example_mat() -> FullMat<F64> {
+    FullMat::from_vec(2, F64::map_vec(vec![1., 3., 2., 2.]))
+}
+
+fn second_difference_mat(n: usize) -> FullMat<F64> {
+    let mut mat = FullMat::zeros(n, n);
+    for i in 0..n {
+        mat[(i, i)] = 2.0.into();
+        if i > 0 {
+            mat[(i, i - 1)] = (-1.0).into();
+            mat[(i - 1, i)] = (-1.0).into();
+        }
+    }
+    mat
+}
+
+fn second_difference_eigenvalue(n: usize, k: usize) -> F64 {
+    F64::from(2.0 - 2.0 * (k as f64 * core::f64::consts::PI / (n + 1) as f64).cos())
+}
+
+// a matrix-free version of the second difference matrix
+struct SecondDifference(usize);
+
+impl LinearOperator for SecondDifference {
+    fn dim(&self) -> usize {
+        self.0
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        let n = self.0;
+        (0..n)
+            .map(|i| {
+                let mut y = F64::from(2.0) * x[i];
+                if i > 0 {
+                    y -= x[i - 1];
+                }
+                if i + 1 < n {
+                    y -= x[i + 1];
+                }
+                y
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_power_iteration() {
+    let mat = example_mat();
+    let result = power_iteration(&mat, &F64::map_vec(vec![-5., 5.]), Default::default());
+    println!("{:?}", result);
+    assert!(result.converged);
+    assert!((result.eigenvalue - F64::from(4.0)).abs() < 1e-10.into());
+    let ratio = result.eigenvector[0] / result.eigenvector[1];
+    assert!((ratio - F64::ONE).abs() < 1e-10.into());
+    assert!(result.iterations == result.residual_history.len());
+    assert!(result.iterations == result.eigenvalue_history.len());
+    // the error shrinks by about |lambda_2 / lambda_1| = 1/4 per step
+    let h = &result.residual_history;
+    let rate = h[h.len() - 1] / h[h.len() - 2];
+    assert!((rate - F64::from(0.25)).abs() < 1e-3.into());
+}
+
+#[test]
+fn test_power_iteration_not_converged() {
+    let options = EigenIterationOptions {
+        tolerance: 1e-14.into(),
+        max_iterations: 3,
+    };
+    let result = power_iteration(&example_mat(), &F64::map_vec(vec![1., 0.]), options);
+    assert!(!result.converged);
+    assert!(result.iterations == 3);
+}
+
+#[test]
+fn test_matrix_free_power_iteration() {
+    let n = 10;
+    let x0: Vec<F64> = (0..n).map(|i| F64::from(1.0 + i as f64)).collect();
+    let options = EigenIterationOptions {
+        tolerance: 1e-8.into(),
+        max_iterations: 5000,
+    };
+    let result = power_iteration(&SecondDifference(n), &x0, options);
+    assert!(result.converged);
+    assert!((result.eigenvalue - second_difference_eigenvalue(n, n)).abs() < 1e-10.into());
+}
+
+#[test]
+fn test_inverse_iteration() {
+    let mat = example_mat();
+    let x0 = F64::map_vec(vec![-5., 5.]);
+    let result = inverse_iteration(&mat, F64::ZERO, &x0, Default::default()).unwrap();
+    assert!(result.converged);
+    assert!((result.eigenvalue + F64::ONE).abs() < 1e-10.into());
+
+    let result = inverse_iteration(&mat, 3.0.into(), &x0, Default::default()).unwrap();
+    assert!(result.converged);
+    assert!((result.eigenvalue - F64::from(4.0)).abs() < 1e-10.into());
+
+    // the shift is exactly an eigenvalue
+    assert!(inverse_iteration(&mat, 4.0.into(), &x0, Default::default()).is_none());
+}
+
+#[test]
+fn test_rayleigh_quotient_iteration() {
+    let n = 12;
+    let mat = second_difference_mat(n);
+    let x0: Vec<F64> = (0..n).map(|i| F64::from((i % 3) as f64 + 1.0)).collect();
+    let options = EigenIterationOptions {
+        tolerance: 1e-12.into(),
+        max_iterations: 20,
+    };
+    let result = rayleigh_quotient_iteration(&mat, &x0, options);
+    println!("residual history = {:?}", result.residual_history);
+    assert!(result.converged);
+    assert!(result.iterations < 8);
+    assert!((1..=n)
+        .any(|k| (result.eigenvalue - second_difference_eigenvalue(n, k)).abs() < 1e-12.into()));
+
+    // far fewer iterations than power iteration needs
+    let power = power_iteration(&mat, &x0, options);
+    assert!(power.iterations > result.iterations);
+}
+
+#[test]
+fn test_dominant_eigenpairs() {
+    let n = 6;
+    let x0: Vec<F64> = (0..n).map(|i| F64::from(1.0 + (i * i) as f64)).collect();
+    let options = EigenIterationOptions {
+        tolerance: 1e-9.into(),
+        max_iterations: 10000,
+    };
+    let results = dominant_eigenpairs(&SecondDifference(n), 3, &x0, options);
+    assert!(results.len() == 3);
+    for (k, result) in results.iter().enumerate() {
+        assert!(result.converged);
+        assert!((result.eigenvalue - second_difference_eigenvalue(n, n - k)).abs() < 1e-8.into());
+    }
+
+    // eigenvectors of a symmetric matrix are orthogonal
+    let d: F64 = results[0]
+        .eigenvector
+        .iter()
+        .zip(results[1].eigenvector.iter())
+        .map(|(x, y)| x * y)
+        .sum();
+    assert!(d.abs() < 1e-8.into());
+}
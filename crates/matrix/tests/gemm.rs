@@ -0,0 +1,153 @@
+#![cfg_attr(feature = "nightly_bench", feature(test))]
+
+use floating_point::F64;
+use matrix::{FullMat, Matrix};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn random_mat(rng: &mut StdRng, m: usize, n: usize) -> FullMat<F64> {
+    FullMat::from_vec(
+        n,
+        (0..(m * n))
+            .map(|_| rng.gen_range(-1.0..1.0).into())
+            .collect(),
+    )
+}
+
+// entrywise |C - C_ref| <= k eps (|A| |B|) with |A| |B| <= k for entries in
+// [-1, 1]
+fn close(c: &FullMat<F64>, reference: &FullMat<F64>, k: usize) -> bool {
+    c.shape() == reference.shape()
+        && c.sub(reference).element_max_abs() <= F64::from((k * k) as f64) * F64::EPSILON
+}
+
+#[test]
+fn test_mul_mat_matches_naive() {
+    let mut rng = StdRng::seed_from_u64(44);
+    // edge cases of the MR x NR register blocks and the KC and MC cache blocks,
+    // on both sides of the small-size cutoff
+    for (m, k, n) in [
+        (1, 1, 1),
+        (3, 5, 7),
+        (33, 33, 33),
+        (4, 300, 8),
+        (5, 257, 9),
+        (129, 64, 17),
+        (130, 513, 3),
+        (1, 2000, 1),
+        (200, 1, 150),
+        (200, 2, 150),
+    ] {
+        let a = random_mat(&mut rng, m, k);
+        let b = random_mat(&mut rng, k, n);
+        let product = a.mul_mat(&b);
+        println!("{} x {} x {}", m, k, n);
+        assert!(close(&product, &a.mul_mat_naive(&b), k));
+    }
+
+    // exact for integers, and the k <= KC case sums in the same order anyway
+    let a = FullMat::from_vec(40, (0..1600).map(|i| F64::from((i % 7) as f64)).collect());
+    let b = a.transpose();
+    assert!(a.mul_mat(&b).sub(&a.mul_mat_naive(&b)).element_max_abs() == F64::ZERO);
+}
+
+#[test]
+fn test_gemm_alpha_beta() {
+    let mut rng = StdRng::seed_from_u64(440);
+    for (m, k, n) in [(6, 4, 5), (70, 90, 60)] {
+        let a = random_mat(&mut rng, m, k);
+        let b = random_mat(&mut rng, k, n);
+        let c0 = random_mat(&mut rng, m, n);
+        let ab = a.mul_mat_naive(&b);
+
+        let mut c = c0.clone();
+        c.gemm(F64::from(2.0), &a, &b, F64::from(-0.5));
+        let mut expected = c0.clone();
+        for i in 0..m {
+            for j in 0..n {
+                expected[(i, j)] = F64::from(2.0) * ab[(i, j)] - F64::from(0.5) * c0[(i, j)];
+            }
+        }
+        assert!(close(&c, &expected, 2 * k));
+
+        // C += A B
+        let mut c = c0.clone();
+        c.gemm(F64::ONE, &a, &b, F64::ONE);
+        assert!(close(&c, &c0.add(&ab), k));
+
+        // C is overwritten, NaNs included, when beta is zero
+        let mut c = FullMat::from_vec(n, vec![F64::from(f64::NAN); m * n]);
+        c.gemm(F64::ONE, &a, &b, F64::ZERO);
+        assert!(close(&c, &ab, k));
+
+        // beta only scales C when alpha is zero
+        let mut c = c0.clone();
+        c.gemm(F64::ZERO, &a, &b, F64::from(3.0));
+        assert!((0..m).all(|i| (0..n).all(|j| c[(i, j)] == F64::from(3.0) * c0[(i, j)])));
+    }
+}
+
+#[test]
+fn test_mul_mat_large() {
+    let mut rng = StdRng::seed_from_u64(4400);
+    let n = 300;
+    let a = random_mat(&mut rng, n, n);
+    let q = FullMat::identity(n);
+    // A I = I A = A bit for bit, the zeros add nothing
+    assert!(a.mul_mat(&q).sub(&a).element_max_abs() == F64::ZERO);
+    assert!(q.mul_mat(&a).sub(&a).element_max_abs() == F64::ZERO);
+
+    let b = random_mat(&mut rng, n, n);
+    let x: Vec<F64> = (0..n).map(|_| rng.gen_range(-1.0..1.0).into()).collect();
+    // (A B) x = A (B x)
+    let left = a.mul_mat(&b).mul_vec(&x);
+    let right = a.mul_vec(&b.mul_vec(&x));
+    let error = left
+        .iter()
+        .zip(right)
+        .fold(F64::ZERO, |max, (l, r)| max.max((*l - r).abs()));
+    assert!(error <= F64::from((2 * n * n) as f64) * F64::EPSILON);
+    assert!(a.mul_mat(&b).shape() == (n, n));
+}
+
+#[cfg(feature = "nightly_bench")]
+mod benchs {
+    extern crate test;
+
+    use super::*;
+
+    fn prepare_data(n: usize) -> (FullMat<F64>, FullMat<F64>) {
+        let mut rng = StdRng::seed_from_u64(0);
+        (random_mat(&mut rng, n, n), random_mat(&mut rng, n, n))
+    }
+
+    #[bench]
+    fn bench_mul_mat_naive_64(b: &mut test::Bencher) {
+        let (x, y) = prepare_data(64);
+        b.iter(|| x.mul_mat_naive(&y));
+    }
+
+    #[bench]
+    fn bench_mul_mat_64(b: &mut test::Bencher) {
+        let (x, y) = prepare_data(64);
+        b.iter(|| x.mul_mat(&y));
+    }
+
+    #[bench]
+    fn bench_mul_mat_naive_256(b: &mut test::Bencher) {
+        let (x, y) = prepare_data(256);
+        b.iter(|| x.mul_mat_naive(&y));
+    }
+
+    #[bench]
+    fn bench_mul_mat_256(b: &mut test::Bencher) {
+        let (x, y) = prepare_data(256);
+        b.iter(|| x.mul_mat(&y));
+    }
+
+    // no naive counterpart here, it needs seconds per product at this size
+    #[bench]
+    fn bench_mul_mat_1000(b: &mut test::Bencher) {
+        let (x, y) = prepare_data(1000);
+        b.iter(|| x.mul_mat(&y));
+    }
+}
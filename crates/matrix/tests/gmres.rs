@@ -0,0 +1,201 @@
+use floating_point::F64;
+use matrix::{
+    arnoldi, gmres, ConvergenceReason, FullMat, GmresOptions, IdentityPreconditioner,
+    LinearOperator, Matrix, PreconditionSide, Preconditioner, StoppingCriterion,
+};
+
+fn vec_max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x - *y).abs())
+        .fold(0.0.into(), |max, x| if max < x { x } else { max })
+}
+
+// upwind discretization of -eps u'' + u' on a uniform grid
+fn convection_diffusion(n: usize, eps: f64) -> FullMat<F64> {
+    let h = 1.0 / (n + 1) as f64;
+    let mut mat = FullMat::zeros(n, n);
+    for i in 0..n {
+        mat[(i, i)] = F64::from(2.0 * eps / (h * h) + 1.0 / h);
+        if i > 0 {
+            mat[(i, i - 1)] = F64::from(-eps / (h * h) - 1.0 / h);
+        }
+        if i + 1 < n {
+            mat[(i, i + 1)] = F64::from(-eps / (h * h));
+        }
+    }
+    mat
+}
+
+struct Diagonal(Vec<F64>);
+
+impl Preconditioner for Diagonal {
+    fn solve(&self, r: &[F64]) -> Vec<F64> {
+        r.iter().zip(self.0.iter()).map(|(r, d)| *r / *d).collect()
+    }
+}
+
+#[test]
+fn test_arnoldi_factorization() {
+    let n = 12;
+    let mat = convection_diffusion(n, 0.1);
+    let v0: Vec<F64> = (0..n).map(|i| F64::from(1.0 + i as f64)).collect();
+    let k = 6;
+    let fact = arnoldi(&mat, &v0, k);
+    assert!(fact.steps() == k);
+    assert!(fact.basis.len() == k + 1);
+    assert!(fact.hessenberg.shape() == (k + 1, k));
+
+    for (i, u) in fact.basis.iter().enumerate() {
+        for (j, v) in fact.basis.iter().enumerate() {
+            let d: F64 = u.iter().zip(v.iter()).map(|(x, y)| x * y).sum();
+            let expected = if i == j { F64::ONE } else { F64::ZERO };
+            assert!((d - expected).abs() < 1e-12.into());
+        }
+    }
+
+    // A V_k = V_{k+1} H_k
+    for j in 0..k {
+        let av = mat.apply(&fact.basis[j]);
+        for (r, x) in av.iter().enumerate() {
+            let vh: F64 = (0..=k)
+                .map(|i| fact.basis[i][r] * fact.hessenberg[(i, j)])
+                .sum();
+            assert!((*x - vh).abs() < 1e-10.into());
+        }
+    }
+}
+
+#[test]
+fn test_arnoldi_invariant_subspace() {
+    let mat = FullMat::from_vec(3, F64::map_vec(vec![2., 0., 0., 0., 3., 0., 0., 0., 4.]));
+    let fact = arnoldi(&mat, &F64::map_vec(vec![1., 1., 0.]), 3);
+    assert!(fact.steps() == 2);
+    let mut ritz = fact.ritz_values().unwrap();
+    ritz.sort_by(|a, b| a.real.partial_cmp(&b.real).unwrap());
+    assert!((ritz[0].real - F64::from(2.0)).abs() < 1e-14.into());
+    assert!((ritz[1].real - F64::from(3.0)).abs() < 1e-14.into());
+}
+
+#[test]
+fn test_full_gmres_terminates() {
+    let n = 20;
+    let mat = convection_diffusion(n, 0.05);
+    let expected: Vec<F64> = (0..n).map(|i| F64::from((i as f64).sin())).collect();
+    let b = mat.mul_vec(&expected);
+    let options = GmresOptions {
+        restart: n,
+        ..Default::default()
+    };
+    let result = gmres(
+        &mat,
+        &b,
+        &vec![F64::ZERO; n],
+        &IdentityPreconditioner,
+        &options,
+        &Default::default(),
+    );
+    assert!(result.converged());
+    assert!(result.iterations <= n);
+    assert!(vec_max_diff(&result.x, &expected) < 1e-8.into());
+    // GMRES residuals are monotone without restarts
+    for w in result.residual_history.windows(2) {
+        assert!(w[1] <= w[0]);
+    }
+}
+
+#[test]
+fn test_restarted_and_preconditioned_gmres() {
+    let n = 60;
+    // badly scaled rows, which the diagonal preconditioner undoes
+    let mut mat = convection_diffusion(n, 0.01);
+    for i in 0..n {
+        let scale = F64::from(1.0 + i as f64);
+        for j in 0..n {
+            mat[(i, j)] *= scale;
+        }
+    }
+    let expected: Vec<F64> = (0..n).map(|i| F64::from((0.1 * i as f64).cos())).collect();
+    let b = mat.mul_vec(&expected);
+    let x0 = vec![F64::ZERO; n];
+    let criterion = StoppingCriterion {
+        max_iterations: 5000,
+        ..Default::default()
+    };
+    let diag = Diagonal((0..n).map(|i| mat[(i, i)]).collect());
+
+    for side in [PreconditionSide::Left, PreconditionSide::Right] {
+        let options = GmresOptions { restart: 20, side };
+        let plain = gmres(&mat, &b, &x0, &IdentityPreconditioner, &options, &criterion);
+        let preconditioned = gmres(&mat, &b, &x0, &diag, &options, &criterion);
+        println!(
+            "{:?}: plain {}, preconditioned {}",
+            side, plain.iterations, preconditioned.iterations
+        );
+        // restarted GMRES stagnates on the badly scaled system
+        assert!(!plain.converged());
+        assert!(preconditioned.converged());
+        assert!(preconditioned.iterations > options.restart);
+        assert!(vec_max_diff(&preconditioned.x, &expected) < 1e-7.into());
+    }
+}
+
+#[test]
+fn test_gmres_max_iterations() {
+    let n = 60;
+    let mat = convection_diffusion(n, 0.01);
+    let b = vec![F64::ONE; n];
+    let criterion = StoppingCriterion {
+        max_iterations: 7,
+        ..Default::default()
+    };
+    let options = GmresOptions {
+        restart: 5,
+        ..Default::default()
+    };
+    let result = gmres(
+        &mat,
+        &b,
+        &vec![F64::ZERO; n],
+        &IdentityPreconditioner,
+        &options,
+        &criterion,
+    );
+    assert!(result.reason == ConvergenceReason::MaxIterationsReached);
+    assert!(result.iterations == 7);
+}
+
+#[test]
+fn test_gmres_singular_matrix() {
+    let options = GmresOptions::default();
+    let criterion = StoppingCriterion::default();
+    let x0 = vec![F64::ZERO; 2];
+
+    // A b = 0, so the Krylov space is invariant at once with a singular H
+    let nilpotent = FullMat::from_vec(2, F64::map_vec(vec![0.0, 1.0, 0.0, 0.0]));
+    let b = F64::map_vec(vec![1.0, 0.0]);
+    let result = gmres(
+        &nilpotent,
+        &b,
+        &x0,
+        &IdentityPreconditioner,
+        &options,
+        &criterion,
+    );
+    println!("x = {:?}, reason = {:?}", result.x, result.reason);
+    assert!(result.reason == ConvergenceReason::Breakdown);
+    assert!(result.x.iter().all(|x| x.is_finite()));
+
+    // a consistent singular system is still solved
+    let projection = FullMat::from_vec(2, F64::map_vec(vec![1.0, 0.0, 0.0, 0.0]));
+    let result = gmres(
+        &projection,
+        &b,
+        &x0,
+        &IdentityPreconditioner,
+        &options,
+        &criterion,
+    );
+    assert!(result.converged());
+    assert!(vec_max_diff(&result.x, &b) <= F64::from(1e-15));
+}
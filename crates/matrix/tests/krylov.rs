@@ -0,0 +1,256 @@
+use floating_point::F64;
+use matrix::{
+    bicgstab, conjugate_gradient, minres, ConvergenceReason, FullMat, IdentityPreconditioner,
+    LinearOperator, Preconditioner, StoppingCriterion,
+};
+
+fn vec_max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x - *y).abs())
+        .fold(0.0.into(), |max, x| if max < x { x } else { max })
+}
+
+// -u'' + c u' + shift u on a uniform grid, applied without forming the matrix
+struct ConvectionDiffusion {
+    n: usize,
+    convection: f64,
+    shift: f64,
+}
+
+impl LinearOperator for ConvectionDiffusion {
+    fn dim(&self) -> usize {
+        self.n
+    }
+
+    fn apply(&self, x: &[F64]) -> Vec<F64> {
+        let n = self.n;
+        let (lower, upper) = (-1.0 - self.convection, -1.0 + self.convection);
+        (0..n)
+            .map(|i| {
+                let mut y = F64::from(2.0 + self.shift) * x[i];
+                if i > 0 {
+                    y += F64::from(lower) * x[i - 1];
+                }
+                if i + 1 < n {
+                    y += F64::from(upper) * x[i + 1];
+                }
+                y
+            })
+            .collect()
+    }
+}
+
+struct Diagonal(Vec<F64>);
+
+impl Preconditioner for Diagonal {
+    fn solve(&self, r: &[F64]) -> Vec<F64> {
+        r.iter().zip(self.0.iter()).map(|(r, d)| *r / *d).collect()
+    }
+}
+
+fn problem(op: &ConvectionDiffusion) -> (Vec<F64>, Vec<F64>) {
+    let x: Vec<F64> = (0..op.n)
+        .map(|i| F64::from((0.3 * i as f64).cos()))
+        .collect();
+    let b = op.apply(&x);
+    (x, b)
+}
+
+#[test]
+fn test_conjugate_gradient() {
+    let op = ConvectionDiffusion {
+        n: 50,
+        convection: 0.0,
+        shift: 0.0,
+    };
+    let (expected, b) = problem(&op);
+    let x0 = vec![F64::ZERO; op.n];
+    let result = conjugate_gradient(&op, &b, &x0, &IdentityPreconditioner, &Default::default());
+    println!("iterations = {}", result.iterations);
+    assert!(result.converged());
+    // at most n steps in exact arithmetic
+    assert!(result.iterations <= op.n + 5);
+    assert!(result.residual_history.len() == result.iterations);
+    assert!(vec_max_diff(&result.x, &expected) < 1e-7.into());
+}
+
+#[test]
+fn test_conjugate_gradient_on_full_mat() {
+    let mat = FullMat::from_vec(2, F64::map_vec(vec![2., 2., 2., 5.]));
+    let b = F64::map_vec(vec![6., 3.]);
+    let result = conjugate_gradient(
+        &mat,
+        &b,
+        &[F64::ZERO; 2],
+        &IdentityPreconditioner,
+        &Default::default(),
+    );
+    assert!(result.converged());
+    assert!(result.iterations == 2);
+    assert!(vec_max_diff(&result.x, &F64::map_vec(vec![4., -1.])) < 1e-12.into());
+}
+
+#[test]
+fn test_conjugate_gradient_preconditioned() {
+    // strongly varying diagonal makes Jacobi preconditioning pay off
+    let n = 40;
+    let mut mat = FullMat::zeros(n, n);
+    for i in 0..n {
+        mat[(i, i)] = F64::from(1.0 + (i * i) as f64);
+        if i > 0 {
+            mat[(i, i - 1)] = F64::from(0.5);
+            mat[(i - 1, i)] = F64::from(0.5);
+        }
+    }
+    let b = vec![F64::ONE; n];
+    let x0 = vec![F64::ZERO; n];
+    let criterion = StoppingCriterion::default();
+
+    let plain = conjugate_gradient(&mat, &b, &x0, &IdentityPreconditioner, &criterion);
+    let diag = Diagonal((0..n).map(|i| mat[(i, i)]).collect());
+    let preconditioned = conjugate_gradient(&mat, &b, &x0, &diag, &criterion);
+    println!(
+        "iterations: plain {}, preconditioned {}",
+        plain.iterations, preconditioned.iterations
+    );
+    assert!(plain.converged() && preconditioned.converged());
+    assert!(preconditioned.iterations < plain.iterations);
+    assert!(vec_max_diff(&plain.x, &preconditioned.x) < 1e-9.into());
+}
+
+#[test]
+fn test_conjugate_gradient_indefinite() {
+    let op = ConvectionDiffusion {
+        n: 30,
+        convection: 0.0,
+        shift: -1.5,
+    };
+    let (_, b) = problem(&op);
+    let result = conjugate_gradient(
+        &op,
+        &b,
+        &vec![F64::ZERO; op.n],
+        &IdentityPreconditioner,
+        &Default::default(),
+    );
+    assert!(result.reason == ConvergenceReason::NotPositiveDefinite);
+}
+
+#[test]
+fn test_conjugate_gradient_bad_preconditioner() {
+    let op = ConvectionDiffusion {
+        n: 30,
+        convection: 0.0,
+        shift: 0.0,
+    };
+    let (_, b) = problem(&op);
+    let x0 = vec![F64::ZERO; op.n];
+    let criterion = StoppingCriterion::default();
+
+    let negative = Diagonal(vec![F64::from(-2.0); op.n]);
+    let result = conjugate_gradient(&op, &b, &x0, &negative, &criterion);
+    assert!(result.reason == ConvergenceReason::Breakdown);
+
+    // z = 0 would make beta 0 / 0
+    let vanishing = Diagonal(vec![F64::from(f64::INFINITY); op.n]);
+    let result = conjugate_gradient(&op, &b, &x0, &vanishing, &criterion);
+    assert!(result.reason == ConvergenceReason::Breakdown);
+    assert!(result.x.iter().all(|x| x.is_finite()));
+
+    // indefinite, with r^T M^-1 r positive at the start and negative later
+    let mut mixed = vec![F64::ONE; op.n];
+    mixed[op.n / 2] = F64::from(-1e-3);
+    let result = conjugate_gradient(&op, &b, &x0, &Diagonal(mixed), &criterion);
+    println!("{:?} after {} iterations", result.reason, result.iterations);
+    assert!(result.reason == ConvergenceReason::Breakdown);
+    assert!(result.x.iter().all(|x| x.is_finite()));
+}
+
+#[test]
+fn test_minres_indefinite() {
+    let op = ConvectionDiffusion {
+        n: 60,
+        convection: 0.0,
+        shift: -1.5,
+    };
+    let (expected, b) = problem(&op);
+    let x0 = vec![F64::ZERO; op.n];
+    let result = minres(&op, &b, &x0, &IdentityPreconditioner, &Default::default());
+    println!("iterations = {}", result.iterations);
+    assert!(result.converged());
+    assert!(vec_max_diff(&result.x, &expected) < 1e-7.into());
+
+    // the residual norm never increases
+    for w in result.residual_history.windows(2) {
+        assert!(w[1] <= w[0] * F64::from(1.0 + 1e-12));
+    }
+    let r: Vec<F64> = op
+        .apply(&result.x)
+        .iter()
+        .zip(b.iter())
+        .map(|(x, y)| *x - *y)
+        .collect();
+    let r_norm = r.iter().map(|x| *x * *x).sum::<F64>().sqrt();
+    let last = result.residual_history[result.iterations - 1];
+    assert!((r_norm - last).abs() < 1e-8.into());
+}
+
+#[test]
+fn test_minres_preconditioned() {
+    let op = ConvectionDiffusion {
+        n: 60,
+        convection: 0.0,
+        shift: -1.5,
+    };
+    let (expected, b) = problem(&op);
+    let x0 = vec![F64::ZERO; op.n];
+    let diag = Diagonal(vec![F64::from(2.0); op.n]);
+    let result = minres(&op, &b, &x0, &diag, &Default::default());
+    assert!(result.converged());
+    assert!(vec_max_diff(&result.x, &expected) < 1e-7.into());
+}
+
+#[test]
+fn test_bicgstab() {
+    let op = ConvectionDiffusion {
+        n: 80,
+        convection: 0.4,
+        shift: 0.1,
+    };
+    let (expected, b) = problem(&op);
+    let x0 = vec![F64::ZERO; op.n];
+    let result = bicgstab(&op, &b, &x0, &IdentityPreconditioner, &Default::default());
+    println!("iterations = {}", result.iterations);
+    assert!(result.converged());
+    assert!(vec_max_diff(&result.x, &expected) < 1e-8.into());
+
+    let diag = Diagonal(vec![F64::from(2.1); op.n]);
+    let result = bicgstab(&op, &b, &expected, &diag, &Default::default());
+    // starting from the solution needs no iterations
+    assert!(result.converged());
+    assert!(result.iterations == 0);
+}
+
+#[test]
+fn test_max_iterations() {
+    let op = ConvectionDiffusion {
+        n: 80,
+        convection: 0.4,
+        shift: 0.0,
+    };
+    let (_, b) = problem(&op);
+    let criterion = StoppingCriterion {
+        max_iterations: 3,
+        ..Default::default()
+    };
+    let result = bicgstab(
+        &op,
+        &b,
+        &vec![F64::ZERO; op.n],
+        &IdentityPreconditioner,
+        &criterion,
+    );
+    assert!(result.reason == ConvergenceReason::MaxIterationsReached);
+    assert!(result.iterations == 3);
+}
@@ -0,0 +1,199 @@
+use floating_point::F64;
+use matrix::{FullMat, Layout, Matrix};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn random_mat(rng: &mut StdRng, m: usize, n: usize) -> FullMat<F64> {
+    FullMat::from_vec(
+        n,
+        (0..(m * n))
+            .map(|_| rng.gen_range(-1.0..1.0).into())
+            .collect(),
+    )
+}
+
+// the same matrix with the columns `lda` apart, the gaps filled with junk
+fn padded_col_major(a: &FullMat<F64>, lda: usize) -> FullMat<F64> {
+    let (m, n) = a.shape();
+    let mut v = vec![F64::from(1e300); lda * n];
+    for i in 0..m {
+        for j in 0..n {
+            v[j * lda + i] = a[(i, j)];
+        }
+    }
+    FullMat::from_vec_strided((m, n), (1, lda), v)
+}
+
+// rows `lda` apart, with `extra` junk entries after the last one
+fn padded_row_major(a: &FullMat<F64>, lda: usize, extra: usize) -> FullMat<F64> {
+    let (m, n) = a.shape();
+    let mut v = vec![F64::from(1e300); lda * m + extra];
+    for i in 0..m {
+        for j in 0..n {
+            v[i * lda + j] = a[(i, j)];
+        }
+    }
+    FullMat::from_vec_strided((m, n), (lda, 1), v)
+}
+
+fn layouts(a: &FullMat<F64>) -> [FullMat<F64>; 5] {
+    let (m, n) = a.shape();
+    [
+        a.to_row_major(),
+        a.to_col_major(),
+        padded_col_major(a, m + 3),
+        padded_row_major(a, n + 2, 0),
+        // row-major strides over a longer storage
+        padded_row_major(a, n, 7),
+    ]
+}
+
+fn same(a: &FullMat<F64>, b: &FullMat<F64>) -> bool {
+    a.shape() == b.shape() && a.iter().zip(b.iter()).all(|(x, y)| x == y)
+}
+
+#[test]
+fn test_constructors_and_conversions() {
+    let rows = FullMat::from_vec(3, F64::map_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]));
+    let cols = FullMat::from_vec_col_major(2, F64::map_vec(vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]));
+    println!("cols = \n{}", cols);
+    assert!(rows.layout() == Layout::RowMajor && rows.strides() == (3, 1));
+    assert!(cols.layout() == Layout::ColMajor && cols.strides() == (1, 2));
+    assert!(same(&rows, &cols) && cols.shape() == (2, 3));
+    assert!(same(
+        &cols,
+        &FullMat::from_cols(vec![
+            F64::map_vec(vec![1.0, 4.0]),
+            F64::map_vec(vec![2.0, 5.0]),
+            F64::map_vec(vec![3.0, 6.0]),
+        ])
+    ));
+
+    // the transpose of a row-major matrix is a column-major one, for free
+    let t = rows.clone().into_transpose();
+    assert!(t.layout() == Layout::ColMajor && t.shape() == (3, 2));
+    assert!(same(&t, &rows.transpose()) && t[(2, 1)] == F64::from(6.0));
+    // the copying transpose keeps the layout
+    assert!(rows.transpose().layout() == Layout::RowMajor);
+    assert!(cols.transpose().layout() == Layout::ColMajor);
+
+    assert!(rows.row_iter(1).copied().collect::<Vec<_>>() == F64::map_vec(vec![4.0, 5.0, 6.0]));
+    assert!(cols.row_iter(1).copied().collect::<Vec<_>>() == F64::map_vec(vec![4.0, 5.0, 6.0]));
+    assert!(cols.col(2) == F64::map_vec(vec![3.0, 6.0]));
+    assert!(cols.col_iter(0).copied().collect::<Vec<_>>() == rows.col(0));
+
+    // tiles at the edges of both dimensions
+    let mut rng = StdRng::seed_from_u64(47);
+    let a = random_mat(&mut rng, 70, 45);
+    let c = a.to_col_major();
+    assert!(c.layout() == Layout::ColMajor && same(&a, &c));
+    assert!(same(&c.to_row_major(), &a) && c.clone().into_row_major().layout() == Layout::RowMajor);
+    let p = padded_col_major(&a, 75);
+    assert!(p.layout() == Layout::Strided && p.strides() == (1, 75));
+    // the junk between the columns is never looked at
+    assert!(p.element_max_abs() == a.element_max_abs());
+    assert!(same(&p.to_row_major(), &a) && same(&p.to_col_major(), &a));
+    assert!(same(&p.add(&c).sub(&a), &a) && p.add(&c).layout() == Layout::RowMajor);
+    assert!(c.add(&a).layout() == Layout::ColMajor);
+}
+
+#[test]
+fn test_swaps() {
+    let mut rng = StdRng::seed_from_u64(470);
+    let a = random_mat(&mut rng, 6, 5);
+    let mut expected = a.clone();
+    expected.swap_row(4, 1);
+    expected.swap_col(0, 3);
+    assert!(expected[(1, 3)] == a[(4, 0)] && expected[(4, 0)] == a[(1, 3)]);
+    for mut b in layouts(&a) {
+        b.swap_row(1, 4);
+        b.swap_col(3, 0);
+        b.swap_row(2, 2);
+        assert!(same(&b, &expected));
+    }
+}
+
+#[test]
+fn test_products() {
+    let mut rng = StdRng::seed_from_u64(4700);
+    // on both sides of the cutoff for packing
+    for (m, k, n) in [(5, 4, 3), (70, 50, 60)] {
+        let a = random_mat(&mut rng, m, k);
+        let b = random_mat(&mut rng, k, n);
+        let c0 = random_mat(&mut rng, m, n);
+        let mut expected = c0.clone();
+        expected.gemm(F64::from(2.0), &a, &b, F64::from(0.5));
+        let tolerance = F64::from((4 * k) as f64) * F64::EPSILON;
+
+        for a in layouts(&a) {
+            for b in layouts(&b) {
+                let product = a.mul_mat(&b);
+                assert!(product.sub(&a.mul_mat_naive(&b)).element_max_abs() <= tolerance);
+                for mut c in layouts(&c0) {
+                    let layout = c.layout();
+                    c.gemm(F64::from(2.0), &a, &b, F64::from(0.5));
+                    assert!(c.layout() == layout);
+                    assert!(c.sub(&expected).element_max_abs() <= tolerance);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_factorizations() {
+    let mut rng = StdRng::seed_from_u64(47000);
+    let n = 80;
+    let a = random_mat(&mut rng, n, n);
+    let (l, u, p) = a.lu().unwrap();
+    let inv = a.inv().unwrap();
+    let spd = a.mul_mat(&a.transpose()).add(&FullMat::identity(n));
+    let chol = FullMat::from(spd.cholesky().unwrap());
+    let x: Vec<F64> = (0..n).map(|_| rng.gen_range(-1.0..1.0).into()).collect();
+    let b = a.mul_vec(&x);
+    let solution = a.lu_solve(&b).unwrap();
+
+    for (a, spd) in layouts(&a).into_iter().zip(layouts(&spd)) {
+        let (l_b, u_b, p_b) = a.lu().unwrap();
+        // the factorizations see the same numbers in the same order
+        assert!(p_b == p);
+        assert!(same(&FullMat::from(l_b), &FullMat::from(l.clone())));
+        assert!(same(&FullMat::from(u_b), &FullMat::from(u.clone())));
+        assert!(same(&a.inv().unwrap(), &inv));
+        assert!(a.lu_solve(&b).unwrap() == solution);
+        assert!(same(&FullMat::from(spd.cholesky().unwrap()), &chol));
+        assert!(a.norm1() == a.to_row_major().norm1());
+
+        let (q, r) = a.qr();
+        assert!(q.mul_mat(&FullMat::from(r)).sub(&a).element_max_abs() <= F64::from(1e-12));
+    }
+}
+
+#[test]
+fn test_storage_longer_than_matrix() {
+    // a 2 x 2 matrix with row-major strides in a storage of 6
+    let a = FullMat::from_vec_strided(
+        (2, 2),
+        (2, 1),
+        F64::map_vec(vec![4.0, 3.0, 6.0, 3.0, 1e300, 1e300]),
+    );
+    let dense = FullMat::from_vec(2, F64::map_vec(vec![4.0, 3.0, 6.0, 3.0]));
+    assert!(a.layout() == Layout::Strided && same(&a, &dense));
+    let r = a.to_row_major();
+    assert!(r.layout() == Layout::RowMajor && same(&r, &dense));
+    assert!(a.clone().into_row_major().layout() == Layout::RowMajor);
+
+    let (l, u, p) = a.lu().unwrap();
+    let (l, u) = (FullMat::from(l), FullMat::from(u));
+    println!("l = \n{}u = \n{}", l, u);
+    assert!(l.shape() == (2, 2) && u.shape() == (2, 2) && p == vec![1, 0]);
+    let pa = FullMat::from_vec(2, F64::map_vec(vec![6.0, 3.0, 4.0, 3.0]));
+    assert!(same(&l.mul_mat(&u), &pa));
+
+    assert!(same(&a.add(&a), &dense.add(&dense)));
+    assert!(same(&dense.add(&a), &dense.add(&dense)));
+    assert!(same(&a.mul_mat(&a), &dense.mul_mat(&dense)));
+    let mut c = a.clone();
+    c.gemm(F64::ONE, &dense, &dense, F64::ONE);
+    assert!(same(&c, &dense.mul_mat(&dense).add(&dense)));
+    assert!(c.strides() == (2, 1));
+}
@@ -0,0 +1,157 @@
+use floating_point::F64;
+use matrix::{lstsq, lstsq_tikhonov, lstsq_weighted, FullMat, LstsqMethod, Matrix};
+
+fn vec_max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x - *y).abs())
+        .fold(0.0.into(), |max, x| if max < x { x } else { max })
+}
+
+fn mat_max_diff(a: &FullMat<F64>, b: &FullMat<F64>) -> F64 {
+    assert!(a.shape() == b.shape());
+    let mut max = F64::ZERO;
+    for i in 0..a.row_count() {
+        for j in 0..a.col_count() {
+            max = max.max((a[(i, j)] - b[(i, j)]).abs());
+        }
+    }
+    max
+}
+
+// inconsistent system from the least squares chapter, x = (7/4, 3/4)
+fn example_system() -> (FullMat<F64>, Vec<F64>) {
+    (
+        FullMat::from_vec(2, F64::map_vec(vec![1., 1., 1., -1., 1., 1.])),
+        F64::map_vec(vec![2., 1., 3.]),
+    )
+}
+
+#[test]
+fn test_cholesky() {
+    let mat = FullMat::from_vec(
+        3,
+        F64::map_vec(vec![4., -2., 2., -2., 2., -4., 2., -4., 11.]),
+    );
+    let l = mat.cholesky().unwrap();
+    println!("l = \n{}", l);
+    assert!(l[(0, 0)] == 2.0.into());
+    assert!(l[(1, 0)] == (-1.0).into());
+    assert!(l[(1, 1)] == 1.0.into());
+    assert!(l[(2, 0)] == 1.0.into());
+    assert!(l[(2, 1)] == (-3.0).into());
+    assert!(l[(2, 2)] == 1.0.into());
+
+    let l = FullMat::from(l);
+    assert!(mat_max_diff(&l.mul_mat(&l.transpose()), &mat) == 0.0.into());
+}
+
+#[test]
+fn test_cholesky_not_positive_definite() {
+    let mat = FullMat::from_vec(2, F64::map_vec(vec![1., 2., 2., 1.]));
+    assert!(mat.cholesky().is_none());
+    assert!(mat.cholesky_solve(&F64::map_vec(vec![1., 1.])).is_none());
+}
+
+#[test]
+fn test_qr() {
+    let mat = FullMat::from_vec(2, F64::map_vec(vec![1., -4., 2., 3., 2., 2.]));
+    let (q, r) = mat.qr();
+    println!("q = \n{}", q);
+    println!("r = \n{}", r);
+
+    assert!(mat_max_diff(&q.transpose().mul_mat(&q), &FullMat::identity(2)) < 1e-15.into());
+    assert!(mat_max_diff(&q.mul_mat(&FullMat::from(r)), &mat) < 1e-14.into());
+}
+
+#[test]
+fn test_svd() {
+    let mat = FullMat::from_vec(2, F64::map_vec(vec![0., 1., 0., 1., 1., 0.]));
+    let svd = mat.svd().unwrap();
+    println!("singular values = {:?}", svd.singular_values);
+    assert!((svd.singular_values[0] - F64::from(2.0).sqrt()).abs() < 1e-15.into());
+    assert!((svd.singular_values[1] - F64::ONE).abs() < 1e-15.into());
+
+    let mut s = FullMat::zeros(2, 2);
+    s[(0, 0)] = svd.singular_values[0];
+    s[(1, 1)] = svd.singular_values[1];
+    let usv = svd.u.mul_mat(&s).mul_mat(&svd.v.transpose());
+    assert!(mat_max_diff(&usv, &mat) < 1e-15.into());
+}
+
+#[test]
+fn test_lstsq_methods() {
+    let (a, b) = example_system();
+    let expected = F64::map_vec(vec![1.75, 0.75]);
+
+    for method in [
+        LstsqMethod::NormalEquations,
+        LstsqMethod::QR,
+        LstsqMethod::SVD,
+    ] {
+        let solution = lstsq(&a, &b, method).unwrap();
+        println!("{:?}: {:?}", method, solution);
+        assert!(vec_max_diff(&solution.x, &expected) < 1e-15.into());
+        assert!((solution.residual_norm - F64::from(0.5).sqrt()).abs() < 1e-15.into());
+        assert!(solution.rank == 2);
+        assert!(solution.singular_values.is_some() == (method == LstsqMethod::SVD));
+    }
+}
+
+#[test]
+fn test_lstsq_rank_deficient() {
+    let a = FullMat::from_vec(2, F64::map_vec(vec![1., 2., 2., 4., 3., 6.]));
+    let b = F64::map_vec(vec![1., 2., 3.]);
+
+    assert!(lstsq(&a, &b, LstsqMethod::QR).is_none());
+
+    let solution = lstsq(&a, &b, LstsqMethod::SVD).unwrap();
+    assert!(solution.rank == 1);
+    // minimum norm solution of x + 2y = 1
+    assert!(vec_max_diff(&solution.x, &F64::map_vec(vec![0.2, 0.4])) < 1e-15.into());
+    assert!(solution.residual_norm < 1e-14.into());
+}
+
+#[test]
+fn test_lstsq_weighted() {
+    let (a, b) = example_system();
+    let ones = vec![F64::ONE; 3];
+    let x0 = lstsq(&a, &b, LstsqMethod::QR).unwrap().x;
+    let x1 = lstsq_weighted(&a, &b, &ones, LstsqMethod::QR).unwrap().x;
+    assert!(vec_max_diff(&x0, &x1) < 1e-15.into());
+
+    // a heavily weighted second row is almost interpolated
+    let weights = F64::map_vec(vec![1., 1e8, 1.]);
+    let x = lstsq_weighted(&a, &b, &weights, LstsqMethod::SVD)
+        .unwrap()
+        .x;
+    assert!((x[0] - x[1] - F64::ONE).abs() < 1e-7.into());
+}
+
+#[test]
+fn test_lstsq_tikhonov() {
+    let (a, b) = example_system();
+    let plain = lstsq(&a, &b, LstsqMethod::QR).unwrap();
+    let x0 = lstsq_tikhonov(&a, &b, F64::ZERO, LstsqMethod::QR)
+        .unwrap()
+        .x;
+    assert!(vec_max_diff(&x0, &plain.x) < 1e-15.into());
+
+    // (A^T A + lambda I) x = A^T b
+    let lambda = F64::from(0.5);
+    let at = a.transpose();
+    let normal = at.mul_mat(&a).add(&FullMat::from_vec(
+        2,
+        vec![lambda, F64::ZERO, F64::ZERO, lambda],
+    ));
+    let expected = normal.lu_solve(&at.mul_vec(&b)).unwrap();
+    for method in [
+        LstsqMethod::NormalEquations,
+        LstsqMethod::QR,
+        LstsqMethod::SVD,
+    ] {
+        let solution = lstsq_tikhonov(&a, &b, lambda, method).unwrap();
+        assert!(vec_max_diff(&solution.x, &expected) < 1e-14.into());
+        assert!(solution.residual_norm > plain.residual_norm);
+    }
+}
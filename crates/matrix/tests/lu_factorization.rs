@@ -1,7 +1,7 @@
 use floating_point::F64;
 use matrix::{FullMat, Matrix};
 
-fn vec_max_diff(a: &Vec<F64>, b: &Vec<F64>) -> F64 {
+fn vec_max_diff(a: &[F64], b: &[F64]) -> F64 {
     a.iter()
         .zip(b.iter())
         .map(|(x, y)| (*x - *y).abs())
@@ -21,7 +21,7 @@ fn test_lu() {
         p.iter()
             .map(|i| {
                 let mut v = vec![F64::from(0.0); p.len()];
-                v[*i as usize] = 1.0.into();
+                v[*i] = 1.0.into();
                 v
             })
             .collect(),
@@ -109,29 +109,29 @@ fn test_norm_and_condition_number() {
 
     let n = 6;
     let mat_a = hibert_mat(n);
-    let ones = vec![F64::from(1.0); n as usize];
+    let ones = vec![F64::from(1.0); n];
     let b = mat_a.mul_vec(&ones);
     let x = mat_a.lu_solve(&b).unwrap();
     println!("n = {}, x = {:#?}", n, x);
     let conditon_number = condition_number(&mat_a);
     println!(
-        "condition number of hibert({}) = {}",
+        "condition number of hibert({}) = {:.6e}",
         n,
-        format!("{:.6e}", conditon_number.to_f64())
+        conditon_number.to_f64()
     );
     assert!(conditon_number > 1e7.into());
 
     let n = 10;
     let mat_a = hibert_mat(n);
-    let ones = vec![F64::from(1.0); n as usize];
+    let ones = vec![F64::from(1.0); n];
     let b = mat_a.mul_vec(&ones);
     let x = mat_a.lu_solve(&b).unwrap();
     println!("n = {}, x = {:#?}", n, x);
     let conditon_number = condition_number(&mat_a);
     println!(
-        "condition number of hibert({}) = {}",
+        "condition number of hibert({}) = {:.6e}",
         n,
-        format!("{:.6e}", conditon_number.to_f64())
+        conditon_number.to_f64()
     );
     assert!(conditon_number > 1e13.into());
 }
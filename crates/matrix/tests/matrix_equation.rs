@@ -0,0 +1,191 @@
+use floating_point::F64;
+use matrix::{care, lyapunov, stein, sylvester, FullMat, Matrix, SylvesterMethod};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const METHODS: [SylvesterMethod; 2] = [
+    SylvesterMethod::BartelsStewart,
+    SylvesterMethod::HessenbergSchur,
+];
+
+fn random_mat(rng: &mut StdRng, m: usize, n: usize) -> FullMat<F64> {
+    FullMat::from_vec(
+        n,
+        (0..(m * n))
+            .map(|_| rng.gen_range(-1.0..1.0).into())
+            .collect(),
+    )
+}
+
+fn shifted(a: &FullMat<F64>, shift: f64) -> FullMat<F64> {
+    let mut a = a.clone();
+    for i in 0..a.row_count() {
+        a[(i, i)] += F64::from(shift);
+    }
+    a
+}
+
+fn relative_error(a: &FullMat<F64>, b: &FullMat<F64>) -> F64 {
+    a.sub(b).element_max_abs() / b.element_max_abs()
+}
+
+fn asymmetry(a: &FullMat<F64>) -> F64 {
+    a.sub(&a.transpose()).element_max_abs()
+}
+
+#[test]
+fn test_sylvester() {
+    let mut rng = StdRng::seed_from_u64(50);
+    for (m, n) in [(1, 1), (3, 5), (8, 2), (20, 15)] {
+        // the spectra of A and -B are apart
+        let a = shifted(&random_mat(&mut rng, m, m), 3.0);
+        let b = shifted(&random_mat(&mut rng, n, n), 2.0);
+        let x = random_mat(&mut rng, m, n);
+        let c = a.mul_mat(&x).add(&x.mul_mat(&b));
+        for method in METHODS {
+            let solution = sylvester(&a, &b, &c, method).unwrap();
+            let error = relative_error(&solution.x, &x);
+            println!(
+                "{:?}, m = {}, n = {}: residual {:e}, error {:e}",
+                method, m, n, solution.residual, error
+            );
+            assert!(solution.x.shape() == (m, n));
+            assert!(solution.residual <= F64::from(1e-15));
+            assert!(error <= F64::from(1e-13));
+        }
+    }
+
+    // A and -B share the eigenvalue 1
+    let a = FullMat::from_vec(2, F64::map_vec(vec![1.0, 5.0, 0.0, 2.0]));
+    let b = FullMat::from_vec(2, F64::map_vec(vec![-1.0, 0.0, 0.0, 3.0]));
+    let c = FullMat::identity(2);
+    for method in METHODS {
+        assert!(sylvester(&a, &b, &c, method).is_none());
+    }
+}
+
+#[test]
+fn test_lyapunov() {
+    let mut rng = StdRng::seed_from_u64(500);
+    for n in [1, 4, 12, 30] {
+        // stable A and Q = C C^T, so that X is symmetric positive definite
+        let a = shifted(&random_mat(&mut rng, n, n), -(n as f64).sqrt() - 1.0);
+        let c = random_mat(&mut rng, n, n);
+        let q = c.mul_mat(&c.transpose());
+        for method in METHODS {
+            let solution = lyapunov(&a, &q, method).unwrap();
+            let x = &solution.x;
+            println!("{:?}, n = {}: residual {:e}", method, n, solution.residual);
+            assert!(solution.residual <= F64::from(1e-15));
+            assert!(asymmetry(x) <= F64::from(1e-13) * x.element_max_abs());
+            assert!(x.cholesky().is_some());
+        }
+    }
+
+    // A = -I gives X = Q / 2
+    let q = FullMat::pascal(4);
+    let solution = lyapunov(
+        &shifted(&FullMat::zeros(4, 4), -1.0),
+        &q,
+        SylvesterMethod::BartelsStewart,
+    )
+    .unwrap();
+    let half = FullMat::from_vec(4, q.iter().map(|x| *x / F64::from(2.0)).collect());
+    assert!(solution.x.sub(&half).element_max_abs() <= F64::from(1e-14));
+}
+
+#[test]
+fn test_stein() {
+    let mut rng = StdRng::seed_from_u64(5000);
+    for n in [1, 3, 10, 25] {
+        // spectral radius below 1: X = sum_k A^k Q (A^T)^k converges
+        let a = random_mat(&mut rng, n, n);
+        let a = FullMat::from_vec(
+            n,
+            a.iter()
+                .map(|x| *x * F64::from(0.9 / (n as f64).sqrt()))
+                .collect(),
+        );
+        let c = random_mat(&mut rng, n, n);
+        let q = c.mul_mat(&c.transpose());
+
+        let mut expected = q.clone();
+        let mut term = q.clone();
+        for _ in 0..400 {
+            term = a.mul_mat(&term).mul_mat(&a.transpose());
+            expected = expected.add(&term);
+        }
+
+        let solution = stein(&a, &q).unwrap();
+        let error = relative_error(&solution.x, &expected);
+        println!(
+            "n = {}: residual {:e}, error {:e}",
+            n, solution.residual, error
+        );
+        assert!(solution.residual <= F64::from(1e-15));
+        assert!(error <= F64::from(1e-12));
+        assert!(asymmetry(&solution.x) <= F64::from(1e-13) * solution.x.element_max_abs());
+    }
+
+    // eigenvalues 2 and 1/2 have a product of 1
+    let a = FullMat::from_vec(2, F64::map_vec(vec![2.0, 1.0, 0.0, 0.5]));
+    assert!(stein(&a, &FullMat::identity(2)).is_none());
+}
+
+#[test]
+fn test_care() {
+    // scalar: 2 a x - x^2 b^2 / r + q = 0 with a = b = q = r = 1
+    let one = FullMat::identity(1);
+    let solution = care(&one, &one, &one, &one).unwrap();
+    assert!((solution.x[(0, 0)] - F64::from(1.0 + 2.0f64.sqrt())).abs() <= F64::from(1e-14));
+
+    // the double integrator with Q = I and R = 1 has X = [[sqrt 3, 1], [1, sqrt 3]]
+    let a = FullMat::from_vec(2, F64::map_vec(vec![0.0, 1.0, 0.0, 0.0]));
+    let b = FullMat::from_vec(1, F64::map_vec(vec![0.0, 1.0]));
+    let solution = care(&a, &b, &FullMat::identity(2), &one).unwrap();
+    let s = 3.0f64.sqrt();
+    let expected = FullMat::from_vec(2, F64::map_vec(vec![s, 1.0, 1.0, s]));
+    println!("x = \n{}", solution.x);
+    assert!(solution.x.sub(&expected).element_max_abs() <= F64::from(1e-14));
+
+    let mut rng = StdRng::seed_from_u64(50000);
+    for (n, m) in [(3, 1), (6, 2), (15, 4)] {
+        // A may be unstable; random B makes (A, B) controllable
+        let a = random_mat(&mut rng, n, n);
+        let b = random_mat(&mut rng, n, m);
+        let c = random_mat(&mut rng, n, n);
+        let q = c.mul_mat(&c.transpose());
+        let r = shifted(&FullMat::zeros(m, m), 0.5);
+        let solution = care(&a, &b, &q, &r).unwrap();
+        let x = &solution.x;
+        println!("n = {}, m = {}: residual {:e}", n, m, solution.residual);
+        assert!(solution.residual <= F64::from(1e-14));
+        assert!(x.cholesky().is_some());
+
+        // the closed loop A - B R^-1 B^T X is stable
+        let gain = b.mul_mat(&b.transpose()).mul_mat(x);
+        let closed_loop = a.sub(&FullMat::from_vec(
+            n,
+            gain.iter().map(|x| *x * F64::from(2.0)).collect(),
+        ));
+        let eigenvalues = closed_loop.eigenvalues().unwrap();
+        assert!(eigenvalues.iter().all(|lambda| lambda.real < F64::ZERO));
+    }
+
+    // an uncontrollable unstable mode cannot be stabilized
+    let a = FullMat::from_vec(2, F64::map_vec(vec![1.0, 0.0, 0.0, -1.0]));
+    let b = FullMat::from_vec(1, F64::map_vec(vec![0.0, 1.0]));
+    let zero_q = FullMat::zeros(2, 2);
+    assert!(care(&a, &b, &zero_q, &one).is_none());
+
+    // non-finite data fails instead of panicking
+    for x in [f64::NAN, f64::INFINITY] {
+        let mut bad_q = FullMat::identity(2);
+        bad_q[(1, 0)] = F64::from(x);
+        assert!(care(&a, &b, &bad_q, &one).is_none());
+        let bad_r = FullMat::from_vec(1, vec![F64::from(x)]);
+        assert!(care(&a, &b, &FullMat::identity(2), &bad_r).is_none());
+        for method in METHODS {
+            assert!(sylvester(&a, &bad_q, &bad_q, method).is_none());
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use floating_point::F64;
+use matrix::{FullMat, Matrix, MatrixError, TriFullMat, TriangleMatType};
+
+fn singular() -> FullMat<F64> {
+    FullMat::from_rows(vec![
+        F64::map_vec(vec![1., 2., 3.]),
+        F64::map_vec(vec![2., 4., 6.]),
+        F64::map_vec(vec![1., 0., 1.]),
+    ])
+}
+
+#[test]
+fn test_try_from_rows() {
+    let mat = FullMat::try_from_rows(vec![F64::map_vec(vec![1., 2.]), F64::map_vec(vec![3., 4.])])
+        .unwrap();
+    assert!(mat.shape() == (2, 2));
+
+    let empty: Vec<Vec<F64>> = vec![];
+    assert!(FullMat::try_from_rows(empty).unwrap_err() == MatrixError::EmptyInput);
+    assert!(FullMat::<F64>::try_from_rows(vec![vec![]]).unwrap_err() == MatrixError::EmptyInput);
+
+    let ragged = vec![
+        F64::map_vec(vec![1., 2., 3.]),
+        F64::map_vec(vec![4., 5., 6.]),
+        F64::map_vec(vec![7., 8.]),
+    ];
+    let error = FullMat::try_from_rows(ragged).unwrap_err();
+    assert!(
+        error
+            == MatrixError::ShapeMismatch {
+                left: (2, 3),
+                right: (1, 2)
+            }
+    );
+    println!("{}", error);
+    assert!(error.to_string() == "shapes 2x3 and 1x2 do not match");
+}
+
+#[test]
+fn test_try_from_vec() {
+    let mat = FullMat::try_from_vec(3, F64::map_vec(vec![1., 2., 3., 4., 5., 6.])).unwrap();
+    assert!(mat.shape() == (2, 3));
+
+    assert!(FullMat::<F64>::try_from_vec(3, vec![]).unwrap_err() == MatrixError::EmptyInput);
+    assert!(
+        FullMat::try_from_vec(0, F64::map_vec(vec![1.])).unwrap_err() == MatrixError::EmptyInput
+    );
+    assert!(
+        FullMat::try_from_vec(3, F64::map_vec(vec![1., 2., 3., 4.])).unwrap_err()
+            == MatrixError::ShapeMismatch {
+                left: (1, 3),
+                right: (1, 1)
+            }
+    );
+}
+
+#[test]
+fn test_try_mul_mat() {
+    let a = FullMat::from_vec(3, F64::map_vec(vec![1., 2., 3., 4., 5., 6.]));
+    let product = a.try_mul_mat(&a.transpose()).unwrap();
+    assert!(product.shape() == (2, 2));
+    assert!(product[(0, 1)] == 32.0.into());
+
+    let error = a.try_mul_mat(&a).unwrap_err();
+    assert!(
+        error
+            == MatrixError::ShapeMismatch {
+                left: (2, 3),
+                right: (2, 3)
+            }
+    );
+}
+
+#[test]
+fn test_try_lu_solve() {
+    let a = FullMat::from_rows(vec![F64::map_vec(vec![2., 1.]), F64::map_vec(vec![1., 3.])]);
+    let x = a.try_lu_solve(&F64::map_vec(vec![3., 4.])).unwrap();
+    assert!(x == F64::map_vec(vec![1., 1.]));
+
+    assert!(
+        a.try_lu_solve(&F64::map_vec(vec![1., 2., 3.])).unwrap_err()
+            == MatrixError::ShapeMismatch {
+                left: (2, 2),
+                right: (3, 1)
+            }
+    );
+
+    let rectangular = FullMat::from_vec(3, F64::map_vec(vec![1., 2., 3., 4., 5., 6.]));
+    let error = rectangular
+        .try_lu_solve(&F64::map_vec(vec![1., 2.]))
+        .unwrap_err();
+    assert!(error == MatrixError::NotSquare((2, 3)));
+    assert!(error.to_string() == "matrix of shape 2x3 is not square");
+
+    // the first row is half the second, so elimination ends on a zero row
+    let error = singular()
+        .try_lu_solve(&F64::map_vec(vec![1., 1., 1.]))
+        .unwrap_err();
+    assert!(error == MatrixError::Singular(2));
+    assert!(error.to_string() == "matrix is singular at pivot 2");
+    assert!(singular()
+        .lu_solve(&F64::map_vec(vec![1., 1., 1.]))
+        .is_none());
+}
+
+#[test]
+fn test_try_inv() {
+    let a = FullMat::from_rows(vec![F64::map_vec(vec![2., 0.]), F64::map_vec(vec![0., 4.])]);
+    let inv = a.try_inv().unwrap();
+    assert!(inv[(0, 0)] == 0.5.into() && inv[(1, 1)] == 0.25.into());
+
+    assert!(singular().try_inv().unwrap_err() == MatrixError::Singular(2));
+    let rectangular = FullMat::from_vec(2, F64::map_vec(vec![1., 2., 3., 4., 5., 6.]));
+    assert!(rectangular.try_inv().unwrap_err() == MatrixError::NotSquare((3, 2)));
+}
+
+#[test]
+fn test_try_tri_from_vec() {
+    let lower =
+        TriFullMat::try_from_vec(TriangleMatType::Lower, F64::map_vec(vec![1., 2., 3.])).unwrap();
+    assert!(lower.shape() == (2, 2) && lower[(1, 0)] == 2.0.into());
+
+    let error =
+        TriFullMat::try_from_vec(TriangleMatType::Upper, F64::map_vec(vec![1., 2., 3., 4.]))
+            .unwrap_err();
+    assert!(error == MatrixError::NonTriangularLength(4));
+    assert!(error.to_string() == "4 elements do not fill a triangular matrix");
+    assert!(
+        TriFullMat::<F64>::try_from_vec(TriangleMatType::Upper, vec![]).unwrap_err()
+            == MatrixError::EmptyInput
+    );
+}
+
+#[test]
+fn test_error_trait() {
+    // usable behind `?` in functions returning a boxed error
+    fn parse_and_solve(
+        rows: Vec<Vec<F64>>,
+        b: &[F64],
+    ) -> Result<Vec<F64>, Box<dyn std::error::Error>> {
+        let a = FullMat::try_from_rows(rows)?;
+        Ok(a.try_lu_solve(b)?)
+    }
+    let error = parse_and_solve(vec![F64::map_vec(vec![1.]), vec![]], &[F64::ONE]).unwrap_err();
+    assert!(error.to_string() == "shapes 1x1 and 1x0 do not match");
+    assert!(
+        parse_and_solve(vec![F64::map_vec(vec![2.])], &[F64::ONE]).unwrap() == vec![F64::from(0.5)]
+    );
+}
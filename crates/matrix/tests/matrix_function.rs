@@ -0,0 +1,250 @@
+use floating_point::F64;
+use matrix::{FullMat, Matrix};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn random_mat(rng: &mut StdRng, n: usize) -> FullMat<F64> {
+    FullMat::from_vec(
+        n,
+        (0..(n * n))
+            .map(|_| rng.gen_range(-1.0..1.0).into())
+            .collect(),
+    )
+}
+
+fn scaled(a: &FullMat<F64>, c: f64) -> FullMat<F64> {
+    let n = a.col_count();
+    FullMat::from_vec(n, a.iter().map(|x| *x * F64::from(c)).collect())
+}
+
+// max |A - B| / max |B|
+fn relative_error(a: &FullMat<F64>, b: &FullMat<F64>) -> F64 {
+    a.sub(b).element_max_abs() / b.element_max_abs()
+}
+
+fn max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b)
+        .fold(F64::ZERO, |max, (x, y)| max.max((*x - *y).abs()))
+}
+
+#[test]
+fn test_expm() {
+    // the example of Moler and Van Loan, A = V diag(-1, -17) V^-1, on which
+    // the Taylor series fails badly
+    let v = FullMat::from_vec(2, F64::map_vec(vec![1.0, 3.0, 2.0, 4.0]));
+    let a = FullMat::from_vec(2, F64::map_vec(vec![-49.0, 24.0, -64.0, 31.0]));
+    let d = FullMat::from_vec(
+        2,
+        F64::map_vec(vec![(-1.0f64).exp(), 0.0, 0.0, (-17.0f64).exp()]),
+    );
+    let expected = v.mul_mat(&d).mul_mat(&v.inv().unwrap());
+    let exp_a = a.expm();
+    println!("expm(a) = \n{}", exp_a);
+    assert!(relative_error(&exp_a, &expected) <= F64::from(1e-13));
+
+    // rotations, with every Pade degree and with squaring
+    for theta in [1e-3, 0.1, 0.5, 1.5, 2.5, 40.0] {
+        let a = FullMat::from_vec(2, F64::map_vec(vec![0.0, -theta, theta, 0.0]));
+        let (c, s) = (theta.cos(), theta.sin());
+        let expected = FullMat::from_vec(2, F64::map_vec(vec![c, -s, s, c]));
+        let error = a.expm().sub(&expected).element_max_abs();
+        println!("theta = {}: error {:e}", theta, error);
+        assert!(error <= F64::from(theta.max(1.0) * 1e-14));
+    }
+
+    let mut rng = StdRng::seed_from_u64(49);
+    for (n, c) in [(1, 3.0), (5, 0.01), (8, 1.0), (20, 1.0)] {
+        let a = scaled(&random_mat(&mut rng, n), c);
+        let product = a.expm().mul_mat(&scaled(&a, -1.0).expm());
+        let error = product.sub(&FullMat::identity(n)).element_max_abs();
+        println!("n = {}, ||a||_1 = {}: error {:e}", n, a.norm1(), error);
+        assert!(error <= F64::from(1e-12));
+    }
+    // a nilpotent matrix has a finite series
+    let a = FullMat::from_vec(
+        3,
+        F64::map_vec(vec![0.0, 1.0, 2.0, 0.0, 0.0, 3.0, 0.0, 0.0, 0.0]),
+    );
+    let expected = FullMat::from_vec(
+        3,
+        F64::map_vec(vec![1.0, 1.0, 3.5, 0.0, 1.0, 3.0, 0.0, 0.0, 1.0]),
+    );
+    assert!(a.expm().sub(&expected).element_max_abs() <= F64::from(1e-15));
+    // A^2 overflows, the scaled one does not
+    let a = scaled(&FullMat::identity(3), -1e160);
+    assert!(a.expm().iter().all(|x| *x == F64::ZERO));
+    assert!(
+        FullMat::zeros(4, 4)
+            .expm()
+            .sub(&FullMat::identity(4))
+            .element_max_abs()
+            == F64::ZERO
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_expm_infinite_norm() {
+    // finite entries with a 1-norm that overflows
+    let mut a = FullMat::zeros(3, 3);
+    a[(1, 0)] = F64::from(1e308);
+    a[(2, 0)] = F64::from(1e308);
+    a.expm();
+}
+
+#[test]
+fn test_sqrtm() {
+    let mut rng = StdRng::seed_from_u64(490);
+    for n in [1, 2, 6, 15] {
+        // eigenvalues near 2n, some of them complex
+        let mut a = random_mat(&mut rng, n);
+        for i in 0..n {
+            a[(i, i)] += F64::from(2.0 * n as f64);
+        }
+        let root = a.sqrtm().unwrap();
+        let error = relative_error(&root.mul_mat(&root), &a);
+        println!("n = {}: error {:e}", n, error);
+        assert!(error <= F64::from(1e-14));
+    }
+
+    // the symmetric positive definite root of an SPD matrix
+    let spd = FullMat::pascal(5);
+    let eigen = spd.symmetric_eigen().unwrap();
+    let mut expected = FullMat::zeros(5, 5);
+    for (lambda, v) in eigen.eigenvalues.iter().zip(&eigen.eigenvectors) {
+        for i in 0..5 {
+            for j in 0..5 {
+                expected[(i, j)] += lambda.sqrt() * v[i] * v[j];
+            }
+        }
+    }
+    let root = spd.sqrtm().unwrap();
+    assert!(relative_error(&root, &expected) <= F64::from(1e-12));
+
+    // a rotation by pi / 2 has the rotation by pi / 4 as its principal root
+    let rotation = FullMat::from_vec(2, F64::map_vec(vec![0.0, -1.0, 1.0, 0.0]));
+    let h = 0.5f64.sqrt();
+    let expected = FullMat::from_vec(2, F64::map_vec(vec![h, -h, h, h]));
+    assert!(rotation.sqrtm().unwrap().sub(&expected).element_max_abs() <= F64::from(1e-15));
+
+    let negative = FullMat::from_vec(2, F64::map_vec(vec![-1.0, 0.0, 0.0, 4.0]));
+    assert!(negative.sqrtm().is_none());
+}
+
+#[test]
+fn test_logm() {
+    // [[1, -2], [2, 1]] = sqrt(5) times the rotation by atan2(2, 1)
+    let a = FullMat::from_vec(2, F64::map_vec(vec![1.0, -2.0, 2.0, 1.0]));
+    let (r, phi) = (5.0f64.sqrt().ln(), 2.0f64.atan2(1.0));
+    let expected = FullMat::from_vec(2, F64::map_vec(vec![r, -phi, phi, r]));
+    let log = a.logm().unwrap();
+    println!("logm(a) = \n{}", log);
+    assert!(log.sub(&expected).element_max_abs() <= F64::from(1e-15));
+
+    let mut rng = StdRng::seed_from_u64(4900);
+    for (n, c) in [(1, 1.0), (3, 0.5), (10, 1.0), (25, 2.0)] {
+        // exp has no trouble with the branch cut, and log(exp(B)) = B while
+        // the eigenvalues of B have imaginary parts in (-pi, pi)
+        let b = scaled(&random_mat(&mut rng, n), c / (n as f64).sqrt());
+        let log = b.expm().logm().unwrap();
+        let error = log.sub(&b).element_max_abs();
+        println!("n = {}: log(exp(b)) error {:e}", n, error);
+        assert!(error <= F64::from(1e-12));
+        let error = relative_error(&log.expm(), &b.expm());
+        assert!(error <= F64::from(1e-12));
+    }
+
+    // a Jordan block needs the off-diagonal entries -1/2, 1/3, ...
+    let mut jordan = FullMat::identity(4);
+    for i in 0..3 {
+        jordan[(i, i + 1)] = F64::ONE;
+    }
+    let log = jordan.logm().unwrap();
+    for (k, expected) in [1.0, -0.5, 1.0 / 3.0].into_iter().enumerate() {
+        assert!((log[(0, k + 1)] - F64::from(expected)).abs() <= F64::from(1e-14));
+    }
+    assert!(log[(0, 0)] == F64::ZERO);
+
+    // [[1, x], [0, 1]] has the logarithm [[0, x], [0, 0]], and x = 2^62 takes
+    // all 64 square roots
+    let x = 2.0f64.powi(62);
+    let shear = FullMat::from_vec(2, F64::map_vec(vec![1.0, x, 0.0, 1.0]));
+    let expected = FullMat::from_vec(2, F64::map_vec(vec![0.0, x, 0.0, 0.0]));
+    assert!(relative_error(&shear.logm().unwrap(), &expected) <= F64::from(1e-14));
+
+    let singular = FullMat::from_vec(2, F64::map_vec(vec![1.0, 2.0, 2.0, 4.0]));
+    assert!(singular.logm().is_none());
+    let negative = FullMat::from_vec(2, F64::map_vec(vec![-2.0, 1.0, 0.0, 3.0]));
+    assert!(negative.logm().is_none());
+}
+
+#[test]
+fn test_powm() {
+    let mut rng = StdRng::seed_from_u64(49000);
+    let n = 7;
+    let mut a = random_mat(&mut rng, n);
+    for i in 0..n {
+        a[(i, i)] += F64::from(3.0);
+    }
+
+    let mut power = FullMat::identity(n);
+    for k in 0..=9 {
+        let error = relative_error(&a.powi(k).unwrap(), &power);
+        assert!(error <= F64::from(1e-14));
+        assert!(relative_error(&a.powm(F64::from(k as f64)).unwrap(), &power) == error);
+        power = power.mul_mat(&a);
+    }
+    let inverse_cube = a.inv().unwrap().powi(3).unwrap();
+    assert!(relative_error(&a.powi(-3).unwrap(), &inverse_cube) <= F64::from(1e-13));
+    assert!(FullMat::zeros(2, 2).powi(-1).is_none());
+
+    let half = a.powm(F64::from(0.5)).unwrap();
+    assert!(relative_error(&half, &a.sqrtm().unwrap()) <= F64::from(1e-13));
+    let third = a.powm(F64::from(1.0 / 3.0)).unwrap();
+    let cube = third.mul_mat(&third).mul_mat(&third);
+    assert!(relative_error(&cube, &a) <= F64::from(1e-13));
+    // A^2.5 = A^2 sqrt(A) and A^-0.5 = sqrt(A)^-1
+    let expected = a.mul_mat(&a).mul_mat(&half);
+    assert!(relative_error(&a.powm(F64::from(2.5)).unwrap(), &expected) <= F64::from(1e-13));
+    let expected = half.inv().unwrap();
+    assert!(relative_error(&a.powm(F64::from(-0.5)).unwrap(), &expected) <= F64::from(1e-13));
+
+    let negative = FullMat::from_vec(2, F64::map_vec(vec![-1.0, 0.0, 0.0, 1.0]));
+    assert!(negative.powm(F64::from(2.0)).is_some() && negative.powm(F64::from(0.5)).is_none());
+}
+
+#[test]
+fn test_expm_multiply() {
+    let mut rng = StdRng::seed_from_u64(490000);
+    for (n, c, shift) in [
+        (1, 1.0, 0.0),
+        (6, 0.1, 0.0),
+        (12, 2.0, 0.0),
+        (30, 20.0, 0.0),
+        (30, 5.0, -60.0),
+    ] {
+        let mut a = scaled(&random_mat(&mut rng, n), c);
+        for i in 0..n {
+            a[(i, i)] += F64::from(shift);
+        }
+        let v: Vec<F64> = (0..n).map(|_| rng.gen_range(-1.0..1.0).into()).collect();
+        let expected = a.expm().mul_vec(&v);
+        let scale = expected.iter().fold(F64::ZERO, |max, x| max.max(x.abs()));
+        let error = max_diff(&a.expm_multiply(&v), &expected) / scale;
+        println!("n = {}, ||a||_1 = {}: error {:e}", n, a.norm1(), error);
+        assert!(error <= F64::from(1e-11));
+    }
+
+    // a stiff diagonal decays without overflow in between; the truncation is
+    // controlled relative to ||A||, so the slow component is good to about
+    // ||A|| eps
+    let a = FullMat::from_vec(2, F64::map_vec(vec![-1000.0, 0.0, 0.0, -1.0]));
+    let w = a.expm_multiply(&F64::map_vec(vec![1.0, 1.0]));
+    assert!(
+        w[0].abs() <= F64::from(1e-300)
+            && (w[1] - F64::from((-1.0f64).exp())).abs() <= F64::from(1e-13)
+    );
+    let zero = FullMat::zeros(3, 3);
+    let v = F64::map_vec(vec![1.0, 2.0, 3.0]);
+    assert!(zero.expm_multiply(&v) == v);
+}
@@ -0,0 +1,276 @@
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use floating_point::{Complex64, F64};
+use matrix::io::mtx::{
+    read_coo, read_coo_complex, read_full, read_full_complex, read_header, write_coo,
+    write_coo_complex, write_full, write_full_complex, MtxError, MtxField, MtxFormat, MtxHeader,
+    MtxSymmetry,
+};
+use matrix::{CooMat, FullMat, Matrix};
+
+fn fixture(name: &str) -> BufReader<File> {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", name]
+        .iter()
+        .collect();
+    BufReader::new(File::open(path).unwrap())
+}
+
+fn same(a: &FullMat<F64>, b: &FullMat<F64>) -> bool {
+    a.shape() == b.shape() && a.sub(b).element_max_abs() == F64::ZERO
+}
+
+fn same_complex(a: &FullMat<Complex64>, b: &FullMat<Complex64>) -> bool {
+    let (m, n) = a.shape();
+    a.shape() == b.shape() && (0..m).all(|i| (0..n).all(|j| a[(i, j)] == b[(i, j)]))
+}
+
+fn parse_error(text: &str) -> (usize, String) {
+    match read_full(text.as_bytes()) {
+        Err(MtxError::Parse(line, message)) => (line, message),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_read_symmetric_coordinate() {
+    let header = read_header(fixture("lap1d_5.mtx")).unwrap();
+    assert!(
+        header
+            == MtxHeader {
+                format: MtxFormat::Coordinate,
+                field: MtxField::Real,
+                symmetry: MtxSymmetry::Symmetric
+            }
+    );
+
+    let mat = read_full(fixture("lap1d_5.mtx")).unwrap();
+    println!("lap1d_5 = \n{}", mat);
+    assert!(mat.shape() == (5, 5));
+    assert!(mat[(2, 2)] == 2.0.into());
+    assert!(mat[(1, 2)] == (-1.0).into() && mat[(2, 1)] == (-1.0).into());
+    assert!(mat[(0, 4)] == F64::ZERO);
+
+    // the upper triangle is filled in for sparse loads as well
+    let coo = read_coo(fixture("lap1d_5.mtx")).unwrap();
+    assert!(coo.nnz() == 13);
+    assert!(same(&coo.to_dense(), &mat));
+    let csr = coo.to_csr();
+    assert!(csr.nnz() == 13);
+
+    let mut written = vec![];
+    write_coo(&mut written, &coo, MtxField::Real, MtxSymmetry::Symmetric).unwrap();
+    let text = String::from_utf8(written.clone()).unwrap();
+    assert!(text.starts_with("%%MatrixMarket matrix coordinate real symmetric\n5 5 9\n"));
+    assert!(same(&read_full(written.as_slice()).unwrap(), &mat));
+}
+
+#[test]
+fn test_read_skew_symmetric_integer() {
+    let mat = read_full(fixture("skew_4.mtx")).unwrap();
+    assert!(mat[(1, 0)] == 3.0.into() && mat[(0, 1)] == (-3.0).into());
+    assert!(mat[(3, 1)] == 7.0.into() && mat[(1, 3)] == (-7.0).into());
+    assert!(same(&mat.transpose(), &FullMat::zeros(4, 4).sub(&mat)));
+
+    let coo = read_coo(fixture("skew_4.mtx")).unwrap();
+    let mut written = vec![];
+    write_coo(
+        &mut written,
+        &coo,
+        MtxField::Integer,
+        MtxSymmetry::SkewSymmetric,
+    )
+    .unwrap();
+    let text = String::from_utf8(written.clone()).unwrap();
+    assert!(text.contains("\n4 2 7\n"));
+    assert!(same(&read_full(written.as_slice()).unwrap(), &mat));
+}
+
+#[test]
+fn test_read_hermitian_complex() {
+    let mat = read_full_complex(fixture("hermitian_3.mtx")).unwrap();
+    println!("hermitian_3 = \n{}", mat);
+    assert!(mat[(1, 0)] == Complex64::new(1.0, -2.0));
+    assert!(mat[(0, 1)] == Complex64::new(1.0, 2.0));
+    assert!(mat[(1, 2)] == Complex64::new(0.0, -1.5));
+    assert!(mat[(0, 2)] == Complex64::ZERO);
+
+    let mut written = vec![];
+    write_full_complex(&mut written, &mat, MtxSymmetry::Hermitian).unwrap();
+    let text = String::from_utf8(written.clone()).unwrap();
+    assert!(text.starts_with("%%MatrixMarket matrix array complex hermitian\n3 3\n"));
+    assert!(same_complex(
+        &read_full_complex(written.as_slice()).unwrap(),
+        &mat
+    ));
+
+    // complex data does not fit a real matrix
+    match read_full(fixture("hermitian_3.mtx")) {
+        Err(MtxError::Parse(1, message)) => assert!(message.contains("complex")),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(read_coo(fixture("hermitian_3.mtx")).is_err());
+}
+
+#[test]
+fn test_complex_coordinate_round_trip() {
+    let coo = read_coo_complex(fixture("hermitian_3.mtx")).unwrap();
+    // the two stored off-diagonal entries are mirrored
+    assert!(coo.shape() == (3, 3) && coo.nnz() == 7);
+    let dense = |coo: &CooMat<Complex64>| {
+        let mut mat = FullMat::from_vec(3, vec![Complex64::ZERO; 9]);
+        for (i, j, v) in coo.iter() {
+            mat[(i, j)] += v;
+        }
+        mat
+    };
+    let expected = read_full_complex(fixture("hermitian_3.mtx")).unwrap();
+    assert!(same_complex(&dense(&coo), &expected));
+
+    for symmetry in [MtxSymmetry::Hermitian, MtxSymmetry::General] {
+        let mut written = vec![];
+        write_coo_complex(&mut written, &coo, symmetry).unwrap();
+        let text = String::from_utf8(written.clone()).unwrap();
+        println!("{}", text);
+        let header = read_header(written.as_slice()).unwrap();
+        assert!(header.format == MtxFormat::Coordinate && header.field == MtxField::Complex);
+        assert!(header.symmetry == symmetry);
+        let read_back = read_coo_complex(written.as_slice()).unwrap();
+        assert!(read_back.nnz() == 7);
+        assert!(same_complex(&dense(&read_back), &expected));
+    }
+
+    // real files are promoted
+    let real = read_coo_complex(fixture("lap1d_5.mtx")).unwrap();
+    let expected = read_coo(fixture("lap1d_5.mtx")).unwrap();
+    assert!(real
+        .iter()
+        .zip(expected.iter())
+        .all(|(a, b)| (a.0, a.1) == (b.0, b.1) && a.2 == Complex64::new(b.2.to_f64(), 0.0)));
+}
+
+#[test]
+fn test_read_pattern() {
+    let coo = read_coo(fixture("pattern_4x5.mtx")).unwrap();
+    assert!(coo.shape() == (4, 5) && coo.nnz() == 6);
+    assert!(coo.iter().all(|(_, _, v)| v == F64::ONE));
+    let mat = coo.to_dense();
+    assert!(mat[(0, 4)] == F64::ONE && mat[(3, 3)] == F64::ONE && mat[(1, 0)] == F64::ZERO);
+
+    let mut written = vec![];
+    write_coo(&mut written, &coo, MtxField::Pattern, MtxSymmetry::General).unwrap();
+    let text = String::from_utf8(written.clone()).unwrap();
+    assert!(text.contains("\n1 5\n"));
+    assert!(same(&read_full(written.as_slice()).unwrap(), &mat));
+}
+
+#[test]
+fn test_read_array() {
+    let mat = read_full(fixture("array_3x2.mtx")).unwrap();
+    let expected = FullMat::from_rows(vec![
+        F64::map_vec(vec![1.5, 4.0]),
+        F64::map_vec(vec![-2.0, 0.0]),
+        F64::map_vec(vec![0.325, -600.0]),
+    ]);
+    assert!(same(&mat, &expected));
+    // array files keep explicit zeros
+    assert!(read_coo(fixture("array_3x2.mtx")).unwrap().nnz() == 6);
+
+    let mut written = vec![];
+    write_full(&mut written, &mat, MtxField::Real, MtxSymmetry::General).unwrap();
+    assert!(same(&read_full(written.as_slice()).unwrap(), &mat));
+
+    let symmetric = read_full(fixture("array_symmetric_3.mtx")).unwrap();
+    assert!(symmetric[(0, 2)] == 0.5.into() && symmetric[(2, 0)] == 0.5.into());
+    assert!(symmetric[(2, 1)] == (-1.0).into() && symmetric[(1, 2)] == (-1.0).into());
+    let mut written = vec![];
+    write_full(
+        &mut written,
+        &symmetric,
+        MtxField::Real,
+        MtxSymmetry::Symmetric,
+    )
+    .unwrap();
+    // six values for the lower triangle after the banner and the size line
+    assert!(String::from_utf8(written.clone()).unwrap().lines().count() == 8);
+    assert!(same(&read_full(written.as_slice()).unwrap(), &symmetric));
+
+    let complex = read_full_complex(fixture("array_complex_2.mtx")).unwrap();
+    assert!(complex[(0, 1)] == Complex64::new(0.0, -1.0));
+    assert!(complex[(1, 0)] == Complex64::new(2.0, 0.0));
+    let mut written = vec![];
+    write_full_complex(&mut written, &complex, MtxSymmetry::General).unwrap();
+    assert!(same_complex(
+        &read_full_complex(written.as_slice()).unwrap(),
+        &complex
+    ));
+
+    // real data loads into a complex matrix with zero imaginary parts
+    let promoted = read_full_complex(fixture("array_3x2.mtx")).unwrap();
+    assert!(promoted[(2, 1)] == Complex64::new(-600.0, 0.0));
+}
+
+#[test]
+fn test_round_trip_precision() {
+    let values = vec![
+        0.1,
+        1.0 / 3.0,
+        -2.5e-300,
+        1.7976931348623157e308,
+        std::f64::consts::PI,
+    ];
+    let mat = FullMat::from_vec(1, F64::map_vec(values));
+    let mut written = vec![];
+    write_full(&mut written, &mat, MtxField::Real, MtxSymmetry::General).unwrap();
+    assert!(same(&read_full(written.as_slice()).unwrap(), &mat));
+}
+
+#[test]
+fn test_parse_errors() {
+    let (line, message) = parse_error("%%MatrixMarket matrix coordinate real\n1 1 0\n");
+    assert!(line == 1 && message.contains("symmetry"));
+    let (line, _) = parse_error("% not a banner\n1 1 0\n");
+    assert!(line == 1);
+    let (line, message) = parse_error("%%MatrixMarket matrix array pattern general\n1 1\n");
+    assert!(line == 1 && message.contains("pattern"));
+    let (line, message) = parse_error("%%MatrixMarket matrix coordinate real hermitian\n1 1 0\n");
+    assert!(line == 1 && message.contains("complex"));
+
+    let (line, message) =
+        parse_error("%%MatrixMarket matrix coordinate real general\n% comment\n\n3 3\n");
+    assert!(line == 4 && message.contains("sizes"));
+    let (line, message) = parse_error("%%MatrixMarket matrix coordinate real symmetric\n3 2 0\n");
+    assert!(line == 2 && message.contains("square"));
+
+    let (line, message) =
+        parse_error("%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1.0\n1 2 x\n");
+    assert!(line == 4 && message.contains("'x'"));
+    let (line, message) = parse_error(
+        "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1.0\n% gap\n3 1 1.0\n",
+    );
+    assert!(line == 5 && message.contains("outside"));
+    let (line, message) =
+        parse_error("%%MatrixMarket matrix coordinate real general\n2 2 1\n1 1 1.0 2.0\n");
+    assert!(line == 3 && message.contains("value"));
+    let (line, message) =
+        parse_error("%%MatrixMarket matrix coordinate integer general\n2 2 1\n1 1 1.5\n");
+    assert!(line == 3 && message.contains("integer"));
+
+    let (line, message) =
+        parse_error("%%MatrixMarket matrix coordinate real symmetric\n2 2 1\n1 2 1.0\n");
+    assert!(line == 3 && message.contains("lower triangle"));
+    let (line, _) =
+        parse_error("%%MatrixMarket matrix coordinate integer skew-symmetric\n2 2 1\n1 1 1\n");
+    assert!(line == 3);
+
+    let (line, message) =
+        parse_error("%%MatrixMarket matrix coordinate real general\n2 2 3\n1 1 1.0\n2 2 1.0\n");
+    assert!(line == 4 && message.contains("expected 3 entries, found 2"));
+    let (line, message) =
+        parse_error("%%MatrixMarket matrix coordinate real general\n2 2 1\n1 1 1.0\n2 2 1.0\n");
+    assert!(line == 4 && message.contains("more entries"));
+    let (line, message) = parse_error("%%MatrixMarket matrix array real general\n2 1\n1.0\n");
+    assert!(line == 3 && message.contains("(2, 1)"));
+
+    let error = read_full("".as_bytes()).unwrap_err();
+    assert!(error.to_string() == "line 1: empty file");
+}
@@ -0,0 +1,165 @@
+use floating_point::{Complex64, F64};
+use matrix::{FullMat, Matrix};
+use rand::prelude::*;
+
+fn mat_max_diff(a: &FullMat<F64>, b: &FullMat<F64>) -> F64 {
+    assert!(a.shape() == b.shape());
+    let mut max = F64::ZERO;
+    for i in 0..a.row_count() {
+        for j in 0..a.col_count() {
+            max = max.max((a[(i, j)] - b[(i, j)]).abs());
+        }
+    }
+    max
+}
+
+fn random_mat(n: usize) -> FullMat<F64> {
+    let mut rng = rand::thread_rng();
+    FullMat::from_vec(
+        n,
+        (0..n * n)
+            .map(|_| F64::from(rng.gen::<f64>() - 0.5))
+            .collect(),
+    )
+}
+
+fn assert_eigen_pairs(mat: &FullMat<F64>, tol: f64) {
+    let n = mat.col_count();
+    let eigen = mat.eigen().unwrap();
+    assert!(eigen.eigenvalues.len() == n);
+
+    for (lambda, v) in eigen.eigenvalues.iter().zip(eigen.eigenvectors.iter()) {
+        let norm = v.iter().fold(F64::ZERO, |acc, c| acc.hypot(c.abs()));
+        assert!((norm - F64::ONE).abs() < 1e-14.into());
+        for i in 0..n {
+            let av: Complex64 = (0..n)
+                .map(|j| Complex64::from((mat[(i, j)], F64::ZERO)) * v[j])
+                .sum();
+            assert!(
+                (av - *lambda * v[i]).abs() < tol.into(),
+                "residual = {:e}",
+                (av - *lambda * v[i]).abs()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_hessenberg() {
+    let mat = random_mat(6);
+    let (q, h) = mat.hessenberg();
+    for i in 0..6_usize {
+        for j in 0..i.saturating_sub(1) {
+            assert!(h[(i, j)] == F64::ZERO);
+        }
+    }
+    assert!(mat_max_diff(&q.transpose().mul_mat(&q), &FullMat::identity(6)) < 1e-14.into());
+    assert!(mat_max_diff(&q.mul_mat(&h).mul_mat(&q.transpose()), &mat) < 1e-14.into());
+}
+
+#[test]
+fn test_schur() {
+    for n in [1, 2, 3, 7, 15] {
+        let mat = random_mat(n);
+        let schur = mat.schur().unwrap();
+        let (z, t) = (&schur.z, &schur.t);
+        assert!(mat_max_diff(&z.transpose().mul_mat(z), &FullMat::identity(n)) < 1e-13.into());
+        assert!(mat_max_diff(&z.mul_mat(t).mul_mat(&z.transpose()), &mat) < 1e-13.into());
+
+        // quasi upper triangular with no two consecutive nonzero subdiagonals
+        for i in 0..n {
+            for j in 0..i.saturating_sub(1) {
+                assert!(t[(i, j)] == F64::ZERO);
+            }
+        }
+        for i in 1..n.saturating_sub(1) {
+            assert!(t[(i, i - 1)] == F64::ZERO || t[(i + 1, i)] == F64::ZERO);
+        }
+    }
+}
+
+#[test]
+fn test_rotation_eigenvalues() {
+    // rotation by 90 degrees in the plane combined with a stretch along z
+    let mat = FullMat::from_vec(3, F64::map_vec(vec![0., -1., 0., 1., 0., 0., 0., 0., 2.]));
+    let mut values = mat.eigenvalues().unwrap();
+    values.sort_by(|a, b| a.imag.partial_cmp(&b.imag).unwrap());
+    println!("eigenvalues = {:?}", values);
+
+    let expected = [
+        Complex64::new(0., -1.),
+        Complex64::new(2., 0.),
+        Complex64::new(0., 1.),
+    ];
+    for (x, y) in values.iter().zip(expected.iter()) {
+        assert!((*x - *y).abs() < 1e-15.into());
+    }
+    assert_eigen_pairs(&mat, 1e-15);
+}
+
+#[test]
+fn test_random_eigen_pairs() {
+    for n in [1, 2, 4, 9, 20] {
+        assert_eigen_pairs(&random_mat(n), 1e-12);
+    }
+}
+
+#[test]
+fn test_companion_matrix() {
+    // companion matrix of (x - 1)(x - 2)(x - 3)(x^2 + 1)
+    let coes = [-6., 11., -12., 12., -6.];
+    let mut mat = FullMat::zeros(5, 5);
+    for i in 1..5 {
+        mat[(i, i - 1)] = F64::ONE;
+    }
+    for (i, c) in coes.iter().enumerate() {
+        mat[(i, 4)] = F64::from(-*c);
+    }
+
+    let values = mat.eigenvalues().unwrap();
+    for root in [
+        Complex64::new(1., 0.),
+        Complex64::new(2., 0.),
+        Complex64::new(3., 0.),
+        Complex64::new(0., 1.),
+        Complex64::new(0., -1.),
+    ] {
+        assert!(values.iter().any(|x| (*x - root).abs() < 1e-12.into()));
+    }
+    assert_eigen_pairs(&mat, 1e-12);
+}
+
+#[test]
+fn test_balancing() {
+    // badly scaled similarity transform of diag(1, 2, 3)
+    let d = [1e-6, 1.0, 1e6];
+    let mut mat = FullMat::from_vec(3, F64::map_vec(vec![1., 1., 1., 0., 2., 1., 0., 0., 3.]));
+    for i in 0..3 {
+        for j in 0..3 {
+            mat[(i, j)] *= F64::from(d[i] / d[j]);
+        }
+    }
+
+    for balanced in [false, true] {
+        let eigen = mat.eigen_with(balanced).unwrap();
+        for root in [1., 2., 3.] {
+            assert!(eigen
+                .eigenvalues
+                .iter()
+                .any(|x| (*x - Complex64::new(root, 0.)).abs() < 1e-9.into()));
+        }
+    }
+    assert_eigen_pairs(&mat, 1e-9);
+}
+
+#[test]
+fn test_non_finite() {
+    for x in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let mut mat = random_mat(4);
+        mat[(1, 2)] = x.into();
+        assert!(mat.eigen().is_none() && mat.eigen_with(false).is_none());
+        assert!(mat.eigenvalues().is_none() && mat.schur().is_none());
+    }
+    let mat = FullMat::from_vec(2, F64::map_vec(vec![1.0, f64::INFINITY, 2.0, 3.0]));
+    assert!(mat.eigen().is_none());
+}
@@ -0,0 +1,160 @@
+use floating_point::F64;
+use matrix::{vector_norm1, vector_norm2, vector_norm_inf, vector_norm_p, FullMat};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn random_mat(rng: &mut StdRng, m: usize, n: usize) -> FullMat<F64> {
+    FullMat::from_vec(
+        n,
+        (0..(m * n))
+            .map(|_| rng.gen_range(-1.0..1.0).into())
+            .collect(),
+    )
+}
+
+fn close(a: F64, b: F64, relative: f64) -> bool {
+    (a - b).abs() <= F64::from(relative) * b.abs()
+}
+
+#[test]
+fn test_vector_norms() {
+    let x = F64::map_vec(vec![3., -4., 0., 1.]);
+    assert!(vector_norm1(&x) == 8.0.into());
+    assert!(close(vector_norm2(&x), F64::from(26f64.sqrt()), 1e-15));
+    assert!(vector_norm_inf(&x) == 4.0.into());
+    assert!(close(vector_norm_p(&x, F64::ONE), F64::from(8.0), 1e-15));
+    assert!(close(
+        vector_norm_p(&x, F64::from(2.0)),
+        vector_norm2(&x),
+        1e-15
+    ));
+    assert!(close(
+        vector_norm_p(&x, F64::from(3.0)),
+        F64::from(92f64.cbrt()),
+        1e-15
+    ));
+    assert!(vector_norm_p(&x, F64::from(f64::INFINITY)) == 4.0.into());
+
+    // p-norms decrease towards the infinity norm
+    let p_norms: Vec<F64> = [1., 1.5, 2., 4., 10., 100.]
+        .iter()
+        .map(|p| vector_norm_p(&x, F64::from(*p)))
+        .collect();
+    assert!(p_norms.windows(2).all(|w| w[0] >= w[1]));
+    assert!(close(p_norms[5], F64::from(4.0), 1e-2));
+
+    // no overflow for huge entries
+    let huge = F64::map_vec(vec![3e200, 4e200]);
+    assert!(close(vector_norm2(&huge), F64::from(5e200), 1e-15));
+    assert!(close(
+        vector_norm_p(&huge, F64::from(3.0)),
+        F64::from(91f64.cbrt() * 1e200),
+        1e-15
+    ));
+
+    let zero = vec![F64::ZERO; 3];
+    assert!(vector_norm_p(&zero, F64::from(2.5)) == F64::ZERO);
+}
+
+#[test]
+fn test_matrix_norms() {
+    let a = FullMat::from_rows(vec![
+        F64::map_vec(vec![1., -2., 3.]),
+        F64::map_vec(vec![-4., 5., -6.]),
+    ]);
+    assert!(a.norm1() == 9.0.into());
+    assert!(a.norm() == a.norm1());
+    assert!(a.norm_inf() == 15.0.into());
+    assert!(close(a.norm_frobenius(), F64::from(91f64.sqrt()), 1e-15));
+    // the largest entry in magnitude is negative
+    assert!(a.element_max_abs() == 6.0.into());
+
+    // ||A||_1 = ||A^T||_inf and the usual inequalities with the 2-norm
+    let mut rng = StdRng::seed_from_u64(40);
+    for (m, n) in [(1, 1), (5, 3), (3, 7), (10, 10)] {
+        let a = random_mat(&mut rng, m, n);
+        assert!(a.norm1() == a.transpose().norm_inf());
+        let norm2 = a.norm2().unwrap();
+        assert!(norm2 <= a.norm_frobenius() * F64::from(1.0 + 1e-14));
+        assert!(
+            a.norm_frobenius()
+                <= F64::from((m.min(n) as f64).sqrt()) * norm2 * F64::from(1.0 + 1e-14)
+        );
+        assert!(norm2 * norm2 <= a.norm1() * a.norm_inf() * F64::from(1.0 + 1e-14));
+        assert!(a.element_max_abs() <= norm2);
+    }
+}
+
+#[test]
+fn test_condition_numbers() {
+    let a = FullMat::from_rows(vec![F64::map_vec(vec![1., 2.]), F64::map_vec(vec![3., 4.])]);
+    // A^-1 = [[-2, 1], [1.5, -0.5]]
+    assert!(close(a.cond1(), F64::from(6.0 * 3.5), 1e-14));
+    assert!(close(a.cond_inf(), F64::from(7.0 * 3.0), 1e-14));
+
+    let mut rng = StdRng::seed_from_u64(400);
+    for n in [1, 2, 5, 20, 50] {
+        let a = random_mat(&mut rng, n, n);
+        let (cond1, estimate1) = (a.cond1(), a.cond1_estimate());
+        let (cond_inf, estimate_inf) = (a.cond_inf(), a.cond_inf_estimate());
+        println!(
+            "n = {}: cond1 = {:e} (estimate {:e}), cond_inf = {:e} (estimate {:e})",
+            n, cond1, estimate1, cond_inf, estimate_inf
+        );
+        assert!(estimate1 <= cond1 * F64::from(1.0 + 1e-10));
+        assert!(estimate1 >= cond1 / F64::from(3.0));
+        assert!(estimate_inf <= cond_inf * F64::from(1.0 + 1e-10));
+        assert!(estimate_inf >= cond_inf / F64::from(3.0));
+
+        // all condition numbers agree up to a factor of n
+        let cond2 = a.cond2().unwrap();
+        assert!(cond1 <= F64::from(n as f64) * cond2 * F64::from(1.0 + 1e-10));
+        assert!(cond2 <= F64::from(n as f64) * cond1 * F64::from(1.0 + 1e-10));
+    }
+
+    // hilbert(n) has cond ~ e^(3.5 n); the estimates are exact here
+    for n in [4, 6, 8] {
+        let hilbert = FullMat::hilbert(n);
+        let exact = FullMat::inverse_hilbert(n).norm1() * hilbert.norm1();
+        assert!(close(hilbert.cond1(), exact, 1e-4));
+        assert!(close(hilbert.cond1_estimate(), exact, 1e-4));
+        assert!(close(hilbert.cond_inf_estimate(), exact, 1e-4));
+    }
+
+    let singular = FullMat::from_rows(vec![F64::map_vec(vec![1., 2.]), F64::map_vec(vec![2., 4.])]);
+    assert!(!singular.cond1().is_finite());
+    assert!(!singular.cond_inf().is_finite());
+    assert!(!singular.cond1_estimate().is_finite());
+    assert!(!singular.cond_inf_estimate().is_finite());
+}
+
+#[test]
+fn test_error_magnification() {
+    // relative forward error / relative backward error <= cond_inf(A), as in
+    // the Hilbert experiment of chapter 2
+    for n in [6, 10] {
+        let hilbert = FullMat::hilbert(n);
+        let x = vec![F64::ONE; n];
+        let b = hilbert.mul_vec(&x);
+        let x_a = hilbert.lu_solve(&b).unwrap();
+
+        let residual: Vec<F64> = b
+            .iter()
+            .zip(hilbert.mul_vec(&x_a))
+            .map(|(b, ax)| *b - ax)
+            .collect();
+        let error: Vec<F64> = x.iter().zip(x_a.iter()).map(|(x, y)| *x - *y).collect();
+        let forward = vector_norm_inf(&error) / vector_norm_inf(&x);
+        let backward = vector_norm_inf(&residual) / vector_norm_inf(&b);
+        let cond = hilbert.cond_inf_estimate();
+        println!(
+            "n = {}: forward {:e}, backward {:e}, magnification {:e}, cond_inf {:e}",
+            n,
+            forward,
+            backward,
+            forward / backward,
+            cond
+        );
+        assert!(forward / backward <= cond);
+        assert!(forward <= F64::from(10.0) * cond * F64::EPSILON);
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    fs::File,
+    io::{BufReader, Cursor},
+    path::PathBuf,
+};
+
+use fft::{RecursiveBisectionFFT, DFT};
+use floating_point::{Complex64, F64};
+use matrix::io::npy::{
+    read_complex_vec, read_full, read_full_complex, read_header, write_complex_vec, write_full,
+    write_full_complex, NpyDtype, NpyError, NpyHeader, NpzReader, NpzWriter,
+};
+use matrix::{FullMat, Matrix};
+
+fn fixture_path(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", name]
+        .iter()
+        .collect()
+}
+
+fn fixture(name: &str) -> BufReader<File> {
+    BufReader::new(File::open(fixture_path(name)).unwrap())
+}
+
+fn same_complex(a: &FullMat<Complex64>, b: &FullMat<Complex64>) -> bool {
+    let (m, n) = a.shape();
+    a.shape() == b.shape() && (0..m).all(|i| (0..n).all(|j| a[(i, j)] == b[(i, j)]))
+}
+
+fn real_2x3() -> FullMat<F64> {
+    FullMat::from_rows(vec![
+        F64::map_vec(vec![1.0, 2.5, -3.0]),
+        F64::map_vec(vec![4.0, 0.1, 6e-300]),
+    ])
+}
+
+// A version 1 file with the given header dict and data.
+fn npy_bytes(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    bytes.extend_from_slice(&(dict.len() as u16 + 1).to_le_bytes());
+    bytes.extend_from_slice(dict.as_bytes());
+    bytes.push(b'\n');
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+fn format_error(bytes: &[u8]) -> String {
+    match read_full_complex(bytes) {
+        Err(NpyError::Format(message)) => message,
+        other => panic!("expected a format error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_read_real() {
+    let header = read_header(fixture("real_2x3.npy")).unwrap();
+    assert!(
+        header
+            == NpyHeader {
+                dtype: NpyDtype::Float64,
+                big_endian: false,
+                fortran_order: false,
+                shape: vec![2, 3]
+            }
+    );
+
+    let mat = read_full(fixture("real_2x3.npy")).unwrap();
+    println!("real_2x3 = \n{}", mat);
+    assert!(mat.shape() == (2, 3));
+    assert!(mat.sub(&real_2x3()).element_max_abs() == F64::ZERO);
+
+    // the writer produces the same bytes as NumPy, header padding included
+    let mut written = vec![];
+    write_full(&mut written, &mat).unwrap();
+    assert!(written == std::fs::read(fixture_path("real_2x3.npy")).unwrap());
+
+    let promoted = read_full_complex(fixture("real_2x3.npy")).unwrap();
+    assert!(promoted[(1, 2)] == Complex64::new(6e-300, 0.0));
+}
+
+#[test]
+fn test_read_complex_fortran_big_endian() {
+    // a version 2 header with column-major big-endian data, which stays
+    // column-major when read and written back
+    let header = read_header(fixture("complex_2x2_fortran_be.npy")).unwrap();
+    assert!(header.dtype == NpyDtype::Complex128);
+    assert!(header.big_endian && header.fortran_order);
+
+    let mat = read_full_complex(fixture("complex_2x2_fortran_be.npy")).unwrap();
+    println!("complex_2x2 = \n{}", mat);
+    assert!(mat[(0, 0)] == Complex64::new(1.0, 2.0));
+    assert!(mat[(0, 1)] == Complex64::new(0.0, -1.0));
+    assert!(mat[(1, 0)] == Complex64::new(3.0, 0.0));
+    assert!(mat[(1, 1)] == Complex64::new(4.5, -0.5));
+
+    let mut written = vec![];
+    write_full_complex(&mut written, &mat).unwrap();
+    let header = read_header(written.as_slice()).unwrap();
+    assert!(!header.big_endian && header.fortran_order);
+    assert!(same_complex(
+        &read_full_complex(written.as_slice()).unwrap(),
+        &mat
+    ));
+
+    match read_full(fixture("complex_2x2_fortran_be.npy")) {
+        Err(NpyError::Format(message)) => assert!(message.contains("complex")),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_fft_buffer() {
+    let signal = read_complex_vec(fixture("signal_8.npy")).unwrap();
+    assert!(signal.len() == 8);
+
+    // exp(2 pi i k / 8) is a single frequency
+    let spectrum = RecursiveBisectionFFT::fourier_transform(&signal);
+    for (k, v) in spectrum.iter().enumerate() {
+        let expected = if k == 1 { 8.0 } else { 0.0 };
+        assert!((*v - Complex64::new(expected, 0.0)).abs() < F64::from(1e-14));
+    }
+
+    let mut written = vec![];
+    write_complex_vec(&mut written, &spectrum).unwrap();
+    assert!(read_header(written.as_slice()).unwrap().shape == vec![8]);
+    assert!(read_complex_vec(written.as_slice()).unwrap() == spectrum);
+
+    // matrices are not vectors and the other way round
+    assert!(read_complex_vec(fixture("real_2x3.npy")).is_err());
+    assert!(format_error(&written).contains("shape (8,)"));
+}
+
+#[test]
+fn test_round_trip_precision() {
+    let values = vec![
+        0.1,
+        1.0 / 3.0,
+        -2.5e-300,
+        1.7976931348623157e308,
+        std::f64::consts::PI,
+        -0.0,
+    ];
+    let mat = FullMat::from_vec(2, F64::map_vec(values));
+    let mut written = vec![];
+    write_full(&mut written, &mat).unwrap();
+    // the data starts at a multiple of 64 bytes
+    assert!((written.len() - 6 * 8) % 64 == 0);
+    let read = read_full(written.as_slice()).unwrap();
+    assert!(read.shape() == (3, 2));
+    assert!(
+        (0..3)
+            .all(|i| (0..2)
+                .all(|j| read[(i, j)].to_f64().to_bits() == mat[(i, j)].to_f64().to_bits()))
+    );
+}
+
+#[test]
+fn test_npz() {
+    // an archive with deflated members, as numpy.savez_compressed writes
+    let mut archive = NpzReader::new(fixture("arrays.npz")).unwrap();
+    assert!(archive.names() == vec!["a", "signal"]);
+    let a = archive.read_full("a").unwrap();
+    assert!(a.sub(&real_2x3()).element_max_abs() == F64::ZERO);
+    let signal = archive.read_complex_vec("signal.npy").unwrap();
+    assert!(signal == read_complex_vec(fixture("signal_8.npy")).unwrap());
+    match archive.read_full("b") {
+        Err(NpyError::Format(message)) => assert!(message.contains("'b'")),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    let complex = read_full_complex(fixture("complex_2x2_fortran_be.npy")).unwrap();
+    for compressed in [false, true] {
+        let buffer = Cursor::new(vec![]);
+        let mut writer = if compressed {
+            NpzWriter::compressed(buffer)
+        } else {
+            NpzWriter::new(buffer)
+        };
+        writer.write_full("a", &a).unwrap();
+        writer.write_full_complex("z", &complex).unwrap();
+        writer.write_complex_vec("signal", &signal).unwrap();
+        let buffer = writer.finish().unwrap();
+
+        let mut archive = NpzReader::new(buffer).unwrap();
+        assert!(archive.names() == vec!["a", "signal", "z"]);
+        assert!(archive.read_full("a").unwrap().sub(&a).element_max_abs() == F64::ZERO);
+        assert!(same_complex(
+            &archive.read_full_complex("z").unwrap(),
+            &complex
+        ));
+        assert!(archive.read_complex_vec("signal").unwrap() == signal);
+    }
+
+    assert!(matches!(
+        NpzReader::new(fixture("real_2x3.npy")),
+        Err(NpyError::Zip(_))
+    ));
+}
+
+#[test]
+fn test_errors() {
+    let error = read_full(fixture("int_3.npy")).unwrap_err();
+    println!("{}", error);
+    assert!(matches!(&error, NpyError::UnsupportedDtype(descr) if descr == "'<i8'"));
+    assert!(error.to_string().contains("'<i8'"));
+
+    for descr in ["<f4", "|b1", "<M8[ns]", "<U3"] {
+        let dict = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': (1,), }}",
+            descr
+        );
+        assert!(matches!(
+            read_header(npy_bytes(&dict, &[]).as_slice()),
+            Err(NpyError::UnsupportedDtype(_))
+        ));
+    }
+    // structured arrays have a list as their dtype
+    let dict = "{'descr': [('x', '<f8'), ('y', '<f8')], 'fortran_order': False, 'shape': (2,), }";
+    let error = read_header(npy_bytes(dict, &[]).as_slice()).unwrap_err();
+    assert!(error.to_string().contains("[('x', '<f8'), ('y', '<f8')]"));
+
+    // key order and quoting are free, the trailing comma is optional
+    let dict = "{\"shape\": (1, 1), \"fortran_order\": True, \"descr\": \">f8\"}";
+    let mat = read_full(npy_bytes(dict, &2.0f64.to_be_bytes()).as_slice()).unwrap();
+    assert!(mat[(0, 0)] == 2.0.into());
+
+    let dict = "{'descr': '<f8', 'fortran_order': False, 'shape': (2, 2), }";
+    assert!(format_error(&npy_bytes(dict, &[0; 24])).contains("ends before the 4 values"));
+    let dict = "{'descr': '<f8', 'fortran_order': False, 'shape': (0, 3), }";
+    assert!(format_error(&npy_bytes(dict, &[])).contains("nonempty 2-D"));
+    let dict = "{'descr': '<f8', 'fortran_order': False, 'shape': (), }";
+    assert!(format_error(&npy_bytes(dict, &[0; 8])).contains("shape ()"));
+    let dict = "{'descr': '<f8', 'shape': (1, 1), }";
+    assert!(format_error(&npy_bytes(dict, &[0; 8])).contains("'fortran_order'"));
+    let dict = "{'descr': '<f8', 'fortran_order': 0, 'shape': (1, 1), }";
+    assert!(format_error(&npy_bytes(dict, &[0; 8])).contains("unexpected value"));
+
+    let mut bytes = npy_bytes("{}", &[]);
+    bytes[0] = b'N';
+    assert!(format_error(&bytes).contains("magic"));
+    let mut bytes = npy_bytes("{}", &[]);
+    bytes[6] = 4;
+    assert!(format_error(&bytes).contains("version 4.0"));
+    assert!(matches!(
+        read_full_complex(&b"\x93NUM"[..]),
+        Err(NpyError::Io(_))
+    ));
+}
+
+#[test]
+fn test_malformed_shape() {
+    // the element count overflows
+    let dict = "{'descr': '<f8', 'fortran_order': False, 'shape': (4294967296, 4294967296), }";
+    let message = format_error(&npy_bytes(dict, &[0; 8]));
+    println!("{}", message);
+    assert!(message.contains("too large"));
+    let dict = "{'descr': '<c16', 'fortran_order': False, 'shape': (1152921504606846976, 1), }";
+    assert!(format_error(&npy_bytes(dict, &[0; 16])).contains("too large"));
+
+    // a huge but representable shape only reads what is there
+    let dict = "{'descr': '<f8', 'fortran_order': False, 'shape': (1099511627776, 1), }";
+    assert!(format_error(&npy_bytes(dict, &[0; 16])).contains("ends before"));
+
+    // as does a huge header length
+    let mut bytes = b"\x93NUMPY\x02\x00".to_vec();
+    bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+    bytes.extend_from_slice(b"{'descr': '<f8', ");
+    assert!(format_error(&bytes).contains("inside the header"));
+}
@@ -0,0 +1,294 @@
+use floating_point::F64;
+use matrix::{
+    bicgstab, conjugate_gradient, gmres, BlockJacobiPreconditioner, CooMat, CsrMat, FullMat,
+    GmresOptions, Ic0Preconditioner, IdentityPreconditioner, Ilu0Preconditioner,
+    IlutPreconditioner, JacobiPreconditioner, Preconditioner, PreconditionerError,
+    SsorPreconditioner, StoppingCriterion,
+};
+
+fn vec_max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x - *y).abs())
+        .fold(0.0.into(), |max, x| if max < x { x } else { max })
+}
+
+// five-point operator on a k x k grid with a diffusion coefficient varying
+// over several orders of magnitude and upwind convection
+fn grid_operator(k: usize, convection: f64) -> CsrMat<F64> {
+    let n = k * k;
+    let mut coo = CooMat::new(n, n);
+    for i in 0..k {
+        for j in 0..k {
+            let p = i * k + j;
+            let c = 1.0 + 100.0 * ((i * j) as f64 / (k * k) as f64);
+            coo.push(p, p, F64::from(c * 4.0 + convection));
+            if i > 0 {
+                coo.push(p, p - k, F64::from(-c - convection));
+            }
+            if i + 1 < k {
+                coo.push(p, p + k, F64::from(-c));
+            }
+            if j > 0 {
+                coo.push(p, p - 1, F64::from(-c));
+            }
+            if j + 1 < k {
+                coo.push(p, p + 1, F64::from(-c));
+            }
+        }
+    }
+    let a = coo.to_csr();
+    if convection == 0.0 {
+        // symmetrize the coefficient for the SPD tests
+        let at = a.transpose();
+        let mut sym = CooMat::new(n, n);
+        for (i, j, v) in a.iter().chain(at.iter()) {
+            sym.push(i, j, v * F64::from(0.5));
+        }
+        return sym.to_csr();
+    }
+    a
+}
+
+fn tridiagonal(n: usize) -> FullMat<F64> {
+    let mut mat = FullMat::zeros(n, n);
+    for i in 0..n {
+        mat[(i, i)] = F64::from(3.0 + i as f64);
+        if i > 0 {
+            mat[(i, i - 1)] = F64::from(-1.0);
+            mat[(i - 1, i)] = F64::from(-1.5);
+        }
+    }
+    mat
+}
+
+#[test]
+fn test_dense_and_sparse_agree() {
+    let a = grid_operator(6, 2.0);
+    let dense = a.to_dense();
+    let r: Vec<F64> = (0..36).map(|i| F64::from((i as f64).sin())).collect();
+
+    let pairs: Vec<(Box<dyn Preconditioner>, Box<dyn Preconditioner>)> = vec![
+        (
+            Box::new(JacobiPreconditioner::new(&a).unwrap()),
+            Box::new(JacobiPreconditioner::new(&dense).unwrap()),
+        ),
+        (
+            Box::new(BlockJacobiPreconditioner::new(&a, 5).unwrap()),
+            Box::new(BlockJacobiPreconditioner::new(&dense, 5).unwrap()),
+        ),
+        (
+            Box::new(SsorPreconditioner::new(&a, F64::from(1.2)).unwrap()),
+            Box::new(SsorPreconditioner::new(&dense, F64::from(1.2)).unwrap()),
+        ),
+        (
+            Box::new(Ilu0Preconditioner::new(&a).unwrap()),
+            Box::new(Ilu0Preconditioner::new(&dense).unwrap()),
+        ),
+        (
+            Box::new(IlutPreconditioner::new(&a, F64::from(1e-3), 8).unwrap()),
+            Box::new(IlutPreconditioner::new(&dense, F64::from(1e-3), 8).unwrap()),
+        ),
+    ];
+    for (sparse, full) in pairs.iter() {
+        let z = sparse.solve(&r);
+        assert!(vec_max_diff(&z, &full.solve(&r)) < 1e-14.into());
+        assert!(z.iter().all(|x| x.is_finite()));
+    }
+
+    let sym = grid_operator(6, 0.0);
+    let z = Ic0Preconditioner::new(&sym).unwrap().solve(&r);
+    let zd = Ic0Preconditioner::new(&sym.to_dense()).unwrap().solve(&r);
+    assert!(vec_max_diff(&z, &zd) < 1e-14.into());
+}
+
+#[test]
+fn test_simple_preconditioners() {
+    let a = tridiagonal(7);
+    let r = vec![F64::ONE; 7];
+
+    let z = JacobiPreconditioner::new(&a).unwrap().solve(&r);
+    assert!((z[2] - F64::from(0.2)).abs() < 1e-15.into());
+
+    // a single block is the whole matrix, blocks of one are Jacobi
+    let whole = BlockJacobiPreconditioner::new(&a, 7).unwrap();
+    assert!(vec_max_diff(&a.mul_vec(&whole.solve(&r)), &r) < 1e-14.into());
+    let single = BlockJacobiPreconditioner::new(&a, 1).unwrap();
+    assert!(vec_max_diff(&single.solve(&r), &z) < 1e-15.into());
+
+    // M z = r for the explicitly formed SSOR matrix
+    let omega = F64::from(1.3);
+    let n = 7;
+    let mut lower = FullMat::zeros(n, n);
+    let mut upper = FullMat::zeros(n, n);
+    let mut inv_d = FullMat::zeros(n, n);
+    for i in 0..n {
+        inv_d[(i, i)] = F64::ONE / a[(i, i)];
+        for j in 0..n {
+            if j < i {
+                lower[(i, j)] = omega * a[(i, j)];
+            } else if j > i {
+                upper[(i, j)] = omega * a[(i, j)];
+            } else {
+                lower[(i, j)] = a[(i, j)];
+                upper[(i, j)] = a[(i, j)];
+            }
+        }
+    }
+    let m = lower.mul_mat(&inv_d).mul_mat(&upper);
+    let scale = omega * (F64::from(2.0) - omega);
+    let z = SsorPreconditioner::new(&a, omega).unwrap().solve(&r);
+    let mz: Vec<F64> = m.mul_vec(&z).into_iter().map(|x| x / scale).collect();
+    assert!(vec_max_diff(&mz, &r) < 1e-14.into());
+}
+
+#[test]
+fn test_incomplete_factorizations_match_pattern() {
+    let a = grid_operator(8, 3.0);
+    let ilu = Ilu0Preconditioner::new(&a).unwrap();
+    let lu = ilu.l().to_dense().mul_mat(&ilu.u().to_dense());
+    assert!(ilu.l().nnz() + ilu.u().nnz() == a.nnz() + 64);
+    for (i, j, v) in a.iter() {
+        assert!((lu[(i, j)] - v).abs() < 1e-12.into());
+    }
+
+    let sym = grid_operator(8, 0.0);
+    let ic = Ic0Preconditioner::new(&sym).unwrap();
+    let l = ic.l().to_dense();
+    let llt = l.mul_mat(&l.transpose());
+    assert!(ic.l().nnz() == (sym.nnz() + 64) / 2);
+    for (i, j, v) in sym.iter() {
+        assert!((llt[(i, j)] - v).abs() < 1e-12.into());
+    }
+
+    // without dropping, ILUT is the exact LU factorization
+    let full = IlutPreconditioner::new(&a, F64::ZERO, 64).unwrap();
+    let b = vec![F64::ONE; 64];
+    assert!(vec_max_diff(&a.mul_vec(&full.solve(&b)), &b) < 1e-12.into());
+    assert!(full.l().nnz() > ilu.l().nnz());
+
+    // and the fill limit bounds every row of the factors
+    let sparse = IlutPreconditioner::new(&a, F64::from(1e-4), 2).unwrap();
+    let (l, u) = (sparse.l().as_csc().to_csr(), sparse.u().as_csc().to_csr());
+    for i in 0..64 {
+        assert!(l.row(i).count() <= 3 && u.row(i).count() <= 3);
+    }
+
+    // tridiagonal matrices have no fill, so ILU(0) and IC(0) are exact
+    let t = tridiagonal(10);
+    let r: Vec<F64> = (0..10).map(|i| F64::from(i as f64)).collect();
+    let z = Ilu0Preconditioner::new(&t).unwrap().solve(&r);
+    assert!(vec_max_diff(&t.mul_vec(&z), &r) < 1e-13.into());
+    let spd = t.add(&t.transpose());
+    let z = Ic0Preconditioner::new(&spd).unwrap().solve(&r);
+    assert!(vec_max_diff(&spd.mul_vec(&z), &r) < 1e-13.into());
+}
+
+#[test]
+fn test_preconditioned_krylov() {
+    let k = 20;
+    let n = k * k;
+    let x0 = vec![F64::ZERO; n];
+    let criterion = StoppingCriterion {
+        max_iterations: 2000,
+        ..Default::default()
+    };
+
+    let sym = grid_operator(k, 0.0);
+    let expected: Vec<F64> = (0..n).map(|i| F64::from((i as f64 * 0.1).cos())).collect();
+    let b = sym.mul_vec(&expected);
+    let plain = conjugate_gradient(&sym, &b, &x0, &IdentityPreconditioner, &criterion);
+    let jacobi = JacobiPreconditioner::new(&sym).unwrap();
+    let jacobi = conjugate_gradient(&sym, &b, &x0, &jacobi, &criterion);
+    let ssor = SsorPreconditioner::new(&sym, F64::from(1.5)).unwrap();
+    let ssor = conjugate_gradient(&sym, &b, &x0, &ssor, &criterion);
+    let ic0 = Ic0Preconditioner::new(&sym).unwrap();
+    let ic0 = conjugate_gradient(&sym, &b, &x0, &ic0, &criterion);
+    println!(
+        "cg: plain {}, jacobi {}, ssor {}, ic0 {}",
+        plain.iterations, jacobi.iterations, ssor.iterations, ic0.iterations
+    );
+    for result in [&plain, &jacobi, &ssor, &ic0] {
+        assert!(result.converged());
+        assert!(vec_max_diff(&result.x, &expected) < 1e-6.into());
+    }
+    assert!(jacobi.iterations < plain.iterations);
+    assert!(ssor.iterations < jacobi.iterations);
+    assert!(ic0.iterations < jacobi.iterations);
+
+    let a = grid_operator(k, 20.0);
+    let b = a.mul_vec(&expected);
+    let plain = bicgstab(&a, &b, &x0, &IdentityPreconditioner, &criterion);
+    let ilu0 = Ilu0Preconditioner::new(&a).unwrap();
+    let ilut = IlutPreconditioner::new(&a, F64::from(1e-3), 10).unwrap();
+    let with_ilu0 = bicgstab(&a, &b, &x0, &ilu0, &criterion);
+    let options = GmresOptions::default();
+    let gmres_plain = gmres(&a, &b, &x0, &IdentityPreconditioner, &options, &criterion);
+    let gmres_ilut = gmres(&a, &b, &x0, &ilut, &options, &criterion);
+    println!(
+        "bicgstab: plain {}, ilu0 {}; gmres: plain {}, ilut {}",
+        plain.iterations, with_ilu0.iterations, gmres_plain.iterations, gmres_ilut.iterations
+    );
+    assert!(with_ilu0.converged() && gmres_ilut.converged());
+    assert!(with_ilu0.iterations * 2 < plain.iterations);
+    assert!(gmres_ilut.iterations * 4 < gmres_plain.iterations);
+    assert!(vec_max_diff(&gmres_ilut.x, &expected) < 1e-6.into());
+}
+
+#[test]
+fn test_zero_pivot_errors() {
+    let swap = FullMat::from_rows(vec![F64::map_vec(vec![0., 1.]), F64::map_vec(vec![1., 0.])]);
+    assert!(JacobiPreconditioner::new(&swap).unwrap_err() == PreconditionerError::ZeroPivot(0));
+    assert!(
+        SsorPreconditioner::new(&swap, F64::ONE).unwrap_err() == PreconditionerError::ZeroPivot(0)
+    );
+    assert!(Ilu0Preconditioner::new(&swap).unwrap_err() == PreconditionerError::ZeroPivot(0));
+    assert!(
+        IlutPreconditioner::new(&swap, F64::ZERO, 2).unwrap_err()
+            == PreconditionerError::ZeroPivot(0)
+    );
+    // the 2 x 2 block itself is nonsingular
+    assert!(BlockJacobiPreconditioner::new(&swap, 2).is_ok());
+    assert!(
+        BlockJacobiPreconditioner::new(&swap, 1).unwrap_err()
+            == PreconditionerError::SingularBlock(0)
+    );
+
+    // a pivot that vanishes during the elimination
+    let a = FullMat::from_rows(vec![
+        F64::map_vec(vec![1., 1., 0.]),
+        F64::map_vec(vec![1., 1., 1.]),
+        F64::map_vec(vec![0., 1., 1.]),
+    ]);
+    let err = Ilu0Preconditioner::new(&a).unwrap_err();
+    assert!(err == PreconditionerError::ZeroPivot(1));
+    assert!(err.to_string() == "zero pivot in row 1");
+
+    // a sparse matrix with no stored diagonal entry in row 2
+    let mut coo = CooMat::new(3, 3);
+    coo.push(0, 0, F64::ONE);
+    coo.push(1, 1, F64::ONE);
+    coo.push(2, 0, F64::ONE);
+    let missing = coo.to_csr();
+    assert!(Ilu0Preconditioner::new(&missing).unwrap_err() == PreconditionerError::ZeroPivot(2));
+    assert!(Ic0Preconditioner::new(&missing).unwrap_err() == PreconditionerError::ZeroPivot(2));
+
+    // a NaN reaches the pivot of row 2, after two NaN entries in its L part
+    // are sorted for the fill limit
+    let nan = FullMat::from_rows(vec![
+        F64::map_vec(vec![2., 1., 0.]),
+        F64::map_vec(vec![1., 3., 1.]),
+        F64::map_vec(vec![f64::NAN, 1., 4.]),
+    ]);
+    assert!(Ilu0Preconditioner::new(&nan).unwrap_err() == PreconditionerError::ZeroPivot(2));
+    assert!(
+        IlutPreconditioner::new(&nan, F64::ZERO, 1).unwrap_err()
+            == PreconditionerError::ZeroPivot(2)
+    );
+
+    let indefinite =
+        FullMat::from_rows(vec![F64::map_vec(vec![1., 2.]), F64::map_vec(vec![2., 1.])]);
+    let err = Ic0Preconditioner::new(&indefinite).unwrap_err();
+    assert!(err == PreconditionerError::NotPositiveDefinite(1));
+    println!("{}", err);
+}
@@ -0,0 +1,239 @@
+use floating_point::F64;
+use matrix::{
+    conjugate_gradient, stationary_solve, CooMat, CscMat, CsrMat, FullMat, IdentityPreconditioner,
+    LinearOperator, Matrix, RowAccess, StationaryMethod, StationaryOptions, StoppingCriterion,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn vec_max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x - *y).abs())
+        .fold(0.0.into(), |max, x| if max < x { x } else { max })
+}
+
+fn mat_max_diff(a: &FullMat<F64>, b: &FullMat<F64>) -> F64 {
+    assert!(a.shape() == b.shape());
+    let mut max = F64::ZERO;
+    for i in 0..a.row_count() {
+        for j in 0..a.col_count() {
+            max = max.max((a[(i, j)] - b[(i, j)]).abs());
+        }
+    }
+    max
+}
+
+// random matrix with roughly the given fraction of nonzeros
+fn random_sparse(rng: &mut StdRng, m: usize, n: usize, density: f64) -> FullMat<F64> {
+    let mut mat = FullMat::zeros(m, n);
+    for i in 0..m {
+        for j in 0..n {
+            if rng.gen::<f64>() < density {
+                mat[(i, j)] = rng.gen_range(-1.0..1.0).into();
+            }
+        }
+    }
+    mat
+}
+
+// five-point Laplacian on a k x k grid, assembled edge by edge so that the
+// diagonal entries arrive as duplicates
+fn laplacian_2d(k: usize) -> CooMat<F64> {
+    let n = k * k;
+    let mut coo = CooMat::with_capacity(n, n, 9 * n);
+    for i in 0..k {
+        for j in 0..k {
+            let p = i * k + j;
+            let mut neighbours = vec![];
+            if i > 0 {
+                neighbours.push(p - k);
+            }
+            if i + 1 < k {
+                neighbours.push(p + k);
+            }
+            if j > 0 {
+                neighbours.push(p - 1);
+            }
+            if j + 1 < k {
+                neighbours.push(p + 1);
+            }
+            // the Dirichlet boundary contributes to the diagonal as well
+            for _ in neighbours.len()..4 {
+                coo.push(p, p, F64::ONE);
+            }
+            for q in neighbours {
+                coo.push(p, q, F64::from(-1.0));
+                coo.push(p, p, F64::ONE);
+            }
+        }
+    }
+    coo
+}
+
+#[test]
+fn test_coo_assembly() {
+    let mut coo = CooMat::new(3, 4);
+    coo.push(2, 1, F64::from(1.0));
+    coo.push(0, 3, F64::from(2.0));
+    coo.push(2, 1, F64::from(3.0));
+    coo.push(0, 0, F64::from(-1.0));
+    coo.push(1, 2, F64::from(5.0));
+    coo.push(1, 2, F64::from(-5.0));
+    assert!(coo.nnz() == 6);
+
+    let expected = FullMat::from_rows(vec![
+        F64::map_vec(vec![-1., 0., 0., 2.]),
+        F64::map_vec(vec![0., 0., 0., 0.]),
+        F64::map_vec(vec![0., 4., 0., 0.]),
+    ]);
+    assert!(mat_max_diff(&coo.to_dense(), &expected) == F64::ZERO);
+
+    let csr = coo.to_csr();
+    println!("csr = \n{}", csr);
+    assert!(csr.shape() == (3, 4));
+    // the cancelled entry stays in the pattern as an explicit zero
+    assert!(csr.nnz() == 4);
+    assert!(csr.row_ptr() == [0, 2, 3, 4]);
+    assert!(csr.col_indices() == [0, 3, 2, 1]);
+    assert!(csr.get(1, 2) == Some(&F64::ZERO));
+    assert!(csr.get(1, 1).is_none());
+    assert!(csr[(2, 1)] == 4.0.into());
+    assert!(csr[(2, 2)] == 0.0.into());
+    assert!(mat_max_diff(&csr.to_dense(), &expected) == F64::ZERO);
+
+    let csc = CscMat::from(coo);
+    assert!(csc.nnz() == 4);
+    assert!(csc.col_ptr() == [0, 1, 2, 3, 4]);
+    assert!(csc.row_indices() == [0, 2, 1, 0]);
+    assert!(csc[(0, 3)] == 2.0.into());
+    assert!(mat_max_diff(&FullMat::from(csc), &expected) == F64::ZERO);
+}
+
+#[test]
+fn test_iterators_and_conversions() {
+    let dense = FullMat::from_rows(vec![
+        F64::map_vec(vec![1., 0., 2.]),
+        F64::map_vec(vec![0., 3., 0.]),
+        F64::map_vec(vec![4., 0., 5.]),
+    ]);
+    let csr = CsrMat::from_dense(&dense);
+    let csc = CscMat::from_dense(&dense);
+
+    let row_major: Vec<(usize, usize, f64)> =
+        csr.iter().map(|(i, j, v)| (i, j, v.to_f64())).collect();
+    assert!(row_major == vec![(0, 0, 1.), (0, 2, 2.), (1, 1, 3.), (2, 0, 4.), (2, 2, 5.)]);
+    let col_major: Vec<(usize, usize, f64)> =
+        csc.iter().map(|(i, j, v)| (i, j, v.to_f64())).collect();
+    assert!(col_major == vec![(0, 0, 1.), (2, 0, 4.), (1, 1, 3.), (0, 2, 2.), (2, 2, 5.)]);
+    assert!(csr.row(2).map(|(j, _)| j).collect::<Vec<_>>() == vec![0, 2]);
+    assert!(csc.col(1).map(|(i, _)| i).collect::<Vec<_>>() == vec![1]);
+
+    let round_trip = csr.to_csc().to_csr();
+    assert!(round_trip.row_ptr() == csr.row_ptr());
+    assert!(round_trip.col_indices() == csr.col_indices());
+    assert!(round_trip.values() == csr.values());
+    assert!(csc.to_csr().values() == csr.values());
+    assert!(mat_max_diff(&csr.to_coo().to_dense(), &dense) == F64::ZERO);
+    assert!(mat_max_diff(&csc.to_coo().to_csr().to_dense(), &dense) == F64::ZERO);
+
+    assert!(CsrMat::identity(3).diagonal() == vec![F64::ONE; 3]);
+    assert!(csr.diagonal() == F64::map_vec(vec![1., 3., 5.]));
+}
+
+#[test]
+fn test_products_against_dense() {
+    let mut rng = StdRng::seed_from_u64(34);
+    for (m, k, n) in [(1, 1, 1), (5, 7, 3), (20, 13, 17), (40, 40, 40)] {
+        let a = random_sparse(&mut rng, m, k, 0.2);
+        let b = random_sparse(&mut rng, k, n, 0.2);
+        let x: Vec<F64> = (0..k).map(|_| rng.gen_range(-1.0..1.0).into()).collect();
+        let (a_csr, a_csc) = (CsrMat::from_dense(&a), CscMat::from_dense(&a));
+        let (b_csr, b_csc) = (CsrMat::from_dense(&b), CscMat::from_dense(&b));
+
+        let ax = a.mul_vec(&x);
+        assert!(vec_max_diff(&a_csr.mul_vec(&x), &ax) < 1e-14.into());
+        assert!(vec_max_diff(&a_csc.mul_vec(&x), &ax) < 1e-14.into());
+
+        let ab = a.mul_mat(&b);
+        assert!(mat_max_diff(&a_csr.mul_dense(&b), &ab) < 1e-14.into());
+        assert!(mat_max_diff(&a_csc.mul_dense(&b), &ab) < 1e-14.into());
+        assert!(mat_max_diff(&a_csr.mul_sparse(&b_csr).to_dense(), &ab) < 1e-14.into());
+        assert!(mat_max_diff(&a_csc.mul_sparse(&b_csc).to_dense(), &ab) < 1e-14.into());
+
+        let at = a.transpose();
+        assert!(a_csr.transpose().shape() == (k, m));
+        assert!(mat_max_diff(&a_csr.transpose().to_dense(), &at) == F64::ZERO);
+        assert!(mat_max_diff(&a_csc.transpose().to_dense(), &at) == F64::ZERO);
+    }
+}
+
+#[test]
+fn test_sparse_product_pattern() {
+    let mut rng = StdRng::seed_from_u64(340);
+    let a = CsrMat::from_dense(&random_sparse(&mut rng, 30, 30, 0.1));
+    let c = a.mul_sparse(&a.transpose());
+    // the product is symmetric and its rows are stored sorted
+    for i in 0..30 {
+        let cols: Vec<usize> = c.row(i).map(|(j, _)| j).collect();
+        assert!(cols.windows(2).all(|w| w[0] < w[1]));
+        for (j, v) in c.row(i) {
+            assert!((v - c[(j, i)]).abs() < 1e-14.into());
+        }
+    }
+    assert!(CsrMat::identity(30).mul_sparse(&a).values() == a.values());
+}
+
+#[test]
+fn test_laplacian_solve() {
+    let k = 100;
+    let n = k * k;
+    let csr = laplacian_2d(k).to_csr();
+    assert!(csr.nnz() == 5 * n - 4 * k);
+    assert!(csr.diagonal().iter().all(|d| *d == 4.0.into()));
+    // the constant vector is only touched at the boundary
+    let y = csr.mul_vec(&vec![F64::ONE; n]);
+    assert!(y[k + 1] == F64::ZERO && y[0] == 2.0.into() && y[1] == 1.0.into());
+
+    let expected: Vec<F64> = (0..n).map(|i| F64::from((i as f64 * 0.01).sin())).collect();
+    let b = csr.mul_vec(&expected);
+    let cg = conjugate_gradient(
+        &csr,
+        &b,
+        &vec![F64::ZERO; n],
+        &IdentityPreconditioner,
+        &StoppingCriterion {
+            relative_tolerance: 1e-12.into(),
+            ..Default::default()
+        },
+    );
+    println!("cg iterations = {}", cg.iterations);
+    assert!(cg.converged());
+    assert!(vec_max_diff(&cg.x, &expected) < 1e-8.into());
+
+    let csc = csr.to_csc();
+    assert!(vec_max_diff(&csc.apply(&expected), &b) == F64::ZERO);
+}
+
+#[test]
+fn test_splitting_on_csr() {
+    let csr = laplacian_2d(8).to_csr();
+    let dense = csr.to_dense();
+    let b = vec![F64::ONE; 64];
+    let options = StationaryOptions {
+        max_iterations: 10000,
+        ..Default::default()
+    };
+    let x0 = vec![F64::ZERO; 64];
+    let method = StationaryMethod::GaussSeidel;
+    let sparse = stationary_solve(&csr, &b, &x0, method, options).unwrap();
+    let full = stationary_solve(&dense, &b, &x0, method, options).unwrap();
+    assert!(sparse.converged);
+    assert!(sparse.iterations == full.iterations);
+    assert!(vec_max_diff(&sparse.x, &full.x) < 1e-14.into());
+}
+
+#[test]
+#[should_panic]
+fn test_unsorted_indices() {
+    CsrMat::from_raw_parts(2, 2, vec![0, 2, 2], vec![1, 0], F64::map_vec(vec![1., 2.]));
+}
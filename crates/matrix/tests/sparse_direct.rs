@@ -0,0 +1,329 @@
+use floating_point::F64;
+use matrix::{
+    approximate_minimum_degree, bandwidth_and_profile, reverse_cuthill_mckee, CooMat, CscMat,
+    CsrMat, FullMat, Matrix, SparseOrdering, SparseTriMat, SymbolicCholesky, SymbolicLu,
+    TriFullMat, TriangleMatType,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+fn vec_max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x - *y).abs())
+        .fold(0.0.into(), |max, x| if max < x { x } else { max })
+}
+
+fn mat_max_diff(a: &FullMat<F64>, b: &FullMat<F64>) -> F64 {
+    assert!(a.shape() == b.shape());
+    let mut max = F64::ZERO;
+    for i in 0..a.row_count() {
+        for j in 0..a.col_count() {
+            max = max.max((a[(i, j)] - b[(i, j)]).abs());
+        }
+    }
+    max
+}
+
+// rows and columns of A in the order given by perm
+fn permute(a: &FullMat<F64>, row_perm: &[usize], col_perm: &[usize]) -> FullMat<F64> {
+    let mut mat = FullMat::zeros(row_perm.len(), col_perm.len());
+    for (k, i) in row_perm.iter().enumerate() {
+        for (l, j) in col_perm.iter().enumerate() {
+            mat[(k, l)] = a[(*i, *j)];
+        }
+    }
+    mat
+}
+
+// upwind convection-diffusion on a k x k grid, with the unknowns numbered by
+// `label`; with zero convection it is the five-point Laplacian
+fn grid_operator(k: usize, convection: f64, label: &[usize]) -> CsrMat<F64> {
+    let n = k * k;
+    let mut coo = CooMat::new(n, n);
+    for i in 0..k {
+        for j in 0..k {
+            let p = label[i * k + j];
+            coo.push(p, p, F64::from(4.0 + convection));
+            if i > 0 {
+                coo.push(p, label[(i - 1) * k + j], F64::from(-1.0 - convection));
+            }
+            if i + 1 < k {
+                coo.push(p, label[(i + 1) * k + j], F64::from(-1.0));
+            }
+            if j > 0 {
+                coo.push(p, label[i * k + j - 1], F64::from(-1.0));
+            }
+            if j + 1 < k {
+                coo.push(p, label[i * k + j + 1], F64::from(-1.0));
+            }
+        }
+    }
+    coo.to_csr()
+}
+
+fn natural_labels(k: usize) -> Vec<usize> {
+    (0..k * k).collect()
+}
+
+#[test]
+fn test_sparse_triangular_solve() {
+    let lower = TriFullMat::from_vec(
+        TriangleMatType::Lower,
+        F64::map_vec(vec![2., 0., 1., -1., 0., 4., 0., 3., 0., 5.]),
+    );
+    let dense = FullMat::from(lower.clone());
+    let sparse = SparseTriMat::from_csc(TriangleMatType::Lower, CscMat::from_dense(&dense));
+    println!("sparse lower = \n{}", sparse);
+    assert!(sparse.nnz() == 6);
+    assert!(sparse[(3, 1)] == 3.0.into() && sparse[(1, 3)] == 0.0.into());
+
+    let b = F64::map_vec(vec![1., 2., 3., 4.]);
+    assert!(vec_max_diff(&sparse.solve(b.clone()), &lower.solve(b.clone())) < 1e-15.into());
+    let upper = lower.transpose();
+    assert!(
+        vec_max_diff(&sparse.solve_transpose(b.clone()), &upper.solve(b.clone())) < 1e-15.into()
+    );
+
+    let sparse_upper = SparseTriMat::from_csc(
+        TriangleMatType::Upper,
+        CscMat::from_dense(&FullMat::from(upper.clone())),
+    );
+    assert!(vec_max_diff(&sparse_upper.solve(b.clone()), &upper.solve(b.clone())) < 1e-15.into());
+    assert!(vec_max_diff(&sparse_upper.solve_transpose(b.clone()), &lower.solve(b)) < 1e-15.into());
+}
+
+#[test]
+#[should_panic]
+fn test_sparse_triangular_missing_diagonal() {
+    let dense = FullMat::from_rows(vec![F64::map_vec(vec![1., 0.]), F64::map_vec(vec![1., 0.])]);
+    SparseTriMat::from_csc(TriangleMatType::Lower, CscMat::from_dense(&dense));
+}
+
+#[test]
+fn test_orderings() {
+    let k = 12;
+    let mut rng = StdRng::seed_from_u64(35);
+    let mut labels = natural_labels(k);
+    labels.shuffle(&mut rng);
+    let a = grid_operator(k, 0.0, &labels);
+
+    let natural: Vec<usize> = (0..k * k).collect();
+    let rcm = reverse_cuthill_mckee(&a);
+    let (band_natural, profile_natural) = bandwidth_and_profile(&a, &natural);
+    let (band_rcm, profile_rcm) = bandwidth_and_profile(&a, &rcm);
+    println!("bandwidth {} -> {}", band_natural, band_rcm);
+    println!("profile {} -> {}", profile_natural, profile_rcm);
+    assert!(band_rcm <= k + 1);
+    assert!(profile_rcm * 4 < profile_natural);
+
+    for ordering in [
+        SparseOrdering::Natural,
+        SparseOrdering::ReverseCuthillMcKee,
+        SparseOrdering::MinimumDegree,
+        SparseOrdering::ApproximateMinimumDegree,
+    ] {
+        let mut perm = ordering.permutation(&a);
+        perm.sort_unstable();
+        assert!(perm == natural);
+    }
+
+    // minimum degree beats the band orderings on the fill of a 2D grid
+    let a = grid_operator(20, 0.0, &natural_labels(20));
+    let fill = |ordering| SymbolicCholesky::analyze(&a, ordering).nnz_l();
+    let (natural_fill, rcm_fill, md_fill, amd_fill) = (
+        fill(SparseOrdering::Natural),
+        fill(SparseOrdering::ReverseCuthillMcKee),
+        fill(SparseOrdering::MinimumDegree),
+        fill(SparseOrdering::ApproximateMinimumDegree),
+    );
+    println!(
+        "nnz(L): natural {natural_fill}, rcm {rcm_fill}, minimum degree {md_fill}, amd {amd_fill}"
+    );
+    assert!(md_fill < rcm_fill && md_fill < natural_fill);
+    assert!(amd_fill < rcm_fill && amd_fill < natural_fill);
+}
+
+#[test]
+fn test_approximate_minimum_degree() {
+    // the degree bounds cost little fill against the exact degrees
+    let mut rng = StdRng::seed_from_u64(3500);
+    let mut matrices = vec![grid_operator(30, 0.0, &natural_labels(30))];
+    let mut labels = natural_labels(25);
+    labels.shuffle(&mut rng);
+    matrices.push(grid_operator(25, 0.0, &labels));
+    for n in [50, 400] {
+        let mut coo = CooMat::new(n, n);
+        for i in 0..n {
+            coo.push(i, i, F64::from(8.0));
+            for _ in 0..3 {
+                let j = rng.gen_range(0..n);
+                if j != i {
+                    coo.push(i, j, F64::ONE);
+                    coo.push(j, i, F64::ONE);
+                }
+            }
+        }
+        matrices.push(coo.to_csr());
+    }
+
+    for a in matrices {
+        let n = a.row_count();
+        let mut perm = approximate_minimum_degree(&a);
+        let fill = |ordering| SymbolicCholesky::analyze(&a, ordering).nnz_l();
+        let (md_fill, amd_fill) = (
+            fill(SparseOrdering::MinimumDegree),
+            fill(SparseOrdering::ApproximateMinimumDegree),
+        );
+        println!("n = {n}: nnz(L) minimum degree {md_fill}, amd {amd_fill}");
+        assert!(amd_fill * 10 <= md_fill * 11);
+        perm.sort_unstable();
+        assert!(perm == (0..n).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_sparse_cholesky() {
+    let k = 9;
+    let a = grid_operator(k, 0.0, &natural_labels(k));
+    let dense = a.to_dense();
+    let expected: Vec<F64> = (0..k * k).map(|i| F64::from((i as f64).cos())).collect();
+    let b = a.mul_vec(&expected);
+
+    for ordering in [
+        SparseOrdering::Natural,
+        SparseOrdering::ReverseCuthillMcKee,
+        SparseOrdering::MinimumDegree,
+        SparseOrdering::ApproximateMinimumDegree,
+    ] {
+        let symbolic = SymbolicCholesky::analyze(&a, ordering);
+        let chol = symbolic.factor(&a).unwrap();
+        assert!(chol.l().nnz() == symbolic.nnz_l());
+        let l = chol.l().to_dense();
+        let llt = l.mul_mat(&l.transpose());
+        assert!(mat_max_diff(&llt, &permute(&dense, chol.perm(), chol.perm())) < 1e-13.into());
+        assert!(vec_max_diff(&chol.solve(&b), &expected) < 1e-12.into());
+
+        // the elimination tree has one root for a connected graph
+        let roots = symbolic
+            .elimination_tree()
+            .iter()
+            .filter(|p| p.is_none())
+            .count();
+        assert!(roots == 1);
+    }
+
+    // the dense factor of the natural ordering agrees
+    let dense_l = FullMat::from(dense.cholesky().unwrap());
+    let sparse_l = a.cholesky(SparseOrdering::Natural).unwrap().l().to_dense();
+    assert!(mat_max_diff(&dense_l, &sparse_l) < 1e-14.into());
+}
+
+#[test]
+fn test_sparse_cholesky_reuse_symbolic() {
+    let k = 15;
+    let a = grid_operator(k, 0.0, &natural_labels(k));
+    let symbolic = SymbolicCholesky::analyze(&a, SparseOrdering::MinimumDegree);
+
+    for shift in [0.0, 0.5, 10.0] {
+        let mut shifted = a.clone();
+        let diagonal: Vec<usize> = shifted
+            .iter()
+            .enumerate()
+            .filter(|(_, (i, j, _))| i == j)
+            .map(|(p, _)| p)
+            .collect();
+        for p in diagonal {
+            shifted.values_mut()[p] += F64::from(shift);
+        }
+        let b = vec![F64::ONE; k * k];
+        let x = symbolic.factor(&shifted).unwrap().solve(&b);
+        assert!(vec_max_diff(&shifted.mul_vec(&x), &b) < 1e-12.into());
+    }
+
+    // an indefinite matrix with the same pattern is rejected
+    let mut indefinite = a.clone();
+    indefinite.values_mut()[0] = F64::from(-1.0);
+    assert!(symbolic.factor(&indefinite).is_none());
+}
+
+#[test]
+fn test_sparse_lu() {
+    let k = 10;
+    let a = grid_operator(k, 3.0, &natural_labels(k));
+    let dense = a.to_dense();
+    let expected: Vec<F64> = (0..k * k).map(|i| F64::from((i as f64).sin())).collect();
+    let b = a.mul_vec(&expected);
+
+    for ordering in [
+        SparseOrdering::Natural,
+        SparseOrdering::ReverseCuthillMcKee,
+        SparseOrdering::MinimumDegree,
+        SparseOrdering::ApproximateMinimumDegree,
+    ] {
+        for threshold in [1.0, 0.1] {
+            let lu = a.lu(ordering, F64::from(threshold)).unwrap();
+            let product = lu.l().to_dense().mul_mat(&lu.u().to_dense());
+            let pa_q = permute(&dense, lu.row_perm(), lu.col_perm());
+            assert!(mat_max_diff(&product, &pa_q) < 1e-13.into());
+            assert!(vec_max_diff(&lu.solve(&b), &expected) < 1e-12.into());
+
+            let bt = a.transpose().mul_vec(&expected);
+            assert!(vec_max_diff(&lu.solve_transpose(&bt), &expected) < 1e-12.into());
+        }
+    }
+}
+
+#[test]
+fn test_sparse_lu_pivoting() {
+    let mut rng = StdRng::seed_from_u64(350);
+    let n = 40;
+    // a permuted diagonal plus random entries, with a zero diagonal
+    let mut shuffled: Vec<usize> = (0..n).collect();
+    shuffled.shuffle(&mut rng);
+    let mut coo = CooMat::new(n, n);
+    for (i, j) in shuffled.iter().enumerate() {
+        if i != *j {
+            coo.push(i, *j, F64::from(rng.gen_range(1.0..2.0)));
+        }
+    }
+    for _ in 0..3 * n {
+        let (i, j) = (rng.gen_range(0..n), rng.gen_range(0..n));
+        if i != j {
+            coo.push(i, j, F64::from(rng.gen_range(-0.5..0.5)));
+        }
+    }
+    let a = coo.to_csr();
+    let dense = a.to_dense();
+    let b: Vec<F64> = (0..n).map(|i| F64::from(i as f64)).collect();
+    let expected = dense.lu_solve(&b).unwrap();
+
+    let symbolic = SymbolicLu::analyze(&a, SparseOrdering::MinimumDegree);
+    let lu = symbolic.factor(&a, F64::ONE).unwrap();
+    assert!(vec_max_diff(&lu.solve(&b), &expected) < 1e-10.into());
+    // partial pivoting keeps the multipliers bounded
+    assert!(lu.l().as_csc().values().iter().all(|v| v.abs() <= F64::ONE));
+
+    let loose = symbolic.factor(&a, F64::from(0.01)).unwrap();
+    assert!(vec_max_diff(&loose.solve(&b), &expected) < 1e-8.into());
+}
+
+#[test]
+fn test_sparse_lu_singular() {
+    let dense = FullMat::from_rows(vec![
+        F64::map_vec(vec![1., 2., 0.]),
+        F64::map_vec(vec![2., 4., 0.]),
+        F64::map_vec(vec![0., 0., 3.]),
+    ]);
+    let a = CsrMat::from_dense(&dense);
+    assert!(a.lu(SparseOrdering::Natural, F64::ONE).is_none());
+
+    // structurally singular: an empty column
+    let mut coo = CooMat::new(3, 3);
+    coo.push(0, 0, F64::ONE);
+    coo.push(1, 0, F64::ONE);
+    coo.push(2, 2, F64::ONE);
+    assert!(coo
+        .to_csr()
+        .lu(SparseOrdering::MinimumDegree, F64::ONE)
+        .is_none());
+}
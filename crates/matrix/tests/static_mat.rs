@@ -0,0 +1,123 @@
+use floating_point::F64;
+use matrix::{FullMat, Matrix, MatrixError, SMat, SVec};
+
+fn mat<const R: usize, const C: usize>(rows: [[f64; C]; R]) -> SMat<F64, R, C> {
+    SMat::from_rows(rows.map(|row| row.map(F64::from)))
+}
+
+fn vec<const N: usize>(v: [f64; N]) -> SVec<F64, N> {
+    SVec::from_array(v.map(F64::from))
+}
+
+#[test]
+fn test_arithmetic() {
+    let a = mat([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    let b = mat([[1.0, 0.0], [0.0, 1.0], [2.0, -1.0]]);
+    // (2 x 3) (3 x 2); `b * b` would not compile
+    let ab: SMat<F64, 2, 2> = a * b;
+    println!("a b = \n{}", ab);
+    assert!(ab == mat([[7.0, -1.0], [16.0, -1.0]]));
+    assert!(ab.shape() == (2, 2) && a.transpose().shape() == (3, 2));
+    assert!(a.transpose() * vec([1.0, -1.0]) == vec([-3.0, -3.0, -3.0]));
+    assert!(a * vec([1.0, 1.0, 1.0]) == vec([6.0, 15.0]));
+    assert!(a + a == a * F64::from(2.0) && a - a == SMat::zeros() && -(-a) == a);
+    assert!(a.row(1) == vec([4.0, 5.0, 6.0]) && a.col(2) == vec([3.0, 6.0]));
+
+    let v = vec([3.0, 4.0]);
+    assert!(v.dot(&v) == F64::from(25.0) && v.norm2() == F64::from(5.0));
+    assert!(v + v - v * F64::from(2.0) == -SVec::zeros());
+}
+
+#[test]
+fn test_lu_det_inv() {
+    let a = mat([[0.0, 2.0, 1.0], [1.0, 1.0, 0.0], [3.0, 0.0, 1.0]]);
+    let lu = a.lu().unwrap();
+    assert!(lu.pivots() == &[2, 2, 2]);
+    // P A = L U, with the interchanges applied to A in order
+    let mut pa = a;
+    for (k, p) in lu.pivots().iter().enumerate() {
+        for j in 0..3 {
+            let t = pa[(k, j)];
+            pa[(k, j)] = pa[(*p, j)];
+            pa[(*p, j)] = t;
+        }
+    }
+    assert!((lu.l() * lu.u() - pa).element_max_abs() <= F64::from(4.0) * F64::EPSILON);
+    // det A = 0 (1 - 0) - 2 (1 - 0) + 1 (0 - 3)
+    assert!((a.det() - F64::from(-5.0)).abs() <= F64::from(8.0) * F64::EPSILON);
+
+    let inv = a.inv().unwrap();
+    assert!((inv * a - SMat::identity()).element_max_abs() <= F64::from(8.0) * F64::EPSILON);
+    let b = vec([1.0, 2.0, 3.0]);
+    let x = a.lu_solve(&b).unwrap();
+    assert!((a * x - b)
+        .as_slice()
+        .iter()
+        .all(|r| r.abs() <= F64::from(8.0) * F64::EPSILON));
+
+    let singular = mat([[1.0, 2.0], [2.0, 4.0]]);
+    assert!(singular.lu().is_none() && singular.inv().is_none());
+    assert!(singular.det() == F64::ZERO);
+
+    // agrees with the heap-allocated code
+    let hilbert: SMat<F64, 5, 5> = SMat::try_from(&FullMat::hilbert(5)).unwrap();
+    let full_inv = FullMat::hilbert(5).inv().unwrap();
+    let static_inv = FullMat::from(hilbert.inv().unwrap());
+    assert!(
+        static_inv.sub(&full_inv).element_max_abs() <= F64::from(1e-9) * full_inv.element_max_abs()
+    );
+}
+
+#[test]
+fn test_cholesky() {
+    let a = mat([[4.0, 2.0, -2.0], [2.0, 10.0, 2.0], [-2.0, 2.0, 5.0]]);
+    let l = a.cholesky().unwrap();
+    println!("l = \n{}", l);
+    assert!(l == mat([[2.0, 0.0, 0.0], [1.0, 3.0, 0.0], [-1.0, 1.0, 3.0f64.sqrt()]]));
+    assert!((l * l.transpose() - a).element_max_abs() <= F64::from(8.0) * F64::EPSILON);
+    let b = vec([2.0, 17.0, 6.0]);
+    let x = a.cholesky_solve(&b).unwrap();
+    assert!((a * x - b)
+        .as_slice()
+        .iter()
+        .all(|r| r.abs() <= F64::from(32.0) * F64::EPSILON));
+
+    assert!(mat([[1.0, 2.0], [2.0, 1.0]]).cholesky().is_none());
+    let pascal: SMat<F64, 6, 6> = SMat::try_from(&FullMat::pascal(6)).unwrap();
+    let lower = pascal.cholesky().unwrap();
+    assert!(
+        FullMat::from(lower)
+            .sub(&FullMat::from(FullMat::pascal(6).cholesky().unwrap()))
+            .element_max_abs()
+            == F64::ZERO
+    );
+}
+
+#[test]
+fn test_conversions() {
+    let full = FullMat::from_rows(vec![
+        F64::map_vec(vec![1.0, 2.0, 3.0]),
+        F64::map_vec(vec![4.0, 5.0, 6.0]),
+    ]);
+    let s: SMat<F64, 2, 3> = SMat::try_from(&full).unwrap();
+    assert!(s[(1, 0)] == F64::from(4.0));
+    let back = FullMat::from(s);
+    assert!(back.shape() == (2, 3) && back.sub(&full).element_max_abs() == F64::ZERO);
+    assert!(
+        SMat::<F64, 3, 2>::try_from(&full).unwrap_err()
+            == MatrixError::ShapeMismatch {
+                left: (2, 3),
+                right: (3, 2)
+            }
+    );
+
+    let v = F64::map_vec(vec![1.0, 2.0, 3.0]);
+    let s: SVec<F64, 3> = SVec::try_from(v.as_slice()).unwrap();
+    assert!(Vec::from(s) == v);
+    assert!(SVec::<F64, 2>::try_from(v.as_slice()).is_err());
+
+    // any Copy element type
+    let chars = SMat::from_rows([['a', 'b'], ['c', 'd']]);
+    assert!(chars.transpose().to_rows() == [['a', 'c'], ['b', 'd']]);
+    assert!(format!("{}", chars) == "[[a,b],\n[c,d]]");
+}
@@ -0,0 +1,128 @@
+use floating_point::F64;
+use matrix::{
+    check_diagonal_dominance, optimal_sor_omega, stationary_solve, FullMat, StationaryError,
+    StationaryMethod, StationaryOptions,
+};
+
+fn vec_max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x - *y).abs())
+        .fold(0.0.into(), |max, x| if max < x { x } else { max })
+}
+
+fn second_difference_mat(n: usize) -> FullMat<F64> {
+    let mut mat = FullMat::zeros(n, n);
+    for i in 0..n {
+        mat[(i, i)] = 2.0.into();
+        if i > 0 {
+            mat[(i, i - 1)] = (-1.0).into();
+            mat[(i - 1, i)] = (-1.0).into();
+        }
+    }
+    mat
+}
+
+#[test]
+fn test_jacobi_and_gauss_seidel() {
+    // 3u + v = 5, u + 2v = 5 with solution (1, 2)
+    let a = FullMat::from_vec(2, F64::map_vec(vec![3., 1., 1., 2.]));
+    let b = F64::map_vec(vec![5., 5.]);
+    let x0 = vec![F64::ZERO; 2];
+    let expected = F64::map_vec(vec![1., 2.]);
+
+    let jacobi =
+        stationary_solve(&a, &b, &x0, StationaryMethod::Jacobi, Default::default()).unwrap();
+    assert!(jacobi.converged);
+    assert!(vec_max_diff(&jacobi.x, &expected) < 1e-9.into());
+    assert!(jacobi.residual_history.len() == jacobi.iterations);
+
+    let gs = stationary_solve(
+        &a,
+        &b,
+        &x0,
+        StationaryMethod::GaussSeidel,
+        Default::default(),
+    )
+    .unwrap();
+    assert!(gs.converged);
+    assert!(vec_max_diff(&gs.x, &expected) < 1e-9.into());
+    assert!(gs.iterations < jacobi.iterations);
+}
+
+#[test]
+fn test_first_jacobi_step() {
+    let a = FullMat::from_vec(2, F64::map_vec(vec![3., 1., 1., 2.]));
+    let b = F64::map_vec(vec![5., 5.]);
+    let options = StationaryOptions {
+        max_iterations: 1,
+        ..Default::default()
+    };
+    let step =
+        stationary_solve(&a, &b, &[F64::ZERO; 2], StationaryMethod::Jacobi, options).unwrap();
+    assert!(!step.converged);
+    assert!(vec_max_diff(&step.x, &F64::map_vec(vec![5. / 3., 2.5])) < 1e-15.into());
+}
+
+#[test]
+fn test_sor_and_ssor() {
+    let n = 20;
+    let a = second_difference_mat(n);
+    let expected: Vec<F64> = (0..n).map(|i| F64::from((i as f64).sin())).collect();
+    let b = a.mul_vec(&expected);
+    let x0 = vec![F64::ZERO; n];
+    let options = StationaryOptions {
+        max_iterations: 10000,
+        ..Default::default()
+    };
+
+    let omega = optimal_sor_omega(&a, options).unwrap();
+    let h = core::f64::consts::PI / (n + 1) as f64;
+    println!("omega = {}", omega);
+    assert!((omega - F64::from(2.0 / (1.0 + h.sin()))).abs() < 1e-6.into());
+
+    let gs = stationary_solve(&a, &b, &x0, StationaryMethod::GaussSeidel, options).unwrap();
+    let sor = stationary_solve(&a, &b, &x0, StationaryMethod::SOR(omega), options).unwrap();
+    let ssor = stationary_solve(&a, &b, &x0, StationaryMethod::SSOR(1.5.into()), options).unwrap();
+    println!(
+        "iterations: gauss-seidel {}, sor {}, ssor {}",
+        gs.iterations, sor.iterations, ssor.iterations
+    );
+    for result in [&gs, &sor, &ssor] {
+        assert!(result.converged);
+        assert!(vec_max_diff(&result.x, &expected) < 1e-7.into());
+    }
+    assert!(sor.iterations * 5 < gs.iterations);
+}
+
+#[test]
+fn test_diagonal_dominance_check() {
+    let a = FullMat::from_vec(2, F64::map_vec(vec![1., 2., 3., 5.]));
+    assert!(check_diagonal_dominance(&a) == Err(StationaryError::NotDiagonallyDominant(0)));
+
+    let b = F64::map_vec(vec![1., 1.]);
+    let x0 = vec![F64::ZERO; 2];
+    let options = StationaryOptions {
+        require_diagonal_dominance: true,
+        ..Default::default()
+    };
+    let err = stationary_solve(&a, &b, &x0, StationaryMethod::Jacobi, options).unwrap_err();
+    assert!(err == StationaryError::NotDiagonallyDominant(0));
+    println!("{}", err);
+
+    // the check is off by default and the iteration simply diverges
+    let result =
+        stationary_solve(&a, &b, &x0, StationaryMethod::Jacobi, Default::default()).unwrap();
+    assert!(!result.converged);
+
+    let a = FullMat::from_vec(2, F64::map_vec(vec![1., 2., 3., 0.]));
+    let err = stationary_solve(
+        &a,
+        &b,
+        &x0,
+        StationaryMethod::GaussSeidel,
+        Default::default(),
+    )
+    .unwrap_err();
+    assert!(err == StationaryError::ZeroDiagonal(1));
+}
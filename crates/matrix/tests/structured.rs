@@ -0,0 +1,289 @@
+use floating_point::F64;
+use matrix::{
+    durbin, CirculantMat, FullMat, HankelMat, Matrix, ToeplitzMat, TriangleMatType, VandermondeMat,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn vec_max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x - *y).abs())
+        .fold(0.0.into(), |max, x| if max < x { x } else { max })
+}
+
+fn random_vec(rng: &mut StdRng, n: usize) -> Vec<F64> {
+    (0..n).map(|_| rng.gen_range(-1.0..1.0).into()).collect()
+}
+
+fn mat_max_abs(mat: &FullMat<F64>) -> F64 {
+    let (m, n) = mat.shape();
+    let mut max = F64::ZERO;
+    for i in 0..m {
+        for j in 0..n {
+            max = max.max(mat[(i, j)].abs());
+        }
+    }
+    max
+}
+
+#[test]
+fn test_toeplitz() {
+    let toeplitz = ToeplitzMat::new(
+        F64::map_vec(vec![1., 2., 3.]),
+        F64::map_vec(vec![1., 4., 5.]),
+    );
+    println!("toeplitz = \n{}", toeplitz);
+    let full = FullMat::from(toeplitz.clone());
+    let expected = FullMat::from_rows(vec![
+        F64::map_vec(vec![1., 4., 5.]),
+        F64::map_vec(vec![2., 1., 4.]),
+        F64::map_vec(vec![3., 2., 1.]),
+    ]);
+    assert!(mat_max_abs(&full.sub(&expected)) == F64::ZERO);
+    let x = F64::map_vec(vec![1., -1., 2.]);
+    assert!(toeplitz.mul_vec(&x) == full.mul_vec(&x));
+
+    // nonsymmetric and diagonally dominant
+    let mut rng = StdRng::seed_from_u64(38);
+    let n = 60;
+    let mut first_col = random_vec(&mut rng, n);
+    let mut first_row = random_vec(&mut rng, n);
+    first_col[0] = F64::from(n as f64);
+    first_row[0] = first_col[0];
+    let toeplitz = ToeplitzMat::new(first_col, first_row);
+    let expected = random_vec(&mut rng, n);
+    let b = toeplitz.mul_vec(&expected);
+    let x = toeplitz.solve(&b).unwrap();
+    assert!(vec_max_diff(&x, &expected) < 1e-12.into());
+
+    // the leading 1 x 1 block is singular although the matrix is not
+    let toeplitz = ToeplitzMat::symmetric(F64::map_vec(vec![0., 1.]));
+    assert!(toeplitz.solve(&F64::map_vec(vec![1., 2.])).is_none());
+}
+
+#[test]
+fn test_durbin() {
+    // autocorrelation of the AR(1) process x_t = phi x_{t-1} + e_t, whose
+    // Yule-Walker solution is (-phi, 0, ..., 0)
+    let phi: f64 = 0.8;
+    let r: Vec<F64> = (0..6).map(|k| F64::from(phi.powi(k))).collect();
+    let y = durbin(&r).unwrap();
+    let mut expected = vec![F64::ZERO; 5];
+    expected[0] = F64::from(-phi);
+    assert!(vec_max_diff(&y, &expected) < 1e-14.into());
+
+    // agrees with the general Levinson solver
+    let r = F64::map_vec(vec![4., 2., 1., 0.5, 0.2]);
+    let y = durbin(&r).unwrap();
+    let toeplitz = ToeplitzMat::symmetric(r[..4].to_vec());
+    let rhs: Vec<F64> = r[1..].iter().map(|v| -*v).collect();
+    assert!(vec_max_diff(&y, &toeplitz.solve(&rhs).unwrap()) < 1e-14.into());
+
+    // not positive definite
+    assert!(durbin(&F64::map_vec(vec![1., 1., 1.])).is_none());
+}
+
+#[test]
+fn test_hankel() {
+    let hankel = HankelMat::from_first_row_and_last_col(
+        F64::map_vec(vec![1., 2., 3.]),
+        F64::map_vec(vec![3., 4., 5.]),
+    );
+    println!("hankel = \n{}", hankel);
+    assert!(hankel.values() == F64::map_vec(vec![1., 2., 3., 4., 5.]));
+    assert!(hankel[(2, 1)] == 4.0.into() && hankel[(1, 2)] == 4.0.into());
+    let full = FullMat::from(hankel.clone());
+    let x = F64::map_vec(vec![1., -1., 2.]);
+    assert!(hankel.mul_vec(&x) == full.mul_vec(&x));
+
+    let mut rng = StdRng::seed_from_u64(380);
+    let n = 40;
+    let mut values = random_vec(&mut rng, 2 * n - 1);
+    values[n - 1] = F64::from(n as f64);
+    let hankel = HankelMat::new(values);
+    let expected = random_vec(&mut rng, n);
+    let b = hankel.mul_vec(&expected);
+    let x = hankel.solve(&b).unwrap();
+    assert!(vec_max_diff(&x, &expected) < 1e-12.into());
+}
+
+#[test]
+fn test_circulant() {
+    let circulant = CirculantMat::new(F64::map_vec(vec![1., 2., 3.]));
+    println!("circulant = \n{}", circulant);
+    let expected = FullMat::from_rows(vec![
+        F64::map_vec(vec![1., 3., 2.]),
+        F64::map_vec(vec![2., 1., 3.]),
+        F64::map_vec(vec![3., 2., 1.]),
+    ]);
+    assert!(mat_max_abs(&FullMat::from(circulant.clone()).sub(&expected)) == F64::ZERO);
+    // the eigenvalue of the constant vector is the column sum
+    assert!((circulant.eigenvalues()[0].real - F64::from(6.0)).abs() < 1e-14.into());
+
+    // powers of two use the FFT directly, other lengths Bluestein's algorithm
+    let mut rng = StdRng::seed_from_u64(3800);
+    for n in [1, 2, 7, 16, 100, 127] {
+        let mut first_col = random_vec(&mut rng, n);
+        first_col[0] = F64::from(n as f64);
+        let circulant = CirculantMat::new(first_col);
+        let full = FullMat::from(circulant.clone());
+
+        let x = random_vec(&mut rng, n);
+        let y = circulant.mul_vec(&x);
+        assert!(vec_max_diff(&y, &full.mul_vec(&x)) < 1e-11.into());
+        let solved = circulant.solve(&y).unwrap();
+        assert!(vec_max_diff(&solved, &x) < 1e-11.into());
+    }
+
+    // zero row sums make the constant vector a null vector
+    let singular = CirculantMat::new(F64::map_vec(vec![2., -1., 0., 0., -1.]));
+    assert!(singular
+        .solve(&F64::map_vec(vec![1., 0., 0., 0., 0.]))
+        .is_none());
+}
+
+#[test]
+fn test_vandermonde() {
+    let vandermonde = VandermondeMat::new(F64::map_vec(vec![1., 2., 3.]));
+    let full = FullMat::from(vandermonde.clone());
+    let expected = FullMat::from_rows(vec![
+        F64::map_vec(vec![1., 1., 1.]),
+        F64::map_vec(vec![1., 2., 4.]),
+        F64::map_vec(vec![1., 3., 9.]),
+    ]);
+    assert!(mat_max_abs(&full.sub(&expected)) == F64::ZERO);
+    assert!(vandermonde.get(2, 2) == 9.0.into());
+    let a = F64::map_vec(vec![1., -1., 2.]);
+    assert!(vandermonde.mul_vec(&a) == full.mul_vec(&a));
+
+    // Chebyshev points, where the Bjorck-Pereyra solution is accurate
+    let n = 20;
+    let nodes: Vec<F64> = (0..n)
+        .map(|i| F64::from(((2 * i + 1) as f64 * core::f64::consts::PI / (2 * n) as f64).cos()))
+        .collect();
+    let vandermonde = VandermondeMat::new(nodes);
+    let mut rng = StdRng::seed_from_u64(38000);
+    let expected = random_vec(&mut rng, n);
+    let f = vandermonde.mul_vec(&expected);
+    let a = vandermonde.solve(&f).unwrap();
+    assert!(vec_max_diff(&a, &expected) < 1e-9.into());
+
+    // the dual system gives the weights of Fejer's rule on [-1, 1] from the
+    // moments of the monomials
+    let moments: Vec<F64> = (0..n)
+        .map(|j| {
+            F64::from(if j % 2 == 0 {
+                2.0 / (j + 1) as f64
+            } else {
+                0.0
+            })
+        })
+        .collect();
+    let weights = vandermonde.solve_transpose(&moments).unwrap();
+    let residual = FullMat::from(vandermonde.clone())
+        .transpose()
+        .mul_vec(&weights);
+    assert!(vec_max_diff(&residual, &moments) < 1e-10.into());
+    assert!(weights.iter().all(|w| *w > F64::ZERO));
+
+    // interpolatory quadrature weights on [0, 1]: V^T w = (1, 1/2, 1/3)
+    // gives Simpson's rule
+    let vandermonde = VandermondeMat::new(F64::map_vec(vec![0., 0.5, 1.]));
+    let moments = F64::map_vec(vec![1., 1. / 2., 1. / 3.]);
+    let weights = vandermonde.solve_transpose(&moments).unwrap();
+    assert!(vec_max_diff(&weights, &F64::map_vec(vec![1. / 6., 2. / 3., 1. / 6.])) < 1e-15.into());
+
+    let repeated = VandermondeMat::new(F64::map_vec(vec![0., 1., 0.]));
+    assert!(repeated.solve(&F64::map_vec(vec![1., 1., 1.])).is_none());
+    assert!(repeated
+        .solve_transpose(&F64::map_vec(vec![1., 1., 1.]))
+        .is_none());
+}
+
+#[test]
+fn test_hilbert() {
+    let hilbert = FullMat::hilbert(3);
+    println!("hilbert = \n{}", hilbert);
+    assert!(hilbert[(1, 2)] == F64::from(0.25));
+
+    // the error of the LU solution grows with the condition number
+    let mut errors = vec![];
+    for n in [4, 8, 12] {
+        let hilbert = FullMat::hilbert(n);
+        let expected = vec![F64::ONE; n];
+        let b = hilbert.mul_vec(&expected);
+        let x = hilbert.lu_solve(&b).unwrap();
+        errors.push(vec_max_diff(&x, &expected));
+    }
+    println!("hilbert errors = {:?}", errors);
+    assert!(errors[0] < 1e-12.into());
+    assert!(errors[0] < errors[1] && errors[1] < errors[2]);
+
+    let product = FullMat::hilbert(4).mul_mat(&FullMat::inverse_hilbert(4));
+    assert!(mat_max_abs(&product.sub(&FullMat::identity(4))) < 1e-10.into());
+    let inverse = FullMat::hilbert(6).inv().unwrap();
+    let exact = FullMat::inverse_hilbert(6);
+    assert!(mat_max_abs(&inverse.sub(&exact)) < F64::from(1e-7) * mat_max_abs(&exact));
+}
+
+#[test]
+fn test_pascal() {
+    let pascal = FullMat::pascal(4);
+    let expected = FullMat::from_rows(vec![
+        F64::map_vec(vec![1., 1., 1., 1.]),
+        F64::map_vec(vec![1., 2., 3., 4.]),
+        F64::map_vec(vec![1., 3., 6., 10.]),
+        F64::map_vec(vec![1., 4., 10., 20.]),
+    ]);
+    assert!(mat_max_abs(&pascal.sub(&expected)) == F64::ZERO);
+
+    // the Cholesky factor is the lower triangular Pascal matrix
+    let pascal = FullMat::pascal(10);
+    let l = pascal.cholesky().unwrap();
+    assert!(matches!(l.ty, TriangleMatType::Lower));
+    for i in 0..10 {
+        for j in 0..=i {
+            let binomial = FullMat::pascal(10)[(i - j, j)];
+            assert!((l[(i, j)] - binomial).abs() < F64::from(1e-9) * binomial);
+        }
+    }
+
+    // det P = 1
+    let (_, u, _) = pascal.lu().unwrap();
+    let det = (0..10).fold(F64::ONE, |det, k| det * u[(k, k)]);
+    assert!((det - F64::ONE).abs() < 1e-6.into());
+}
+
+#[test]
+fn test_frank_and_kahan() {
+    let frank = FullMat::frank(4);
+    let expected = FullMat::from_rows(vec![
+        F64::map_vec(vec![4., 3., 2., 1.]),
+        F64::map_vec(vec![3., 3., 2., 1.]),
+        F64::map_vec(vec![0., 2., 2., 1.]),
+        F64::map_vec(vec![0., 0., 1., 1.]),
+    ]);
+    assert!(mat_max_abs(&frank.sub(&expected)) == F64::ZERO);
+
+    // det F = 1 although the entries grow with n
+    for n in [4, 8, 12] {
+        let (_, u, p) = FullMat::frank(n).lu().unwrap();
+        let mut det = (0..n).fold(F64::ONE, |det, k| det * u[(k, k)]);
+        let mut p = p;
+        for k in 0..n {
+            while p[k] != k {
+                let target = p[k];
+                p.swap(k, target);
+                det = -det;
+            }
+        }
+        assert!((det - F64::ONE).abs() < F64::from(1e-13 * 10f64.powi(n as i32 / 2)));
+    }
+
+    let theta = F64::from(1.2);
+    let kahan = FullMat::kahan(5, theta);
+    let (s, c) = (1.2f64.sin(), 1.2f64.cos());
+    assert!(kahan[(0, 0)] == F64::ONE && kahan[(1, 0)] == F64::ZERO);
+    assert!((kahan[(2, 4)] - F64::from(-c * s * s)).abs() < 1e-15.into());
+    assert!((kahan[(4, 4)] - F64::from(s.powi(4))).abs() < 1e-15.into());
+}
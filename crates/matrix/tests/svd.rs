@@ -0,0 +1,167 @@
+use floating_point::F64;
+use matrix::{FullMat, Matrix, SvdMode};
+use rand::prelude::*;
+
+fn mat_max_diff(a: &FullMat<F64>, b: &FullMat<F64>) -> F64 {
+    assert!(a.shape() == b.shape());
+    let mut max = F64::ZERO;
+    for i in 0..a.row_count() {
+        for j in 0..a.col_count() {
+            max = max.max((a[(i, j)] - b[(i, j)]).abs());
+        }
+    }
+    max
+}
+
+fn random_mat(m: usize, n: usize) -> FullMat<F64> {
+    let mut rng = rand::thread_rng();
+    FullMat::from_vec(
+        n,
+        (0..m * n)
+            .map(|_| F64::from(rng.gen::<f64>() - 0.5))
+            .collect(),
+    )
+}
+
+fn diag(m: usize, n: usize, s: &[F64]) -> FullMat<F64> {
+    let mut mat = FullMat::zeros(m, n);
+    for (i, x) in s.iter().enumerate() {
+        mat[(i, i)] = *x;
+    }
+    mat
+}
+
+fn assert_orthonormal_cols(q: &FullMat<F64>) {
+    let n = q.col_count();
+    assert!(mat_max_diff(&q.transpose().mul_mat(q), &FullMat::identity(n)) < 1e-13.into());
+}
+
+#[test]
+fn test_thin_and_full_svd() {
+    for (m, n) in [(7, 4), (4, 7), (5, 5), (1, 3), (3, 1)] {
+        let mat = random_mat(m, n);
+        let k = m.min(n);
+
+        let thin = mat.svd_with(SvdMode::Thin).unwrap();
+        assert!(thin.u.shape() == (m, k));
+        assert!(thin.v.shape() == (n, k));
+        assert_orthonormal_cols(&thin.u);
+        assert_orthonormal_cols(&thin.v);
+        let usv = thin
+            .u
+            .mul_mat(&diag(k, k, &thin.singular_values))
+            .mul_mat(&thin.v.transpose());
+        assert!(mat_max_diff(&usv, &mat) < 1e-13.into());
+        for w in thin.singular_values.windows(2) {
+            assert!(w[0] >= w[1]);
+        }
+
+        let full = mat.svd_with(SvdMode::Full).unwrap();
+        assert!(full.u.shape() == (m, m));
+        assert!(full.v.shape() == (n, n));
+        assert_orthonormal_cols(&full.u);
+        assert_orthonormal_cols(&full.v);
+        let usv = full
+            .u
+            .mul_mat(&diag(m, n, &full.singular_values))
+            .mul_mat(&full.v.transpose());
+        assert!(mat_max_diff(&usv, &mat) < 1e-13.into());
+
+        let values = mat.singular_values().unwrap();
+        for (x, y) in values.iter().zip(thin.singular_values.iter()) {
+            assert!((*x - *y).abs() < 1e-14.into());
+        }
+    }
+}
+
+#[test]
+fn test_pinv() {
+    let mat = random_mat(6, 3);
+    let pinv = mat.pinv().unwrap();
+    assert!(pinv.shape() == (3, 6));
+    assert!(mat_max_diff(&pinv.mul_mat(&mat), &FullMat::identity(3)) < 1e-13.into());
+
+    // Penrose conditions on a rank-deficient matrix
+    let mat = FullMat::from_vec(3, F64::map_vec(vec![1., 2., 3., 2., 4., 6., 1., 0., 1.]));
+    let pinv = mat.pinv().unwrap();
+    assert!(mat_max_diff(&mat.mul_mat(&pinv).mul_mat(&mat), &mat) < 1e-13.into());
+    assert!(mat_max_diff(&pinv.mul_mat(&mat).mul_mat(&pinv), &pinv) < 1e-13.into());
+}
+
+#[test]
+fn test_rank_norm_and_condition_number() {
+    let mat = FullMat::from_vec(3, F64::map_vec(vec![1., 2., 3., 2., 4., 6., 1., 0., 1.]));
+    assert!(mat.rank().unwrap() == 2);
+    assert!(mat.cond2().unwrap() > 1e14.into());
+
+    let mat = FullMat::from_vec(2, F64::map_vec(vec![3., 0., 0., -4.]));
+    assert!(mat.rank().unwrap() == 2);
+    assert!(mat.norm2().unwrap() == 4.0.into());
+    assert!((mat.cond2().unwrap() - F64::from(4.0 / 3.0)).abs() < 1e-15.into());
+
+    let zero = FullMat::zeros(2, 3);
+    assert!(zero.rank().unwrap() == 0);
+    assert!(zero.cond2().unwrap() == f64::INFINITY.into());
+}
+
+#[test]
+fn test_null_space_and_range() {
+    let mat = FullMat::from_vec(3, F64::map_vec(vec![1., 2., 3., 2., 4., 6.]));
+    let null = mat.null_space().unwrap();
+    assert!(null.len() == 2);
+    for v in &null {
+        let y = mat.mul_vec(v);
+        assert!(y.iter().all(|x| x.abs() < 1e-14.into()));
+    }
+
+    let range = mat.range().unwrap();
+    assert!(range.len() == 1);
+    let scale = F64::from(5.0).sqrt();
+    assert!((range[0][0].abs() - F64::ONE / scale).abs() < 1e-15.into());
+    assert!((range[0][1].abs() - F64::from(2.0) / scale).abs() < 1e-15.into());
+
+    let full_rank = random_mat(3, 5);
+    assert!(full_rank.null_space().unwrap().len() == 2);
+    assert!(full_rank.transpose().null_space().unwrap().is_empty());
+}
+
+#[test]
+fn test_low_rank_approx() {
+    let mat = random_mat(6, 5);
+    let s = mat.singular_values().unwrap();
+
+    let approx = mat.low_rank_approx(2).unwrap();
+    assert!(approx.rank().unwrap() == 2);
+    // the 2-norm error is the first dropped singular value
+    let err = mat.sub(&approx).norm2().unwrap();
+    assert!((err - s[2]).abs() < 1e-14.into());
+
+    let exact = mat.low_rank_approx(5).unwrap();
+    assert!(mat_max_diff(&exact, &mat) < 1e-14.into());
+}
+
+#[test]
+fn test_full_svd_of_tall_matrix() {
+    // U is completed from a QR of the thin U, so it stays cheap when m >> n
+    let (m, n) = (300, 4);
+    let mat = random_mat(m, n);
+    let full = mat.svd_with(SvdMode::Full).unwrap();
+    assert!(full.u.shape() == (m, m));
+    assert_orthonormal_cols(&full.u);
+    let usv = full
+        .u
+        .mul_mat(&diag(m, n, &full.singular_values))
+        .mul_mat(&full.v.transpose());
+    assert!(mat_max_diff(&usv, &mat) < 1e-13.into());
+}
+
+#[test]
+fn test_non_finite() {
+    for x in [f64::NAN, f64::INFINITY] {
+        let mut mat = random_mat(4, 3);
+        mat[(2, 1)] = x.into();
+        assert!(mat.svd().is_none() && mat.svd_with(SvdMode::Full).is_none());
+        assert!(mat.singular_values().is_none());
+        assert!(mat.pinv().is_none() && mat.rank().is_none());
+    }
+}
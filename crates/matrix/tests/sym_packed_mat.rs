@@ -0,0 +1,223 @@
+use floating_point::{Complex64, F64};
+use matrix::{FullMat, HermPackedMat, Matrix, MatrixError, SymPackedMat, TriangleMatType};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const TYPES: [TriangleMatType; 2] = [TriangleMatType::Upper, TriangleMatType::Lower];
+
+fn random_symmetric(rng: &mut StdRng, n: usize) -> FullMat<F64> {
+    let mut mat = FullMat::zeros(n, n);
+    for i in 0..n {
+        for j in 0..=i {
+            let x = F64::from(rng.gen_range(-1.0..1.0));
+            mat[(i, j)] = x;
+            mat[(j, i)] = x;
+        }
+    }
+    mat
+}
+
+fn random_vec(rng: &mut StdRng, n: usize) -> Vec<F64> {
+    (0..n).map(|_| rng.gen_range(-1.0..1.0).into()).collect()
+}
+
+fn max_diff(a: &[F64], b: &[F64]) -> F64 {
+    a.iter()
+        .zip(b)
+        .fold(F64::ZERO, |max, (x, y)| max.max((*x - *y).abs()))
+}
+
+#[test]
+fn test_storage_and_index() {
+    // [[1, 2, 3], [2, 4, 5], [3, 5, 6]]
+    let upper = SymPackedMat::from_rows(
+        TriangleMatType::Upper,
+        vec![
+            F64::map_vec(vec![1.0, 2.0, 3.0]),
+            F64::map_vec(vec![4.0, 5.0]),
+            F64::map_vec(vec![6.0]),
+        ],
+    );
+    let lower = SymPackedMat::from_vec(
+        TriangleMatType::Lower,
+        F64::map_vec(vec![1.0, 2.0, 4.0, 3.0, 5.0, 6.0]),
+    );
+    println!("upper = \n{}", upper);
+    assert!(upper.shape() == (3, 3) && upper.storage().len() == 6);
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!(upper[(i, j)] == upper[(j, i)] && upper[(i, j)] == lower[(i, j)]);
+        }
+    }
+    assert!(upper.to_other_triangle().storage() == lower.storage());
+
+    // one write changes both mirror images
+    let mut mat = lower.clone();
+    mat[(0, 2)] = F64::from(-3.0);
+    assert!(mat[(2, 0)] == F64::from(-3.0) && mat.storage()[3] == F64::from(-3.0));
+
+    let full = FullMat::from(&upper);
+    assert!(full[(2, 1)] == F64::from(5.0) && full.shape() == (3, 3));
+    // only the chosen triangle of the full matrix is read
+    let mut skewed = full.clone();
+    skewed[(0, 1)] = F64::from(100.0);
+    let read = SymPackedMat::from_full(TriangleMatType::Lower, &skewed);
+    assert!(read.storage() == lower.storage());
+
+    assert!(
+        SymPackedMat::<F64>::try_from_vec(TriangleMatType::Upper, vec![F64::ONE; 4]).unwrap_err()
+            == MatrixError::NonTriangularLength(4)
+    );
+}
+
+#[test]
+fn test_mul_vec_and_updates() {
+    let mut rng = StdRng::seed_from_u64(48);
+    let n = 9;
+    let full = random_symmetric(&mut rng, n);
+    let (x, y) = (random_vec(&mut rng, n), random_vec(&mut rng, n));
+    let alpha = F64::from(0.75);
+
+    let mut expected = full.clone();
+    for i in 0..n {
+        for j in 0..n {
+            expected[(i, j)] += alpha * x[i] * x[j] - alpha * (x[i] * y[j] + y[i] * x[j]);
+        }
+    }
+    for ty in TYPES {
+        let mut packed = SymPackedMat::from_full(ty, &full);
+        assert!(max_diff(&packed.mul_vec(&x), &full.mul_vec(&x)) <= F64::from(16.0) * F64::EPSILON);
+
+        packed.rank1_update(alpha, &x);
+        packed.rank2_update(-alpha, &x, &y);
+        let updated = FullMat::from(&packed);
+        assert!(updated.sub(&expected).element_max_abs() <= F64::from(8.0) * F64::EPSILON);
+    }
+}
+
+#[test]
+fn test_cholesky() {
+    let pascal = FullMat::pascal(6);
+    let expected = FullMat::from(pascal.cholesky().unwrap());
+    let b = F64::map_vec(vec![1.0, -1.0, 2.0, 0.5, 3.0, -2.0]);
+    for ty in TYPES {
+        let packed = SymPackedMat::from_full(ty, &pascal);
+        // the same operations in the same order as the full version
+        let l = packed.cholesky().unwrap();
+        assert!(FullMat::from(l).sub(&expected).element_max_abs() == F64::ZERO);
+        let x = packed.cholesky_solve(&b).unwrap();
+        assert!(max_diff(&pascal.mul_vec(&x), &b) <= F64::from(1e-9));
+    }
+
+    let indefinite =
+        SymPackedMat::from_vec(TriangleMatType::Lower, F64::map_vec(vec![1.0, 2.0, 1.0]));
+    assert!(indefinite.into_cholesky().is_none());
+}
+
+#[test]
+fn test_symmetric_eigen() {
+    let mut rng = StdRng::seed_from_u64(480);
+    for n in [1, 2, 3, 10, 40] {
+        let full = random_symmetric(&mut rng, n);
+        let reference = full.symmetric_eigenvalues().unwrap();
+        for ty in TYPES {
+            let packed = SymPackedMat::from_full(ty, &full);
+            let values = packed.symmetric_eigenvalues().unwrap();
+            println!("n = {}: {:?}", n, values);
+            assert!(max_diff(&values, &reference) <= F64::from(1e-13));
+
+            let eigen = packed.symmetric_eigen().unwrap();
+            assert!(eigen.eigenvalues == values);
+            for (lambda, v) in eigen.eigenvalues.iter().zip(&eigen.eigenvectors) {
+                let lambda_v: Vec<F64> = v.iter().map(|x| *lambda * *x).collect();
+                assert!(max_diff(&packed.mul_vec(v), &lambda_v) <= F64::from(1e-13));
+                assert!((matrix::vector_norm2(v) - F64::ONE).abs() <= F64::from(1e-14));
+            }
+        }
+    }
+
+    // the second difference matrix has eigenvalues 2 - 2 cos(k pi / (n + 1))
+    let n = 20;
+    let mut storage = vec![];
+    for i in 0..n {
+        storage.push(F64::from(2.0));
+        if i + 1 < n {
+            storage.push(F64::from(-1.0));
+            storage.extend(vec![F64::ZERO; n - i - 2]);
+        }
+    }
+    let values = SymPackedMat::from_vec(TriangleMatType::Upper, storage)
+        .symmetric_eigenvalues()
+        .unwrap();
+    for (k, lambda) in values.iter().enumerate() {
+        let exact = 2.0 - 2.0 * ((k + 1) as f64 * std::f64::consts::PI / (n + 1) as f64).cos();
+        assert!((*lambda - F64::from(exact)).abs() <= F64::from(1e-14));
+    }
+}
+
+#[test]
+fn test_hermitian() {
+    // [[2, 1 - i], [1 + i, 3]]
+    let mut a = HermPackedMat::from_vec(
+        TriangleMatType::Upper,
+        vec![
+            Complex64::new(2.0, 0.0),
+            Complex64::new(1.0, -1.0),
+            Complex64::new(3.0, 0.0),
+        ],
+    );
+    assert!(a.order() == 2);
+    assert!(a.get((1, 0)) == Complex64::new(1.0, 1.0));
+    let full = FullMat::from(&a);
+    println!("a = \n{}", a);
+    assert!(full[(0, 1)] == Complex64::new(1.0, -1.0) && full[(1, 0)] == Complex64::new(1.0, 1.0));
+    let lower = HermPackedMat::from_full(TriangleMatType::Lower, &full);
+    assert!(lower.storage()[1] == Complex64::new(1.0, 1.0));
+
+    let x = vec![Complex64::new(1.0, 2.0), Complex64::new(-1.0, 0.5)];
+    let ax = a.mul_vec(&x);
+    for (i, ax_i) in ax.iter().enumerate() {
+        let expected = (0..2).fold(Complex64::new(0.0, 0.0), |sum, j| sum + full[(i, j)] * x[j]);
+        assert!(*ax_i == expected && lower.mul_vec(&x)[i] == expected);
+    }
+
+    // A + 2 x x^H - (y x^H + x y^H) stays Hermitian with a real diagonal
+    let y = vec![Complex64::new(0.5, -1.0), Complex64::new(2.0, 1.0)];
+    a.rank1_update(F64::from(2.0), &x);
+    a.rank2_update(Complex64::new(-1.0, 0.0), &x, &y);
+    for i in 0..2 {
+        for j in 0..2 {
+            let expected = full[(i, j)] + Complex64::new(2.0, 0.0) * x[i] * x[j].conjugate()
+                - x[i] * y[j].conjugate()
+                - y[i] * x[j].conjugate();
+            assert!((a.get((i, j)) - expected).abs() <= F64::from(1e-14));
+        }
+        assert!(a.get((i, i)).imag == F64::ZERO);
+    }
+    a.set((1, 0), Complex64::new(0.0, 1.0));
+    assert!(a.get((0, 1)) == Complex64::new(0.0, -1.0));
+}
+
+#[test]
+fn test_hermitian_updates_with_complex_alpha() {
+    let mut rng = StdRng::seed_from_u64(4800);
+    let n = 12;
+    let mut random_complex = || Complex64::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+    let x: Vec<Complex64> = (0..n).map(|_| random_complex()).collect();
+    let y: Vec<Complex64> = (0..n).map(|_| random_complex()).collect();
+    let alpha = random_complex();
+
+    for ty in TYPES {
+        let mut a = HermPackedMat::from_vec(ty, vec![Complex64::new(0.0, 0.0); n * (n + 1) / 2]);
+        a.rank2_update(alpha, &x, &y);
+        a.rank1_update(F64::from(0.3), &y);
+        for i in 0..n {
+            assert!(a.get((i, i)).imag == F64::ZERO);
+            for j in 0..n {
+                let expected = alpha * x[i] * y[j].conjugate()
+                    + alpha.conjugate() * y[i] * x[j].conjugate()
+                    + Complex64::new(0.3, 0.0) * y[i] * y[j].conjugate();
+                assert!((a.get((i, j)) - expected).abs() <= F64::from(1e-15));
+            }
+        }
+    }
+}
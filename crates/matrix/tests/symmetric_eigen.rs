@@ -0,0 +1,143 @@
+use floating_point::F64;
+use matrix::{EigenSubset, FullMat, SymmetricEigenMethod};
+use rand::prelude::*;
+
+const METHODS: [SymmetricEigenMethod; 2] = [
+    SymmetricEigenMethod::TridiagonalQL,
+    SymmetricEigenMethod::Jacobi,
+];
+
+fn random_symmetric_mat(n: usize) -> FullMat<F64> {
+    let mut rng = rand::thread_rng();
+    let mut mat = FullMat::zeros(n, n);
+    for i in 0..n {
+        for j in 0..=i {
+            let x = F64::from(rng.gen::<f64>() - 0.5);
+            mat[(i, j)] = x;
+            mat[(j, i)] = x;
+        }
+    }
+    mat
+}
+
+fn second_difference_mat(n: usize) -> FullMat<F64> {
+    let mut mat = FullMat::zeros(n, n);
+    for i in 0..n {
+        mat[(i, i)] = 2.0.into();
+        if i > 0 {
+            mat[(i, i - 1)] = (-1.0).into();
+            mat[(i - 1, i)] = (-1.0).into();
+        }
+    }
+    mat
+}
+
+#[test]
+fn test_eigen_pairs() {
+    for n in [1, 2, 5, 12] {
+        let mat = random_symmetric_mat(n);
+        for method in METHODS {
+            let eigen = mat.symmetric_eigen_with(method, EigenSubset::All).unwrap();
+            assert!(eigen.eigenvalues.len() == n);
+            for w in eigen.eigenvalues.windows(2) {
+                assert!(w[0] <= w[1]);
+            }
+
+            for (lambda, v) in eigen.eigenvalues.iter().zip(eigen.eigenvectors.iter()) {
+                let av = mat.mul_vec(v);
+                for (x, y) in av.iter().zip(v.iter()) {
+                    assert!((*x - *lambda * *y).abs() < 1e-14.into());
+                }
+            }
+
+            for (i, u) in eigen.eigenvectors.iter().enumerate() {
+                for (j, v) in eigen.eigenvectors.iter().enumerate() {
+                    let d: F64 = u.iter().zip(v.iter()).map(|(x, y)| x * y).sum();
+                    let expected = if i == j { F64::ONE } else { F64::ZERO };
+                    assert!((d - expected).abs() < 1e-14.into());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_known_eigenvalues() {
+    // eigenvalues of the second difference matrix are 2 - 2 cos(k pi / (n + 1))
+    let n = 10;
+    let mat = second_difference_mat(n);
+    for method in METHODS {
+        let eigen = mat.symmetric_eigen_with(method, EigenSubset::All).unwrap();
+        for (k, lambda) in eigen.eigenvalues.iter().enumerate() {
+            let expected =
+                2.0 - 2.0 * ((k + 1) as f64 * core::f64::consts::PI / (n + 1) as f64).cos();
+            assert!((*lambda - F64::from(expected)).abs() < 1e-14.into());
+        }
+    }
+}
+
+#[test]
+fn test_only_lower_triangle_is_used() {
+    let mut mat = random_symmetric_mat(4);
+    let expected = mat.symmetric_eigenvalues().unwrap();
+    mat[(0, 3)] = 100.0.into();
+    mat[(1, 2)] = (-7.0).into();
+    let values = mat.symmetric_eigenvalues().unwrap();
+    for (x, y) in values.iter().zip(expected.iter()) {
+        assert!((*x - *y).abs() < 1e-15.into());
+    }
+}
+
+#[test]
+fn test_jacobi_relative_accuracy() {
+    // D H D with D = diag(1e20, 1e10, 1) and H = 0.9 I + 0.1 ones, so the
+    // eigenvalues span 40 orders of magnitude; the reference values were
+    // computed in 200-digit arithmetic
+    let mat = FullMat::from_vec(
+        3,
+        F64::map_vec(vec![1e40, 1e29, 1e19, 1e29, 1e20, 1e9, 1e19, 1e9, 1.0]),
+    );
+    let expected = [9.818181818181818e-1, 9.9e19, 1e40];
+    let eigen = mat
+        .symmetric_eigen_with(SymmetricEigenMethod::Jacobi, EigenSubset::All)
+        .unwrap();
+    println!("eigenvalues = {:?}", eigen.eigenvalues);
+
+    for (x, y) in eigen.eigenvalues.iter().zip(expected) {
+        let y = F64::from(y);
+        assert!(((*x - y) / y).abs() < 1e-12.into());
+    }
+}
+
+#[test]
+fn test_subsets() {
+    let n = 8;
+    let mat = second_difference_mat(n);
+    let all = mat.symmetric_eigenvalues().unwrap();
+
+    for method in METHODS {
+        let eigen = mat
+            .symmetric_eigen_with(method, EigenSubset::Index(2..5))
+            .unwrap();
+        assert!(eigen.eigenvalues.len() == 3);
+        assert!(eigen.eigenvectors.len() == 3);
+        for (x, y) in eigen.eigenvalues.iter().zip(all[2..5].iter()) {
+            assert!((*x - *y).abs() < 1e-14.into());
+        }
+
+        let eigen = mat
+            .symmetric_eigen_with(method, EigenSubset::Interval(0.5.into(), 2.5.into()))
+            .unwrap();
+        let expected: Vec<F64> = all
+            .iter()
+            .copied()
+            .filter(|x| *x > 0.5.into() && *x <= 2.5.into())
+            .collect();
+        assert!(eigen.eigenvalues.len() == expected.len());
+
+        let eigen = mat
+            .symmetric_eigen_with(method, EigenSubset::Interval(10.0.into(), 20.0.into()))
+            .unwrap();
+        assert!(eigen.eigenvalues.is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use floating_point::{Complex64, F64};
+use matrix::io::text::{TextError, TextOptions};
+use matrix::{FullMat, Matrix};
+
+fn fixture(name: &str) -> BufReader<File> {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", name]
+        .iter()
+        .collect();
+    BufReader::new(File::open(path).unwrap())
+}
+
+fn same_bits(a: &FullMat<F64>, b: &FullMat<F64>) -> bool {
+    let (m, n) = a.shape();
+    a.shape() == b.shape()
+        && (0..m)
+            .all(|i| (0..n).all(|j| a[(i, j)].to_f64().to_bits() == b[(i, j)].to_f64().to_bits()))
+}
+
+fn parse_error(text: &str, options: &TextOptions) -> (usize, String) {
+    match FullMat::<Complex64>::from_csv_reader(text.as_bytes(), options) {
+        Err(TextError::Parse(line, message)) => (line, message),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_read_csv() {
+    let options = TextOptions {
+        skip_rows: 1,
+        ..TextOptions::default()
+    };
+    let mat = FullMat::<F64>::from_csv_reader(fixture("samples.csv"), &options).unwrap();
+    println!("samples = \n{}", mat);
+    assert!(mat.shape() == (3, 3));
+    assert!(mat.col(0) == F64::map_vec(vec![0.0, 0.5, 1.0]));
+    assert!(mat[(1, 1)] == 1.5e-3.into());
+    assert!(!mat[(1, 2)].is_finite() && mat[(1, 2)] != mat[(1, 2)]);
+    assert!(mat[(2, 1)] == f64::NEG_INFINITY.into());
+
+    // the header is not a number
+    let error = FullMat::<F64>::from_csv_reader(fixture("samples.csv"), &TextOptions::default())
+        .unwrap_err();
+    assert!(error.to_string() == "line 1: expected a number, found 'time'");
+
+    let mut written = vec![];
+    mat.to_csv_writer(&mut written, &TextOptions::default())
+        .unwrap();
+    let text = String::from_utf8(written.clone()).unwrap();
+    assert!(text == "0e0,1.25e0,-5e-1\n5e-1,1.5e-3,nan\n1e0,-inf,2e0\n");
+    let read =
+        FullMat::<F64>::from_csv_reader(written.as_slice(), &TextOptions::default()).unwrap();
+    assert!(same_bits(&read, &mat));
+}
+
+#[test]
+fn test_nan_and_inf_tokens() {
+    // missing values as empty cells, the way pandas writes them
+    let options = TextOptions {
+        delimiter: Some(';'),
+        comment: None,
+        nan_token: "".to_string(),
+        inf_token: "Infinity".to_string(),
+        ..TextOptions::default()
+    };
+    let mat =
+        FullMat::<F64>::from_csv_reader("1;;-INFINITY\n;NaN;inf\n".as_bytes(), &options).unwrap();
+    assert!(mat.shape() == (2, 3));
+    assert!(mat[(0, 1)] != mat[(0, 1)] && mat[(1, 0)] != mat[(1, 0)] && mat[(1, 1)] != mat[(1, 1)]);
+    assert!(mat[(0, 2)] == f64::NEG_INFINITY.into() && mat[(1, 2)] == f64::INFINITY.into());
+
+    let mut written = vec![];
+    mat.to_csv_writer(&mut written, &options).unwrap();
+    assert!(String::from_utf8(written).unwrap() == "1e0;;-Infinity\n;;Infinity\n");
+}
+
+#[test]
+fn test_whitespace() {
+    let text = "  1   2.5\t-3\n\n# comment\n4 5 6  \n";
+    let mat = FullMat::<F64>::from_whitespace_reader(text.as_bytes()).unwrap();
+    assert!(mat.shape() == (2, 3));
+    assert!(mat[(0, 1)] == 2.5.into() && mat[(1, 2)] == 6.0.into());
+
+    let mut written = vec![];
+    mat.to_whitespace_writer(&mut written).unwrap();
+    assert!(String::from_utf8(written.clone()).unwrap() == "1e0 2.5e0 -3e0\n4e0 5e0 6e0\n");
+    assert!(same_bits(
+        &FullMat::<F64>::from_whitespace_reader(written.as_slice()).unwrap(),
+        &mat
+    ));
+}
+
+#[test]
+fn test_complex() {
+    let mat = FullMat::<Complex64>::from_whitespace_reader(fixture("impedance.txt")).unwrap();
+    println!("impedance = \n{}", mat);
+    assert!(mat.shape() == (3, 3));
+    assert!(mat[(1, 0)] == Complex64::new(1e3, 0.0));
+    assert!(mat[(1, 1)] == Complex64::new(49.5, 25.0));
+    assert!((0..3).all(|i| mat[(i, 2)] == mat[(i, 1)].conjugate()));
+
+    // the spaced syntax of Complex64 in a csv file, exponents included
+    let text = "1e0 + 2e0 I, -3.5 - 1e-3 I\n-2 I, 4e+1-5E-1i\n";
+    let csv =
+        FullMat::<Complex64>::from_csv_reader(text.as_bytes(), &TextOptions::default()).unwrap();
+    assert!(csv[(0, 0)] == Complex64::new(1.0, 2.0));
+    assert!(csv[(0, 1)] == Complex64::new(-3.5, -1e-3));
+    assert!(csv[(1, 0)] == Complex64::new(0.0, -2.0));
+    assert!(csv[(1, 1)] == Complex64::new(40.0, -0.5));
+
+    let mut written = vec![];
+    csv.to_csv_writer(&mut written, &TextOptions::default())
+        .unwrap();
+    let text = String::from_utf8(written.clone()).unwrap();
+    assert!(text.starts_with("1e0 + 2e0 I,-3.5e0 - 1e-3 I\n"));
+    let read =
+        FullMat::<Complex64>::from_csv_reader(written.as_slice(), &TextOptions::default()).unwrap();
+    assert!((0..2).all(|i| (0..2).all(|j| read[(i, j)] == csv[(i, j)])));
+
+    // no spaces inside the cells when they separate the columns
+    let special = FullMat::from_vec(
+        2,
+        vec![
+            Complex64::new(f64::INFINITY, -0.0),
+            Complex64::new(f64::NAN, -1.0e300),
+        ],
+    );
+    let mut written = vec![];
+    special.to_whitespace_writer(&mut written).unwrap();
+    let text = String::from_utf8(written.clone()).unwrap();
+    assert!(text == "inf-0e0I nan-1e300I\n");
+    let read = FullMat::<Complex64>::from_whitespace_reader(written.as_slice()).unwrap();
+    assert!(read[(0, 0)].real == f64::INFINITY.into());
+    assert!(read[(0, 0)].imag.to_f64().to_bits() == (-0.0f64).to_bits());
+    assert!(read[(0, 1)].real != read[(0, 1)].real && read[(0, 1)].imag == (-1.0e300).into());
+}
+
+#[test]
+fn test_parse_errors() {
+    let csv = TextOptions::default();
+    let (line, message) = parse_error("1,2\n# skipped\n3,4,5\n", &csv);
+    assert!(line == 3 && message == "expected 2 columns, found 3");
+    let (line, message) = parse_error("1,2\n3,4 + x I\n", &csv);
+    assert!(line == 2 && message == "expected a complex number, found '4 + x I'");
+    let (line, message) = parse_error("1,2,\n", &csv);
+    assert!(line == 1 && message.contains("''"));
+    let (line, message) = parse_error("# only a comment\n\n", &csv);
+    assert!(line == 2 && message == "no data");
+    let (line, message) = parse_error("", &csv);
+    assert!(line == 1 && message == "no data");
+
+    // a whitespace-delimited file needs the compact syntax
+    let (line, message) = parse_error("1 + 2 I\n", &TextOptions::whitespace());
+    assert!(line == 1 && message.contains("'+'"));
+
+    let skip_all = TextOptions {
+        skip_rows: 3,
+        ..TextOptions::default()
+    };
+    let (line, message) = parse_error("a\nb\nc\n", &skip_all);
+    assert!(line == 3 && message == "no data");
+}